@@ -0,0 +1,15 @@
+ALTER TABLE asset_items DROP COLUMN purchase_date;
+
+ALTER TABLE asset_types DROP COLUMN full_search;
+ALTER TABLE asset_types DROP COLUMN useful_life_years;
+ALTER TABLE asset_types DROP CONSTRAINT asset_types_cost_non_negative;
+ALTER TABLE asset_types ALTER COLUMN cost TYPE TEXT USING cost::TEXT;
+
+ALTER TABLE asset_types
+ADD full_search tsvector
+GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', coalesce(brand, '')), 'A') || ' ' || 
+    setweight(to_tsvector('simple', coalesce(model, '')), 'B') || ' ' || 
+    setweight(to_tsvector('english', coalesce(description, '')), 'C') || ' ' || 
+	setweight(to_tsvector('simple', coalesce(cost, '')), 'D') :: tsvector
+) STORED;
@@ -0,0 +1,32 @@
+-- full_search is generated from cost, so it has to be dropped before the type change
+ALTER TABLE asset_types DROP COLUMN full_search;
+
+-- costs were free text, anything that isn't a plain non negative amount once the currency symbols
+-- and separators are gone ("1.2.3", "5-10", "-5", too large for the column) is cleared rather than
+-- failing the migration. CASE keeps the casts from running on values that would not parse.
+ALTER TABLE asset_types
+ALTER COLUMN cost TYPE NUMERIC(12, 2)
+USING CASE
+    WHEN regexp_replace(cost, '[^0-9.\-]', '', 'g') ~ '^([0-9]{1,10}(\.[0-9]*)?|\.[0-9]+)$' THEN
+        CASE
+            WHEN round(regexp_replace(cost, '[^0-9.\-]', '', 'g')::NUMERIC, 2) < 10000000000
+            THEN regexp_replace(cost, '[^0-9.\-]', '', 'g')::NUMERIC(12, 2)
+        END
+END;
+
+ALTER TABLE asset_types
+ADD CONSTRAINT asset_types_cost_non_negative CHECK (cost >= 0);
+
+ALTER TABLE asset_types
+ADD useful_life_years INTEGER CHECK (useful_life_years > 0);
+
+ALTER TABLE asset_types
+ADD full_search tsvector
+GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', coalesce(brand, '')), 'A') || ' ' || 
+    setweight(to_tsvector('simple', coalesce(model, '')), 'B') || ' ' || 
+    setweight(to_tsvector('english', coalesce(description, '')), 'C') :: tsvector
+) STORED;
+
+ALTER TABLE asset_items
+ADD purchase_date DATE;
@@ -0,0 +1,16 @@
+ALTER TABLE asset_items
+ADD brand TEXT,
+ADD model TEXT;
+
+UPDATE asset_items AS ai
+SET brand = at.brand, model = at.model
+FROM asset_types AS at
+WHERE ai.asset_type_id = at.id;
+
+DROP INDEX IF EXISTS idx_asset_items_asset_type_id;
+ALTER TABLE asset_items DROP CONSTRAINT asset_items_asset_type_id_fkey;
+ALTER TABLE asset_items DROP COLUMN asset_type_id;
+
+ALTER TABLE asset_items
+ADD CONSTRAINT asset_items_brand_model_fkey
+FOREIGN KEY (brand, model) REFERENCES asset_types (brand, model);
@@ -0,0 +1,40 @@
+ALTER TABLE asset_items
+ADD asset_type_id INTEGER;
+
+-- backfill from the old (brand, model) reference
+UPDATE asset_items AS ai
+SET asset_type_id = at.id
+FROM asset_types AS at
+WHERE ai.brand = at.brand AND ai.model = at.model;
+
+-- the old foreign key only checked rows with both brand and model set, anything else would lose its brand and model here
+DO $$
+DECLARE
+    unmatched INTEGER;
+    untyped INTEGER;
+BEGIN
+    SELECT count(*) INTO unmatched
+    FROM asset_items
+    WHERE asset_type_id IS NULL AND (brand IS NOT NULL OR model IS NOT NULL);
+
+    IF unmatched > 0 THEN
+        RAISE EXCEPTION '% asset items have a brand or model that matches no asset type, fix or clear them before migrating', unmatched;
+    END IF;
+
+    SELECT count(*) INTO untyped FROM asset_items WHERE asset_type_id IS NULL;
+
+    IF untyped > 0 THEN
+        RAISE NOTICE '% asset items had no brand and model and are left without an asset type', untyped;
+    END IF;
+END $$;
+
+ALTER TABLE asset_items DROP CONSTRAINT asset_items_brand_model_fkey;
+ALTER TABLE asset_items DROP COLUMN brand;
+ALTER TABLE asset_items DROP COLUMN model;
+
+-- types that still have items cannot be deleted, items must be reassigned first
+ALTER TABLE asset_items
+ADD CONSTRAINT asset_items_asset_type_id_fkey
+FOREIGN KEY (asset_type_id) REFERENCES asset_types (id) ON DELETE RESTRICT;
+
+CREATE INDEX idx_asset_items_asset_type_id ON asset_items (asset_type_id);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_asset_items_serial_number_lookup;
+DROP INDEX IF EXISTS idx_asset_items_asset_id_lookup;
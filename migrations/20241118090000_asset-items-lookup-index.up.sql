@@ -0,0 +1,6 @@
+-- scan lookups compare codes uppercased with whitespace and leading zeros removed
+CREATE INDEX idx_asset_items_asset_id_lookup
+    ON asset_items (LTRIM(UPPER(REGEXP_REPLACE(asset_id, '\s', '', 'g')), '0'));
+
+CREATE INDEX idx_asset_items_serial_number_lookup
+    ON asset_items (LTRIM(UPPER(REGEXP_REPLACE(serial_number, '\s', '', 'g')), '0'));
@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS inventory_audit_results;
+DROP TABLE IF EXISTS inventory_audit_scans;
+DROP TABLE IF EXISTS inventory_audits;
+
+ALTER TABLE asset_items
+DROP CONSTRAINT IF EXISTS asset_items_status_check;
+
+ALTER TABLE asset_items
+DROP COLUMN IF EXISTS status;
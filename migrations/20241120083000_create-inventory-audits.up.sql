@@ -0,0 +1,48 @@
+ALTER TABLE asset_items
+ADD status TEXT NOT NULL DEFAULT 'active';
+
+ALTER TABLE asset_items
+ADD CONSTRAINT asset_items_status_check CHECK (status IN ('active', 'missing', 'retired'));
+
+CREATE TABLE
+    "inventory_audits" (
+        id SERIAL PRIMARY KEY,
+        school TEXT NOT NULL,
+        room TEXT,
+        status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'completed')),
+        created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        completed_at TIMESTAMP
+            WITH TIME ZONE
+    );
+
+CREATE TABLE
+    "inventory_audit_scans" (
+        id SERIAL PRIMARY KEY,
+        audit_id INTEGER NOT NULL REFERENCES inventory_audits (id) ON DELETE CASCADE,
+        code TEXT NOT NULL,
+        asset_item_id INTEGER REFERENCES asset_items (id) ON DELETE SET NULL,
+        scanned_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        -- scanning the same label twice is a no-op
+        UNIQUE (audit_id, code)
+    );
+
+-- snapshot written when an audit is completed so the report does not change as items move
+CREATE TABLE
+    "inventory_audit_results" (
+        id SERIAL PRIMARY KEY,
+        audit_id INTEGER NOT NULL REFERENCES inventory_audits (id) ON DELETE CASCADE,
+        outcome TEXT NOT NULL CHECK (outcome IN ('found', 'missing', 'unexpected', 'unknown')),
+        asset_item_id INTEGER REFERENCES asset_items (id) ON DELETE SET NULL,
+        code TEXT,
+        asset_id TEXT,
+        name TEXT,
+        serial_number TEXT,
+        recorded_school TEXT,
+        recorded_room TEXT
+    );
+
+CREATE INDEX idx_inventory_audit_scans_audit_id ON inventory_audit_scans (audit_id);
+CREATE INDEX idx_inventory_audit_results_audit_id ON inventory_audit_results (audit_id);
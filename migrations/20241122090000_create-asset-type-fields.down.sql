@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_asset_items_custom_fields;
+
+ALTER TABLE asset_items
+DROP COLUMN IF EXISTS custom_fields;
+
+DROP TABLE IF EXISTS asset_type_fields;
@@ -0,0 +1,20 @@
+CREATE TABLE
+    "asset_type_fields" (
+        id SERIAL PRIMARY KEY,
+        asset_type_id INTEGER NOT NULL REFERENCES asset_types (id) ON DELETE CASCADE,
+        -- key used in asset_items.custom_fields and as the csv column header
+        name TEXT NOT NULL,
+        label TEXT NOT NULL,
+        field_type TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'select', 'boolean')),
+        options TEXT[] NOT NULL DEFAULT '{}',
+        required BOOLEAN NOT NULL DEFAULT FALSE,
+        position INTEGER NOT NULL DEFAULT 0,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        UNIQUE (asset_type_id, name)
+    );
+
+ALTER TABLE asset_items
+ADD custom_fields JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_asset_items_custom_fields ON asset_items USING GIN (custom_fields);
@@ -0,0 +1,12 @@
+DROP VIEW IF EXISTS asset_type_effective_fields;
+
+DROP VIEW IF EXISTS category_paths;
+
+DROP TABLE IF EXISTS category_fields;
+
+DROP INDEX IF EXISTS idx_asset_types_category_id;
+
+ALTER TABLE asset_types
+DROP COLUMN IF EXISTS category_id;
+
+DROP TABLE IF EXISTS categories;
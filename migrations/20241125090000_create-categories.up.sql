@@ -0,0 +1,78 @@
+CREATE TABLE
+    "categories" (
+        id SERIAL PRIMARY KEY,
+        parent_id INTEGER REFERENCES categories (id) ON DELETE RESTRICT,
+        name TEXT NOT NULL,
+        -- default for asset types in this category that do not set their own
+        useful_life_years INTEGER CHECK (useful_life_years > 0),
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        CHECK (parent_id <> id)
+    );
+
+-- sibling names are unique, top level categories compare against each other through the COALESCE
+CREATE UNIQUE INDEX idx_categories_parent_name ON categories (COALESCE(parent_id, 0), name);
+
+ALTER TABLE asset_types
+ADD category_id INTEGER REFERENCES categories (id) ON DELETE SET NULL;
+
+CREATE INDEX idx_asset_types_category_id ON asset_types (category_id);
+
+CREATE TABLE
+    "category_fields" (
+        id SERIAL PRIMARY KEY,
+        category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        label TEXT NOT NULL,
+        field_type TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'select', 'boolean')),
+        options TEXT[] NOT NULL DEFAULT '{}',
+        required BOOLEAN NOT NULL DEFAULT FALSE,
+        position INTEGER NOT NULL DEFAULT 0,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        UNIQUE (category_id, name)
+    );
+
+-- every category with its ancestors (root first, itself last) and the useful life it inherits
+CREATE VIEW category_paths AS
+WITH RECURSIVE tree AS (
+    SELECT id, parent_id, name, useful_life_years,
+        useful_life_years AS effective_useful_life_years,
+        ARRAY[name] AS path_names,
+        ARRAY[id] AS ancestor_ids,
+        created_at
+    FROM categories
+    WHERE parent_id IS NULL
+    UNION ALL
+    SELECT c.id, c.parent_id, c.name, c.useful_life_years,
+        COALESCE(c.useful_life_years, t.effective_useful_life_years),
+        t.path_names || c.name,
+        t.ancestor_ids || c.id,
+        c.created_at
+    FROM categories AS c
+    JOIN tree AS t
+        ON c.parent_id = t.id
+)
+SELECT id, parent_id, name, useful_life_years, effective_useful_life_years, path_names, ancestor_ids, created_at
+FROM tree;
+
+-- fields that apply to each asset type: its own plus those of its category and the category's ancestors,
+-- when names collide the definition closest to the asset type wins
+CREATE VIEW asset_type_effective_fields AS
+SELECT id, asset_type_id, category_id, name, label, field_type, options, required, position, created_at, distance
+FROM (
+    SELECT DISTINCT ON (f.asset_type_id, f.name) *
+    FROM (
+        SELECT id, asset_type_id, NULL::INTEGER AS category_id, name, label, field_type, options, required, position, created_at, 0 AS distance
+        FROM asset_type_fields
+        UNION ALL
+        SELECT cf.id, at.id, cf.category_id, cf.name, cf.label, cf.field_type, cf.options, cf.required, cf.position, cf.created_at,
+            CARDINALITY(cp.ancestor_ids) - ARRAY_POSITION(cp.ancestor_ids, cf.category_id) + 1
+        FROM asset_types AS at
+        JOIN category_paths AS cp
+            ON at.category_id = cp.id
+        JOIN category_fields AS cf
+            ON cf.category_id = ANY(cp.ancestor_ids)
+    ) AS f
+    ORDER BY f.asset_type_id, f.name, f.distance
+) AS effective_fields;
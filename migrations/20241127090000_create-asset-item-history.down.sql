@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_asset_item_history_asset_item_id;
+
+DROP TABLE IF EXISTS asset_item_history;
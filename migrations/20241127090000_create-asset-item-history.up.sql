@@ -0,0 +1,14 @@
+-- change log for asset items, there is no foreign key so entries for deleted items stay on record
+CREATE TABLE
+    "asset_item_history" (
+        id SERIAL PRIMARY KEY,
+        asset_item_id INTEGER NOT NULL,
+        action TEXT NOT NULL CHECK (action IN ('updated', 'deleted')),
+        -- [{"field": ..., "from": ..., "to": ...}]
+        changes JSONB NOT NULL DEFAULT '[]',
+        changed_by UUID REFERENCES users (id) ON DELETE SET NULL,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW()
+    );
+
+CREATE INDEX idx_asset_item_history_asset_item_id ON asset_item_history (asset_item_id, created_at);
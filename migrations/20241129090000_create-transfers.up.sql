@@ -0,0 +1,34 @@
+CREATE TABLE
+    "transfers" (
+        id SERIAL PRIMARY KEY,
+        source_school TEXT NOT NULL,
+        destination_school TEXT NOT NULL,
+        -- None leaves the room for the receiving school to fill in later
+        destination_room TEXT,
+        notes TEXT,
+        status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'received', 'cancelled')),
+        created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+        received_by UUID REFERENCES users (id) ON DELETE SET NULL,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        received_at TIMESTAMP
+            WITH TIME ZONE,
+        CHECK (source_school <> destination_school)
+    );
+
+CREATE TABLE
+    "transfer_items" (
+        id SERIAL PRIMARY KEY,
+        transfer_id INTEGER NOT NULL REFERENCES transfers (id) ON DELETE CASCADE,
+        asset_item_id INTEGER NOT NULL REFERENCES asset_items (id) ON DELETE CASCADE,
+        -- location when the transfer was created, kept so the transfer still reads correctly after receipt
+        from_room TEXT,
+        -- set when the receiving school scans the item in
+        scanned_at TIMESTAMP
+            WITH TIME ZONE,
+        UNIQUE (transfer_id, asset_item_id)
+    );
+
+CREATE INDEX idx_transfers_source_school ON transfers (source_school, status);
+CREATE INDEX idx_transfers_destination_school ON transfers (destination_school, status);
+CREATE INDEX idx_transfer_items_asset_item_id ON transfer_items (asset_item_id);
@@ -0,0 +1,10 @@
+DELETE FROM asset_item_history WHERE action IN ('document_added', 'document_removed');
+
+ALTER TABLE asset_item_history
+DROP CONSTRAINT IF EXISTS asset_item_history_action_check;
+
+ALTER TABLE asset_item_history
+ADD CONSTRAINT asset_item_history_action_check CHECK (action IN ('updated', 'deleted'));
+
+DROP TABLE IF EXISTS asset_type_documents;
+DROP TABLE IF EXISTS asset_item_documents;
@@ -0,0 +1,36 @@
+-- documents are shared by hash, so the same manual can be linked to several types without a second copy
+CREATE TABLE
+    "asset_item_documents" (
+        id SERIAL PRIMARY KEY,
+        asset_item_id INTEGER NOT NULL REFERENCES asset_items (id) ON DELETE CASCADE,
+        document_id INTEGER NOT NULL REFERENCES document_attachments (id) ON DELETE RESTRICT,
+        kind TEXT NOT NULL DEFAULT 'other' CHECK (kind IN ('invoice', 'manual', 'warranty', 'damage', 'other')),
+        description TEXT,
+        created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        UNIQUE (asset_item_id, document_id)
+    );
+
+CREATE TABLE
+    "asset_type_documents" (
+        id SERIAL PRIMARY KEY,
+        asset_type_id INTEGER NOT NULL REFERENCES asset_types (id) ON DELETE CASCADE,
+        document_id INTEGER NOT NULL REFERENCES document_attachments (id) ON DELETE RESTRICT,
+        kind TEXT NOT NULL DEFAULT 'other' CHECK (kind IN ('invoice', 'manual', 'warranty', 'damage', 'other')),
+        description TEXT,
+        created_by UUID REFERENCES users (id) ON DELETE SET NULL,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        UNIQUE (asset_type_id, document_id)
+    );
+
+CREATE INDEX idx_asset_item_documents_document_id ON asset_item_documents (document_id);
+CREATE INDEX idx_asset_type_documents_document_id ON asset_type_documents (document_id);
+
+-- attaching and removing documents is recorded in the item history
+ALTER TABLE asset_item_history
+DROP CONSTRAINT IF EXISTS asset_item_history_action_check;
+
+ALTER TABLE asset_item_history
+ADD CONSTRAINT asset_item_history_action_check CHECK (action IN ('updated', 'deleted', 'document_added', 'document_removed'));
@@ -0,0 +1,5 @@
+ALTER TABLE asset_items
+DROP COLUMN IF EXISTS picture;
+
+DROP TABLE IF EXISTS asset_item_images;
+DROP TABLE IF EXISTS asset_type_images;
@@ -0,0 +1,40 @@
+CREATE TABLE
+    "asset_type_images" (
+        id SERIAL PRIMARY KEY,
+        asset_type_id INTEGER NOT NULL REFERENCES asset_types (id) ON DELETE CASCADE,
+        image_id INTEGER NOT NULL REFERENCES image_attachments (id) ON DELETE RESTRICT,
+        position INTEGER NOT NULL DEFAULT 0,
+        is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        UNIQUE (asset_type_id, image_id)
+    );
+
+CREATE TABLE
+    "asset_item_images" (
+        id SERIAL PRIMARY KEY,
+        asset_item_id INTEGER NOT NULL REFERENCES asset_items (id) ON DELETE CASCADE,
+        image_id INTEGER NOT NULL REFERENCES image_attachments (id) ON DELETE RESTRICT,
+        position INTEGER NOT NULL DEFAULT 0,
+        is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        UNIQUE (asset_item_id, image_id)
+    );
+
+-- at most one primary image per gallery
+CREATE UNIQUE INDEX idx_asset_type_images_primary ON asset_type_images (asset_type_id) WHERE is_primary;
+CREATE UNIQUE INDEX idx_asset_item_images_primary ON asset_item_images (asset_item_id) WHERE is_primary;
+CREATE INDEX idx_asset_type_images_image_id ON asset_type_images (image_id);
+CREATE INDEX idx_asset_item_images_image_id ON asset_item_images (image_id);
+
+-- url of the item's primary image, list thumbnails fall back to the type's picture when it is null
+ALTER TABLE asset_items
+ADD picture TEXT;
+
+-- the existing single picture becomes the first image of each type's gallery
+INSERT INTO asset_type_images (asset_type_id, image_id, position, is_primary)
+SELECT t.id, i.id, 0, TRUE
+FROM asset_types AS t
+INNER JOIN image_attachments AS i
+    ON t.picture = i.url;
@@ -0,0 +1,2 @@
+ALTER TABLE image_attachments
+DROP COLUMN IF EXISTS variants;
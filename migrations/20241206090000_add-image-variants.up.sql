@@ -0,0 +1,4 @@
+-- resized webp copies of the image, e.g. [{"size": "thumbnail", "width": 320, "url": "/content/images/<hash>-thumbnail.webp"}]
+-- images uploaded before variants existed keep an empty list and are shown at their original url
+ALTER TABLE image_attachments
+ADD variants JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,12 @@
+DROP VIEW IF EXISTS referenced_document_attachments;
+DROP VIEW IF EXISTS referenced_image_attachments;
+
+DROP INDEX IF EXISTS idx_users_picture;
+DROP INDEX IF EXISTS idx_asset_items_picture;
+DROP INDEX IF EXISTS idx_asset_types_picture;
+
+ALTER TABLE document_attachments
+DROP COLUMN IF EXISTS unreferenced_since;
+
+ALTER TABLE image_attachments
+DROP COLUMN IF EXISTS unreferenced_since;
@@ -0,0 +1,37 @@
+-- set by the attachment gc when nothing references the row, cleared again once something does.
+-- rows and their files are deleted once this is older than the grace period
+ALTER TABLE image_attachments
+ADD unreferenced_since TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE document_attachments
+ADD unreferenced_since TIMESTAMP WITH TIME ZONE;
+
+-- pictures are stored as urls, not ids
+CREATE INDEX idx_asset_types_picture ON asset_types (picture);
+CREATE INDEX idx_asset_items_picture ON asset_items (picture);
+CREATE INDEX idx_users_picture ON users (picture);
+
+-- everything that can point at an attachment, the gc only collects rows missing from these
+CREATE VIEW referenced_image_attachments AS
+SELECT a.id
+FROM image_attachments AS a
+WHERE EXISTS (SELECT 1 FROM asset_type_images AS r WHERE r.image_id = a.id)
+    OR EXISTS (SELECT 1 FROM asset_item_images AS r WHERE r.image_id = a.id)
+    -- pictures hold the url of the image or one of its variants
+    OR EXISTS (
+        SELECT 1
+        FROM (
+            SELECT a.url
+            UNION ALL
+            SELECT v->>'url' FROM jsonb_array_elements(a.variants) AS v
+        ) AS u (url)
+        WHERE EXISTS (SELECT 1 FROM asset_types AS t WHERE t.picture = u.url)
+            OR EXISTS (SELECT 1 FROM asset_items AS i WHERE i.picture = u.url)
+            OR EXISTS (SELECT 1 FROM users AS p WHERE p.picture = u.url)
+    );
+
+CREATE VIEW referenced_document_attachments AS
+SELECT a.id
+FROM document_attachments AS a
+WHERE EXISTS (SELECT 1 FROM asset_type_documents AS r WHERE r.document_id = a.id)
+    OR EXISTS (SELECT 1 FROM asset_item_documents AS r WHERE r.document_id = a.id);
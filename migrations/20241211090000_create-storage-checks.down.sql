@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS storage_checks;
+
+ALTER TABLE document_attachments
+DROP COLUMN IF EXISTS checksum;
+
+ALTER TABLE image_attachments
+DROP COLUMN IF EXISTS checksum;
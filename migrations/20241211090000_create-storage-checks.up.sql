@@ -0,0 +1,25 @@
+-- blake3 of the stored file, the hash column identifies the upload and stops matching once it is converted or sanitized.
+-- null for rows stored before this, their files were kept as uploaded so the hash still applies
+ALTER TABLE image_attachments
+ADD checksum TEXT;
+
+ALTER TABLE document_attachments
+ADD checksum TEXT;
+
+-- results of the storage integrity check, unfinished while finished_at is null
+CREATE TABLE
+    "storage_checks" (
+        id SERIAL PRIMARY KEY,
+        quarantine BOOLEAN NOT NULL DEFAULT FALSE,
+        checked_files INTEGER NOT NULL DEFAULT 0,
+        issues JSONB NOT NULL DEFAULT '[]',
+        error TEXT,
+        started_by UUID REFERENCES users (id) ON DELETE SET NULL,
+        started_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        finished_at TIMESTAMP
+            WITH TIME ZONE
+    );
+
+-- one check at a time across all app instances
+CREATE UNIQUE INDEX idx_storage_checks_running ON storage_checks ((TRUE)) WHERE finished_at IS NULL AND error IS NULL;
@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS idx_document_attachments_text_pending;
+DROP INDEX IF EXISTS idx_document_attachments_text_search;
+
+ALTER TABLE document_attachments
+DROP COLUMN IF EXISTS text_search;
+
+ALTER TABLE document_attachments
+DROP COLUMN IF EXISTS text_extraction_error,
+DROP COLUMN IF EXISTS text_extracted_at,
+DROP COLUMN IF EXISTS text_content;
@@ -0,0 +1,17 @@
+-- text pulled out of pdf and text documents by the background extraction job. text_extracted_at stays null
+-- until the job got to the document, a document it could not read keeps the error instead of the text
+ALTER TABLE document_attachments
+ADD text_content TEXT,
+ADD text_extracted_at TIMESTAMP
+    WITH TIME ZONE,
+ADD text_extraction_error TEXT;
+
+ALTER TABLE document_attachments
+ADD text_search tsvector
+GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(filename, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(text_content, '')), 'B')
+) STORED;
+
+CREATE INDEX idx_document_attachments_text_search ON document_attachments USING GIN (text_search);
+CREATE INDEX idx_document_attachments_text_pending ON document_attachments (id) WHERE text_extracted_at IS NULL;
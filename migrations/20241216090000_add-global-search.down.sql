@@ -0,0 +1,15 @@
+DROP INDEX IF EXISTS idx_users_name_trgm;
+DROP INDEX IF EXISTS idx_asset_items_name_trgm;
+DROP INDEX IF EXISTS idx_asset_types_brand_model_trgm;
+
+DROP INDEX IF EXISTS idx_users_full_search;
+DROP INDEX IF EXISTS idx_asset_items_full_search;
+DROP INDEX IF EXISTS idx_asset_types_full_search;
+
+ALTER TABLE users
+DROP COLUMN IF EXISTS full_search;
+
+ALTER TABLE asset_items
+DROP COLUMN IF EXISTS full_search;
+
+-- pg_trgm is left installed, other database objects may have come to depend on it
@@ -0,0 +1,29 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- ranked matches for the global search, asset_types already has full_search.
+-- 'simple' keeps codes and names as typed, there is nothing to stem in them
+ALTER TABLE asset_items
+ADD full_search tsvector
+GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', coalesce(asset_id, '')), 'A') ||
+    setweight(to_tsvector('simple', coalesce(serial_number, '')), 'A') ||
+    setweight(to_tsvector('simple', coalesce(name, '')), 'B') ||
+    setweight(to_tsvector('simple', coalesce(school, '') || ' ' || coalesce(room, '')), 'C')
+) STORED;
+
+ALTER TABLE users
+ADD full_search tsvector
+GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', given_name || ' ' || family_name), 'A') ||
+    setweight(to_tsvector('simple', email), 'B')
+) STORED;
+
+CREATE INDEX idx_asset_types_full_search ON asset_types USING GIN (full_search);
+CREATE INDEX idx_asset_items_full_search ON asset_items USING GIN (full_search);
+CREATE INDEX idx_users_full_search ON users USING GIN (full_search);
+
+-- typo tolerant matching, the search compares against these exact expressions
+CREATE INDEX idx_asset_types_brand_model_trgm ON asset_types USING GIN ((brand || ' ' || model) gin_trgm_ops);
+CREATE INDEX idx_asset_items_name_trgm
+    ON asset_items USING GIN ((coalesce(name, '') || ' ' || coalesce(asset_id, '') || ' ' || coalesce(serial_number, '')) gin_trgm_ops);
+CREATE INDEX idx_users_name_trgm ON users USING GIN ((given_name || ' ' || family_name || ' ' || email) gin_trgm_ops);
@@ -0,0 +1 @@
+-- pg_trgm is left installed, other database objects may have come to depend on it
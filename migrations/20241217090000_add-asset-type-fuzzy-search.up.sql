@@ -0,0 +1,3 @@
+-- the asset type search matches with the <% operator and ranks with word_similarity.
+-- the global search migration already installs pg_trgm, this keeps the asset type search from relying on it.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
@@ -0,0 +1,19 @@
+CREATE TABLE
+    "saved_views" (
+        id SERIAL PRIMARY KEY,
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        search TEXT,
+        sort TEXT,
+        sort_order TEXT CHECK (sort_order IN ('ASC', 'DESC')),
+        category_id INTEGER REFERENCES categories (id) ON DELETE SET NULL,
+        -- asset item columns to show, empty shows every column
+        columns TEXT[] NOT NULL DEFAULT '{}',
+        -- None keeps the view to its owner
+        shared_role_id INTEGER REFERENCES roles (id) ON DELETE SET NULL,
+        created_at TIMESTAMP
+            WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        UNIQUE (user_id, name)
+    );
+
+CREATE INDEX idx_saved_views_shared_role_id ON saved_views (shared_role_id);
@@ -0,0 +1 @@
+DROP VIEW IF EXISTS asset_item_details;
@@ -0,0 +1,10 @@
+-- asset items joined with the asset type columns they display, every AssetItem query selects from here
+CREATE VIEW asset_item_details AS
+SELECT ai.id, ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, at.brand, at.model, at.description, at.cost,
+    ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status, ai.custom_fields,
+    -- the item's primary image, otherwise its asset type's picture
+    COALESCE(ai.picture, at.picture) AS picture,
+    at.category_id, ai.created_at
+FROM asset_items AS ai
+LEFT JOIN asset_types AS at
+    ON ai.asset_type_id = at.id;
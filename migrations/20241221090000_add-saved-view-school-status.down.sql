@@ -0,0 +1,3 @@
+ALTER TABLE saved_views
+    DROP COLUMN IF EXISTS school,
+    DROP COLUMN IF EXISTS status;
@@ -0,0 +1,3 @@
+ALTER TABLE saved_views
+    ADD COLUMN school TEXT,
+    ADD COLUMN status TEXT;
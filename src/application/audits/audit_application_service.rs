@@ -0,0 +1,128 @@
+use uuid::Uuid;
+
+use crate::{domain::{audits::{audit_repository::{AuditRepository, AuditRepositoryError}, model::audits::{Audit, AuditReconciliation, NewAudit}}, crud::{crud_repository::{CrudRepository, CrudRepositoryError}, model::{asset_items::ASSET_ITEM_STATUS_MISSING, lookup::{parse_lookup_code, select_code_match, LookupCode}}}}, infastructure::services::{postgres_audit_repository::PostgresAuditRepository, postgres_crud_repository::PostgresCrudRepository}};
+
+use super::schema::{AuditScanSchema, NewAuditSchema};
+
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    #[error(transparent)]
+    Repo(#[from] AuditRepositoryError),
+    #[error(transparent)]
+    Crud(#[from] CrudRepositoryError),
+    #[error("audit not found")]
+    NotFound,
+    #[error("audit is already completed")]
+    Completed,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditApplicationService {
+    audit_repo: PostgresAuditRepository,
+    crud_repo: PostgresCrudRepository,
+}
+
+impl AuditApplicationService {
+    pub fn new(audit_repo: PostgresAuditRepository, crud_repo: PostgresCrudRepository) -> Self {
+        Self{audit_repo, crud_repo}
+    }
+
+    pub async fn get_audits(&self) -> Result<Vec<Audit>, AuditError> {
+        let audits = self.audit_repo.get_audits().await?;
+
+        Ok(audits)
+    }
+
+    pub async fn get_audit(&self, id: i32) -> Result<Audit, AuditError> {
+        self.audit_repo.get_audit_by_id(id)
+            .await?
+            .ok_or(AuditError::NotFound)
+    }
+
+    pub async fn add_audit(&self, schema: NewAuditSchema, created_by: Uuid) -> Result<Audit, AuditError> {
+        // should be validated in handler
+        let new_audit = NewAudit {
+            school: schema.school.trim().to_string(),
+            room: schema.room.map(|s| s.trim().to_string()).and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            created_by,
+        };
+
+        let audit = self.audit_repo.add_audit(new_audit).await?;
+
+        Ok(audit)
+    }
+
+    /// Open audits are reconciled live, completed audits read back their stored report
+    pub async fn get_audit_reconciliation(&self, audit: &Audit) -> Result<AuditReconciliation, AuditError> {
+        if !audit.is_open() {
+            let results = self.audit_repo.get_audit_results(audit.id).await?;
+            return Ok(AuditReconciliation::from_results(results));
+        }
+
+        let expected = self.audit_repo.get_audit_expected_items(audit.school.clone(), audit.room.clone()).await?;
+        let scans = self.audit_repo.get_audit_scans(audit.id).await?;
+        let scanned_items = self.audit_repo.get_audit_scanned_items(audit.id).await?;
+
+        Ok(AuditReconciliation::build(audit, &expected, &scans, &scanned_items))
+    }
+
+    /// Records a scan, codes that match no item are kept so they show up as unknown
+    pub async fn add_audit_scan(&self, audit_id: i32, schema: AuditScanSchema) -> Result<(), AuditError> {
+        let audit = self.get_open_audit(audit_id).await?;
+        let raw_code = schema.code.trim().to_string();
+
+        let asset_item_id = match parse_lookup_code(&raw_code) {
+            Some(LookupCode::ItemId(id)) => {
+                self.crud_repo.get_asset_item_by_id(id).await?.map(|i| i.id)
+            },
+            Some(LookupCode::Code(code)) => {
+                let asset_items = self.crud_repo.get_asset_items_by_code(code.clone()).await?;
+                select_code_match(&code, &asset_items).map(|i| i.id)
+            },
+            None => None,
+        };
+
+        self.audit_repo.add_audit_scan(audit.id, raw_code, asset_item_id).await?;
+
+        Ok(())
+    }
+
+    /// Moves a misplaced item to where it was scanned, a school wide audit does not know the room so it is cleared
+    pub async fn relocate_asset_item(&self, audit_id: i32, asset_item_id: i32, changed_by: Uuid) -> Result<(), AuditError> {
+        let audit = self.get_open_audit(audit_id).await?;
+
+        self.crud_repo.update_asset_item_location(asset_item_id, audit.school, audit.room, changed_by).await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_asset_item_missing(&self, audit_id: i32, asset_item_id: i32, changed_by: Uuid) -> Result<(), AuditError> {
+        self.get_open_audit(audit_id).await?;
+
+        self.crud_repo.update_asset_item_status(asset_item_id, ASSET_ITEM_STATUS_MISSING.to_string(), changed_by).await?;
+
+        Ok(())
+    }
+
+    /// Freezes the current reconciliation as the audit report
+    pub async fn complete_audit(&self, audit_id: i32) -> Result<Audit, AuditError> {
+        let audit = self.get_open_audit(audit_id).await?;
+        let reconciliation = self.get_audit_reconciliation(&audit).await?;
+
+        self.audit_repo.complete_audit(audit.id, &reconciliation.results())
+            .await?
+            .ok_or(AuditError::Completed)
+    }
+
+    async fn get_open_audit(&self, audit_id: i32) -> Result<Audit, AuditError> {
+        let audit = self.get_audit(audit_id).await?;
+        if !audit.is_open() {
+            return Err(AuditError::Completed);
+        }
+
+        Ok(audit)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod audit_application_service;
+pub mod schema;
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use garde::Validate;
+
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct NewAuditSchema {
+    #[garde(length(min=1))]
+    pub school: String,
+    #[garde(skip)]
+    pub room: Option<String>,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct AuditScanSchema {
+    #[garde(length(min=1))]
+    pub code: String,
+}
@@ -0,0 +1,25 @@
+use crate::settings::AttachmentGcConfig;
+
+use super::{content_application_service::ContentApplicationService, schedule::job_interval};
+
+
+/// Runs the attachment gc every `interval_mins` for as long as the app runs, failures are logged and retried on the next tick
+pub async fn run_scheduled(content_service: ContentApplicationService, config: AttachmentGcConfig) {
+    let mut interval = job_interval(config.interval_mins);
+
+    loop {
+        interval.tick().await;
+
+        match content_service.collect_unreferenced_attachments(config.grace_period_hours, false).await {
+            Ok(report) => tracing::info!(
+                "attachment gc: {} newly unreferenced, {} images and {} documents collected, {} files deleted, {} failed",
+                report.newly_unreferenced,
+                report.collected.images.len(),
+                report.collected.documents.len(),
+                report.deleted_files,
+                report.failed_files,
+            ),
+            Err(e) => tracing::error!("attachment gc failed: {:?}", e),
+        }
+    }
+}
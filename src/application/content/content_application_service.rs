@@ -1,10 +1,12 @@
-use std::{io::Read, path::Path};
+use std::{collections::HashSet, path::Path, sync::Arc};
 
 use anyhow::Context;
 use axum_typed_multipart::FieldData;
 use tempfile::NamedTempFile;
+use tokio::{io::AsyncReadExt, sync::Notify};
+use uuid::Uuid;
 
-use crate::{domain::filesystem::{attachment_repository::AttachmentRepository, models::{ContentType, DocumentAttachment, FilePayload, Filename, ImageAttachment, MIME_LOOKUP}, persistence_service::PersistenceService}, infastructure::services::{local_persistence_service::LocalPersistenceService, postgres_attachment_repository::PostgresAttachmentRepository}};
+use crate::{domain::filesystem::{attachment_repository::{AttachmentRepository, AttachmentRepositoryError}, image_utils::ImageProcessingError, models::{stored_name, ContentType, DocumentAttachment, FilePayload, Filename, HashedTempFile, ImageAttachment, UnreferencedAttachments, MIME_LOOKUP, SNIFF_LENGTH}, persistence_service::{FileRequest, PersistenceError, PersistenceService, StorageFolder, StoredFile}, storage_check::{AttachmentFiles, StorageCheck, StorageCheckResult, StorageIssue, StorageIssueKind}, text_extraction::extract_text}, infastructure::services::{storage_persistence_service::StoragePersistenceService, postgres_attachment_repository::PostgresAttachmentRepository}, settings::{UploadLimitConfig, UploadsConfig}};
 
 
 #[derive(Debug, thiserror::Error)]
@@ -13,63 +15,193 @@ pub enum ContentError {
     InvalidFileMetadata,
     #[error("the file attachment could not be found")]
     Missing,
+    /// the upload was refused, the reason is shown to the user
+    #[error("{0}")]
+    InvalidContent(String),
+    #[error("a storage check is already running")]
+    CheckRunning,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
+/// Outcome of an attachment gc run, a dry run lists what would be deleted without touching anything
+#[derive(Debug, Default)]
+pub struct AttachmentGcReport {
+    pub dry_run: bool,
+    /// attachments that lost their last reference since the previous run, they are collected after the grace period
+    pub newly_unreferenced: u64,
+    pub collected: UnreferencedAttachments,
+    pub deleted_files: usize,
+    pub failed_files: usize,
+}
+
+/// Outcome of one batch of the text extraction job
+#[derive(Debug, Default)]
+pub struct DocumentTextReport {
+    pub extracted: usize,
+    /// recorded on the document with the reason, they are not retried
+    pub failed: usize,
+}
+
+impl DocumentTextReport {
+    pub fn processed(&self) -> usize {
+        self.extracted + self.failed
+    }
+}
+
+/// Which `[uploads]` limits apply to a file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UploadCategory {
+    Images,
+    Documents,
+    Imports,
+}
+
+impl std::fmt::Display for UploadCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let category = match self {
+            UploadCategory::Images => "images",
+            UploadCategory::Documents => "documents",
+            UploadCategory::Imports => "imports",
+        };
+
+        write!(f, "{}", category)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ContentApplicationService {
     attachment_repo: PostgresAttachmentRepository,
-    persistence: LocalPersistenceService,
+    persistence: StoragePersistenceService,
+    uploads: UploadsConfig,
+    /// wakes the text extraction job when a document is uploaded
+    new_documents: Arc<Notify>,
 }
 
 impl ContentApplicationService {
-    pub fn new(attachment_repo: PostgresAttachmentRepository, persistence: LocalPersistenceService) -> Self {
-        Self {
+    pub fn new(attachment_repo: PostgresAttachmentRepository, persistence: StoragePersistenceService, uploads: &UploadsConfig) -> anyhow::Result<Self> {
+        // stored uploads need a known extension and signature, catch typos in the settings at startup rather than on upload
+        for (category, limits) in [(UploadCategory::Images, &uploads.images), (UploadCategory::Documents, &uploads.documents)] {
+            for content_type in limits.allowed_types.iter() {
+                let ext = MIME_LOOKUP.get(content_type.as_str())
+                    .with_context(|| format!("uploads.{} allows {} which is not a known file type", category, content_type))?;
+
+                if category == UploadCategory::Images && !ext.is_decodable() {
+                    anyhow::bail!("uploads.images allows {} which can not be converted, allow it for documents instead", content_type);
+                }
+            }
+        }
+
+        Ok(Self {
             attachment_repo,
             persistence,
+            uploads: uploads.clone(),
+            new_documents: Arc::new(Notify::new()),
+        })
+    }
+
+    fn upload_limits(&self, category: UploadCategory) -> &UploadLimitConfig {
+        match category {
+            UploadCategory::Images => &self.uploads.images,
+            UploadCategory::Documents => &self.uploads.documents,
+            UploadCategory::Imports => &self.uploads.imports,
+        }
+    }
+
+    /// The request body limit only bounds the whole form, this holds each file to the limits of its category
+    pub fn check_upload(&self, category: UploadCategory, content_type: &str, size: u64) -> Result<(), ContentError> {
+        let limits = self.upload_limits(category);
+
+        if !limits.allows(content_type) {
+            return Err(ContentError::InvalidContent(format!("{} files are not allowed for {}", content_type, category)));
+        }
+
+        if MIME_LOOKUP.get(content_type).is_some_and(|ext| !ext.is_supported()) {
+            return Err(ContentError::InvalidContent("HEIC, HEIF and AVIF images are not supported by this server".to_string()));
         }
+
+        if size > limits.max_size as u64 {
+            return Err(ContentError::InvalidContent(format!("the file is too large, {} can be at most {}", category, limits.max_size_display())));
+        }
+
+        Ok(())
+    }
+
+    /// Checks an upload that is read in place instead of stored, like a csv import
+    pub fn check_file_upload(&self, category: UploadCategory, upload: &FieldData<NamedTempFile>) -> Result<(), ContentError> {
+        let content_type = upload.metadata.content_type.as_deref().ok_or(ContentError::InvalidFileMetadata)?;
+        let size = upload.contents.as_file()
+            .metadata()
+            .context("could not read the upload size")?
+            .len();
+
+        self.check_upload(category, content_type, size)
     }
 
-    pub async fn retrieve_image_file(&self, hash: String) -> Result<(tokio::fs::File, Filename, ContentType), ContentError> {
-        let attachment = self.attachment_repo.get_image_attachent_from_hash(hash)
+    /// name is the last segment of an attachment or variant url, e.g. <hash>.webp or <hash>-thumbnail.webp
+    pub async fn retrieve_image_file(&self, name: String, range: Option<(u64, Option<u64>)>) -> Result<(StoredFile, Filename, ContentType), ContentError> {
+        let attachment = self.attachment_repo.get_image_attachent_from_hash(hash_from_name(&name).to_string())
             .await
             .context("could not retrieve attachment from database")?
             .ok_or(ContentError::Missing)?;
 
-        let filepath = self.persistence.images_path.join(AsRef::<str>::as_ref(&attachment.filename));
-        let file = tokio::fs::File::open(&filepath).await.map_err(|_| ContentError::Missing)?;
+        // only names the attachment actually owns, the name becomes part of the storage path
+        let owned = std::iter::once(attachment.url.as_str())
+            .chain(attachment.variants.iter().map(|v| v.url.as_str()))
+            .any(|url| stored_name(url) == name);
+        if !owned {
+            return Err(ContentError::Missing);
+        }
+
+        let request = FileRequest {
+            content_type: attachment.content_type.to_string(),
+            content_disposition: attachment.filename.content_disposition(),
+            range,
+        };
+        let file = self.persistence.retrieve_file(StorageFolder::Images, &name, &request)
+            .await
+            .map_err(retrieve_error)?;
 
         Ok((file, attachment.filename, attachment.content_type))
     }
 
-    pub async fn retrieve_document_file(&self, hash: String) -> Result<(tokio::fs::File, Filename, ContentType), ContentError> {
-        let attachment = self.attachment_repo.get_document_attachent_from_hash(hash)
+    pub async fn retrieve_document_file(&self, name: String, range: Option<(u64, Option<u64>)>) -> Result<(StoredFile, Filename, ContentType), ContentError> {
+        let attachment = self.attachment_repo.get_document_attachent_from_hash(hash_from_name(&name).to_string())
             .await
             .context("could not retrieve attachment from database")?
             .ok_or(ContentError::Missing)?;
 
-        let filepath = self.persistence.docs_path.join(AsRef::<str>::as_ref(&attachment.filename));
-        let file = tokio::fs::File::open(&filepath).await.map_err(|_| ContentError::Missing)?;
+        if stored_name(&attachment.url) != name {
+            return Err(ContentError::Missing);
+        }
+
+        let request = FileRequest {
+            content_type: attachment.content_type.to_string(),
+            content_disposition: attachment.filename.content_disposition(),
+            range,
+        };
+        let file = self.persistence.retrieve_file(StorageFolder::Documents, &name, &request)
+            .await
+            .map_err(retrieve_error)?;
 
         Ok((file, attachment.filename, attachment.content_type))
     }
 
-    pub async fn upload_image_file_as_attachment(&self, uploaded_file: FieldData<NamedTempFile>) -> Result<ImageAttachment, ContentError> {
-        let payload = self.create_file_payload(uploaded_file).await?;
+    pub async fn upload_image_file_as_attachment(&self, uploaded_file: FieldData<HashedTempFile>) -> Result<ImageAttachment, ContentError> {
+        let payload = self.create_file_payload(UploadCategory::Images, uploaded_file).await?;
 
         let attachment = self.attachment_repo.get_image_attachent_from_hash(payload.hash.clone())
             .await
             .context("could not retrieve attachment from database")?;
 
-        if attachment.is_some() {
+        if let Some(attachment) = attachment {
             tracing::info!("duplicate attachment found");
-            return Ok(attachment.unwrap());
+            return Ok(attachment);
         }
 
         let new_attachment = self.persistence.persist_image_file(payload)
             .await
-            .context("error persisting file")?;
+            .map_err(persistence_error)?;
 
         let attachment = self.attachment_repo.add_image_attachent(new_attachment)
             .await
@@ -78,36 +210,309 @@ impl ContentApplicationService {
         Ok(attachment)
     }
 
-    pub async fn upload_document_file_as_attachment(&self, uploaded_file: FieldData<NamedTempFile>) -> Result<DocumentAttachment, ContentError> {
-        let payload = self.create_file_payload(uploaded_file).await?;
+    pub async fn upload_document_file_as_attachment(&self, uploaded_file: FieldData<HashedTempFile>) -> Result<DocumentAttachment, ContentError> {
+        let payload = self.create_file_payload(UploadCategory::Documents, uploaded_file).await?;
 
         let attachment = self.attachment_repo.get_document_attachent_from_hash(payload.hash.clone())
             .await
             .context("could not retrieve attachment from database")?;
 
-        if attachment.is_some() {
+        if let Some(attachment) = attachment {
             tracing::info!("duplicate attachment found");
-            return Ok(attachment.unwrap());
+            return Ok(attachment);
         }
 
         let new_attachment = self.persistence.persist_document_file(payload)
             .await
-            .context("error persisting file")?;
+            .map_err(persistence_error)?;
 
         let attachment = self.attachment_repo.add_document_attachent(new_attachment)
             .await
             .context("could not add new attachment to database")?;
 
+        let content_type: &str = attachment.content_type.as_ref();
+        if MIME_LOOKUP.get(content_type).is_some_and(|ext| ext.has_text()) {
+            self.new_documents.notify_one();
+        }
+
         Ok(attachment)
     }
 
-    async fn create_file_payload(&self, uploaded_file: FieldData<NamedTempFile>) -> Result<FilePayload, ContentError> {
+    /// Resolves once a document with text was uploaded since the last call
+    pub async fn wait_for_new_documents(&self) {
+        self.new_documents.notified().await
+    }
+
+    /// Extracts the text of up to batch_size documents the job has not been to yet, for the full-text search.
+    /// A document that can not be read records why, so one broken pdf does not hold up the queue.
+    pub async fn extract_document_texts(&self, batch_size: i64) -> Result<DocumentTextReport, ContentError> {
+        let content_types: Vec<String> = MIME_LOOKUP.iter()
+            .filter(|(_, ext)| ext.has_text())
+            .map(|(content_type, _)| content_type.to_string())
+            .collect();
+
+        let documents = self.attachment_repo.get_documents_without_text(&content_types, batch_size)
+            .await
+            .context("could not retrieve documents without text")?;
+
+        let mut report = DocumentTextReport::default();
+        for document in documents {
+            let (text, error) = match self.extract_document_text(&document).await {
+                Ok(text) => (Some(text), None),
+                Err(e) => {
+                    tracing::warn!("could not extract text from document {}: {:?}", document.id, e);
+                    (None, Some(format!("{:#}", e)))
+                },
+            };
+
+            match error {
+                None => report.extracted += 1,
+                Some(_) => report.failed += 1,
+            }
+
+            self.attachment_repo.set_document_text(document.id, text, error)
+                .await
+                .context("could not store document text")?;
+        }
+
+        Ok(report)
+    }
+
+    async fn extract_document_text(&self, document: &DocumentAttachment) -> anyhow::Result<String> {
+        let content_type: &str = document.content_type.as_ref();
+        let ext = *MIME_LOOKUP.get(content_type).context("unknown document type")?;
+
+        let data = self.persistence.read_file(StorageFolder::Documents, stored_name(&document.url))
+            .await
+            .context("could not read the stored file")?;
+
+        // a malformed pdf can panic the parser, that ends up as a JoinError here instead of taking the job down
+        let text = tokio::task::spawn_blocking(move || extract_text(&data, ext))
+            .await
+            .context("text extraction stopped unexpectedly")??;
+
+        Ok(text)
+    }
+
+    /// Mark and sweep over every attachment reference, see the referenced_*_attachments views.
+    /// Marking only stamps rows, so a dry run marks too and the grace period still starts counting.
+    pub async fn collect_unreferenced_attachments(&self, grace_period_hours: i32, dry_run: bool) -> Result<AttachmentGcReport, ContentError> {
+        let newly_unreferenced = self.attachment_repo.mark_unreferenced_attachments()
+            .await
+            .context("could not mark unreferenced attachments")?;
+
+        if dry_run {
+            let collected = self.attachment_repo.get_collectable_attachments(grace_period_hours)
+                .await
+                .context("could not retrieve collectable attachments")?;
+
+            return Ok(AttachmentGcReport { dry_run, newly_unreferenced, collected, ..Default::default() });
+        }
+
+        // rows go first, a file left behind by a failed delete is only wasted space while a row without its file is a broken link
+        let collected = self.attachment_repo.delete_collectable_attachments(grace_period_hours)
+            .await
+            .context("could not delete collectable attachments")?;
+
+        let mut report = AttachmentGcReport { dry_run, newly_unreferenced, ..Default::default() };
+        let folders = [(StorageFolder::Images, &collected.images), (StorageFolder::Documents, &collected.documents)];
+
+        for (folder, attachments) in folders {
+            for name in attachments.iter().flat_map(|a| a.stored_names()) {
+                match self.persistence.delete_file(folder, name).await {
+                    Ok(()) => report.deleted_files += 1,
+                    Err(e) => {
+                        tracing::warn!("could not delete {}/{}: {}", folder, name, e);
+                        report.failed_files += 1;
+                    }
+                }
+            }
+        }
+
+        report.collected = collected;
+        Ok(report)
+    }
+
+    /// Starts a storage check in the background, the returned check is updated once it completes
+    pub async fn start_storage_check(&self, quarantine: bool, started_by: Option<Uuid>) -> Result<StorageCheck, ContentError> {
+        let check = self.add_storage_check(quarantine, started_by).await?;
+
+        let service = self.clone();
+        let id = check.id;
+        tokio::spawn(async move { service.complete_storage_check(id, quarantine).await });
+
+        Ok(check)
+    }
+
+    /// Runs a storage check to completion, for the cli
+    pub async fn run_storage_check(&self, quarantine: bool) -> Result<StorageCheck, ContentError> {
+        let check = self.add_storage_check(quarantine, None).await?;
+        self.complete_storage_check(check.id, quarantine).await;
+
+        self.get_storage_check(check.id).await
+    }
+
+    pub async fn get_storage_checks(&self) -> Result<Vec<StorageCheck>, ContentError> {
+        let checks = self.attachment_repo.get_storage_checks(20)
+            .await
+            .context("could not retrieve storage checks")?;
+
+        Ok(checks)
+    }
+
+    pub async fn get_storage_check(&self, id: i32) -> Result<StorageCheck, ContentError> {
+        let check = self.attachment_repo.get_storage_check(id)
+            .await
+            .context("could not retrieve storage check")?
+            .ok_or(ContentError::Missing)?;
+
+        Ok(check)
+    }
+
+    async fn add_storage_check(&self, quarantine: bool, started_by: Option<Uuid>) -> Result<StorageCheck, ContentError> {
+        self.attachment_repo.add_storage_check(quarantine, started_by)
+            .await
+            .map_err(|e| match e {
+                AttachmentRepositoryError::Duplicate => ContentError::CheckRunning,
+                e => ContentError::Unknown(anyhow::Error::new(e).context("could not add storage check")),
+            })
+    }
+
+    /// Records the outcome on the check row, failures included, since nobody is waiting on the background task
+    async fn complete_storage_check(&self, id: i32, quarantine: bool) {
+        let recorded = match self.check_storage(quarantine).await {
+            Ok(result) => self.attachment_repo.finish_storage_check(id, result).await,
+            Err(e) => {
+                tracing::error!("storage check {} failed: {:?}", id, e);
+                self.attachment_repo.fail_storage_check(id, e.to_string()).await
+            }
+        };
+
+        if let Err(e) = recorded {
+            tracing::error!("could not record storage check {}: {:?}", id, e);
+        }
+    }
+
+    /// Re-hashes every stored file and compares the store with the attachment rows
+    async fn check_storage(&self, quarantine: bool) -> Result<StorageCheckResult, ContentError> {
+        let images = self.attachment_repo.get_image_attachment_files()
+            .await
+            .context("could not retrieve image attachment files")?;
+        let documents = self.attachment_repo.get_document_attachment_files()
+            .await
+            .context("could not retrieve document attachment files")?;
+
+        let mut result = StorageCheckResult::default();
+        for (folder, rows) in [(StorageFolder::Images, images), (StorageFolder::Documents, documents)] {
+            self.check_storage_folder(folder, &rows, quarantine, &mut result).await?;
+        }
+
+        Ok(result)
+    }
+
+    async fn check_storage_folder(&self, folder: StorageFolder, rows: &[AttachmentFiles], quarantine: bool, result: &mut StorageCheckResult) -> Result<(), ContentError> {
+        let stored: HashSet<String> = self.persistence.list_files(folder)
+            .await
+            .context(format!("could not list stored {}", folder))?
+            .into_iter()
+            .collect();
+
+        let mut expected = HashSet::new();
+        for row in rows {
+            for (name, checksum) in row.expected_files() {
+                expected.insert(name);
+
+                let issue = |kind, actual| StorageIssue {
+                    kind,
+                    folder,
+                    name: name.to_string(),
+                    attachment_id: Some(row.id),
+                    expected: checksum.map(str::to_string),
+                    actual,
+                    quarantined: false,
+                };
+
+                if !stored.contains(name) {
+                    result.issues.push(issue(StorageIssueKind::Missing, None));
+                    continue;
+                }
+
+                // converted files from before checksums were recorded can only be checked for presence
+                let Some(checksum) = checksum else { continue };
+
+                let actual = match self.persistence.hash_stored_file(folder, name).await {
+                    Ok(actual) => actual,
+                    Err(PersistenceError::NotFound) => {
+                        result.issues.push(issue(StorageIssueKind::Missing, None));
+                        continue;
+                    }
+                    Err(e) => return Err(anyhow::Error::new(e).context(format!("could not hash {}/{}", folder, name)).into()),
+                };
+                result.checked_files += 1;
+
+                if actual != checksum {
+                    let mut issue = issue(StorageIssueKind::Mismatch, Some(actual));
+                    if quarantine {
+                        match self.persistence.quarantine_file(folder, name).await {
+                            Ok(()) => issue.quarantined = true,
+                            Err(e) => tracing::warn!("could not quarantine {}/{}: {}", folder, name, e),
+                        }
+                    }
+                    result.issues.push(issue);
+                }
+            }
+        }
+
+        // left behind by failed deletes or copied in by hand, reported but never touched
+        let mut orphaned: Vec<&String> = stored.iter().filter(|name| !expected.contains(name.as_str())).collect();
+        orphaned.sort();
+
+        for name in orphaned {
+            result.issues.push(StorageIssue {
+                kind: StorageIssueKind::Orphaned,
+                folder,
+                name: name.to_string(),
+                attachment_id: None,
+                expected: None,
+                actual: None,
+                quarantined: false,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn create_file_payload(&self, category: UploadCategory, uploaded_file: FieldData<HashedTempFile>) -> Result<FilePayload, ContentError> {
         let filename = uploaded_file.metadata.file_name.clone().ok_or(ContentError::InvalidFileMetadata)?;
         let content_type = uploaded_file.metadata.content_type.clone().ok_or(ContentError::InvalidFileMetadata)?;
 
-        let (temp_file, hash) = self.persistence.hash_file(uploaded_file.contents)
+        // browsers that have no type registered for heic send it untyped, fall back to the file extension,
+        // the signature check below still has the last word
+        let content_type = match content_type.as_str() {
+            "" | "application/octet-stream" => mime_guess::from_path(&filename)
+                .first_raw()
+                .map(str::to_string)
+                .unwrap_or(content_type),
+            _ => content_type,
+        };
+
+        let HashedTempFile { temp_file, hash, size } = uploaded_file.contents;
+        if size == 0 {
+            return Err(ContentError::InvalidContent("the file is empty".to_string()));
+        }
+
+        self.check_upload(category, &content_type, size)?;
+        let ext = MIME_LOOKUP.get(content_type.as_str())
+            .ok_or(ContentError::InvalidContent(format!("{} files are not supported", content_type)))?;
+
+        // the declared content type picks the stored extension, so it has to agree with the bytes
+        let head = read_head(temp_file.path())
             .await
-            .context("error hashing file")?;
+            .context("error reading file")?;
+
+        if !ext.matches_signature(&head) {
+            return Err(ContentError::InvalidContent(format!("the file contents do not match its type {}", content_type)));
+        }
 
         Ok(FilePayload {
             filename,
@@ -116,4 +521,33 @@ impl ContentApplicationService {
             temp_file,
         })
     }
-}
\ No newline at end of file
+}
+
+async fn read_head(path: &Path) -> std::io::Result<Vec<u8>> {
+    let file = tokio::fs::File::open(path).await?;
+
+    let mut head = Vec::with_capacity(SNIFF_LENGTH);
+    file.take(SNIFF_LENGTH as u64).read_to_end(&mut head).await?;
+
+    Ok(head)
+}
+
+fn retrieve_error(e: PersistenceError) -> ContentError {
+    match e {
+        PersistenceError::NotFound => ContentError::Missing,
+        e => ContentError::Unknown(anyhow::Error::new(e).context("error retrieving file")),
+    }
+}
+
+fn persistence_error(e: PersistenceError) -> ContentError {
+    match e {
+        PersistenceError::ExtNotSupported => ContentError::InvalidContent("this file type is not supported here".to_string()),
+        PersistenceError::Image(ImageProcessingError::Decode(_)) => ContentError::InvalidContent("the image is damaged or could not be read".to_string()),
+        e => ContentError::Unknown(anyhow::Error::new(e).context("error persisting file")),
+    }
+}
+
+/// Files are stored as <hash>.<ext>, or <hash>-<size>.webp for image variants
+fn hash_from_name(name: &str) -> &str {
+    name.split(['.', '-']).next().unwrap_or(name)
+}
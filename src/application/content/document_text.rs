@@ -0,0 +1,34 @@
+use crate::settings::TextExtractionConfig;
+
+use super::{content_application_service::ContentApplicationService, schedule::job_interval};
+
+
+/// Indexes the text of new documents for as long as the app runs. Uploads wake the job, the interval
+/// catches documents from before the search existed and uploads handled by another instance.
+pub async fn run_scheduled(content_service: ContentApplicationService, config: TextExtractionConfig) {
+    let mut interval = job_interval(config.interval_mins);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {},
+            _ = content_service.wait_for_new_documents() => {},
+        }
+
+        // keep going while batches come back full, there may be more waiting
+        loop {
+            match content_service.extract_document_texts(config.batch_size).await {
+                Ok(report) if report.processed() == 0 => break,
+                Ok(report) => {
+                    tracing::info!("text extraction: {} documents indexed, {} failed", report.extracted, report.failed);
+                    if (report.processed() as i64) < config.batch_size {
+                        break;
+                    }
+                },
+                Err(e) => {
+                    tracing::error!("text extraction failed: {:?}", e);
+                    break;
+                },
+            }
+        }
+    }
+}
@@ -1,2 +1,5 @@
+pub mod attachment_gc;
 pub mod content_application_service;
-pub mod schema;
\ No newline at end of file
+pub mod document_text;
+pub mod schedule;
+pub mod schema;
@@ -0,0 +1,13 @@
+use std::{num::NonZeroU64, time::Duration};
+
+use tokio::time::{Interval, MissedTickBehavior};
+
+
+/// Interval for a background job, the first tick completes straight away so the job also runs on startup.
+/// A slow run is not followed by a burst of catch up runs.
+pub fn job_interval(interval_mins: NonZeroU64) -> Interval {
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_mins.get() * 60));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    interval
+}
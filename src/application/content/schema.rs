@@ -1,9 +1,20 @@
 use axum_typed_multipart::{FieldData, TryFromMultipart};
-use tempfile::NamedTempFile;
+use garde::Validate;
+use serde::Deserialize;
+
+use crate::domain::filesystem::models::HashedTempFile;
 
 
 #[derive(TryFromMultipart)]
 pub struct ImageSchema {
-    #[form_data(limit = "5MiB")]
-    pub image: FieldData<NamedTempFile>,
+    #[form_data(limit = "unlimited")]
+    pub image: FieldData<HashedTempFile>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StorageCheckSchema {
+    /// move files whose bytes no longer match their checksum out of the served folders
+    #[garde(skip)]
+    #[serde(default)]
+    pub quarantine: bool,
 }
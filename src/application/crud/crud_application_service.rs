@@ -1,8 +1,14 @@
-use axum::http::uri::Scheme;
+use std::collections::HashMap;
 
-use crate::{application::content::content_application_service::{ContentApplicationService, ContentError}, domain::crud::{crud_repository::{CrudRepository, CrudRepositoryError}, model::{asset_items::{AssetItem, AssetItemID, NewAssetItem}, asset_types::{AssetType, AssetTypeFilter, NewAssetType, UpdateAssetType, UploadResult}}}, infastructure::services::postgres_crud_repository::PostgresCrudRepository};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use axum_typed_multipart::FieldData;
+use uuid::Uuid;
 
-use super::schema::{FilterSchema, NewAssetItemSchema, NewAssetTypeSchema, UpdateAssetTypeSchema, UploadAsetTypesSchema};
+use crate::{application::content::content_application_service::{ContentApplicationService, ContentError}, domain::{filesystem::models::HashedTempFile, crud::{crud_repository::{CrudRepository, CrudRepositoryError}, model::{documents::{AssetDocument, NewAssetDocument}, images::{reorder_positions, AssetImage, EMPTY_IMAGE_URL}, bulk::{BulkAssetItemAction, BulkAssetItemUpdate, BulkEditSummary}, history::AssetItemHistoryEntry, categories::{validate_parent, Category, CategoryError, CategoryField, NewCategory, NewCategoryField, UpdateCategory}, asset_items::{AssetItem, AssetItemFilter, AssetItemID, NewAssetItem, UpdateAssetItem}, custom_fields::{custom_form_values, field_name_from_label, parse_custom_values, CustomField, CustomFieldError, NewCustomField}, asset_types::{AssetType, AssetTypeFilter, NewAssetType, UpdateAssetType, UploadResult}, depreciation::DepreciationReport, lookup::{parse_lookup_code, select_code_match, LookupCode, LookupResult}, money::parse_money}}}, infastructure::services::postgres_crud_repository::PostgresCrudRepository};
+
+use super::schema::{BulkEditSchema, ReorderImagesSchema, NewDocumentSchema, NewCategorySchema, UpdateCategorySchema, DeleteAssetTypeSchema, DepreciationReportSchema, FilterSchema, LookupSchema, NewCustomFieldSchema, UpdateAssetItemSchema, UploadAssetItemsSchema, NewAssetItemSchema, NewAssetTypeSchema, UpdateAssetTypeSchema, UploadAsetTypesSchema};
 
 
 
@@ -13,6 +19,15 @@ pub enum CrudError {
     #[error("transparent")]
     Content(#[from] ContentError),
     #[error(transparent)]
+    CustomField(#[from] CustomFieldError),
+    #[error(transparent)]
+    Category(#[from] CategoryError),
+    #[error("no changes were selected")]
+    NoChanges,
+    /// one message per import row that could not be read, prefixed with its line in the file
+    #[error("{}", .0.join("; "))]
+    ImportRows(Vec<String>),
+    #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
@@ -45,6 +60,7 @@ impl CrudApplicationService {
             search: schema.search.and_then(|s| if s.is_empty() {None} else {Some(s)}),
             sort: schema.sort.and_then(|s| if s.is_empty() {None} else {Some(s)}),
             order: schema.order.and_then(|s| if s.is_empty() {None} else {Some(s.to_uppercase())}),
+            category_id: parse_id(schema.category_id)?,
         };
 
 
@@ -53,72 +69,163 @@ impl CrudApplicationService {
         Ok((asset_types, asset_type_filter))
     }
 
+    /// The closest brand or model to a search that found nothing, shown as "did you mean"
+    pub async fn get_asset_type_search_suggestion(&self, search: &str) -> Result<Option<String>, CrudError> {
+        let suggestion = self.crud_repo.get_asset_type_search_suggestion(search).await?;
+
+        Ok(suggestion.filter(|term| !term.eq_ignore_ascii_case(search)))
+    }
+
    pub async fn add_asset_type(&self, schema: NewAssetTypeSchema, content: &ContentApplicationService) -> Result<AssetType, CrudError> {
         // should be validated in handler 
-        let attachment_url = match schema.picture {
-            Some(temp_file) => {
-                let attachment = content.upload_image_file_as_attachment(temp_file)
-                    .await?;
-
-                attachment.url
-            },
-            None => {
-                "/static/images/empty-image.svg".to_string()
-            },
+        let attachment = match schema.picture {
+            Some(temp_file) => Some(content.upload_image_file_as_attachment(temp_file).await?),
+            None => None,
         };
 
         let new_asset_type = NewAssetType {
             brand: schema.brand,
             model: schema.model,
             description: schema.description,
-            cost: schema.cost,
-            picture: Some(attachment_url),
+            cost: parse_cost(schema.cost)?,
+            useful_life_years: parse_useful_life(schema.useful_life_years)?,
+            picture: Some(EMPTY_IMAGE_URL.to_string()),
+            category_id: parse_id(schema.category_id)?,
         };
 
-        let asset_type = self.crud_repo.add_asset_type(new_asset_type)
+        let mut asset_type = self.crud_repo.add_asset_type(new_asset_type)
             .await?;
 
+        // the uploaded picture starts the gallery as its primary image
+        if let Some(attachment) = attachment {
+            let image = self.crud_repo.add_asset_type_image(asset_type.id, attachment.id, true).await?;
+            asset_type.picture = Some(image.thumbnail_url().to_string());
+        }
+
         Ok(asset_type)
    } 
 
    pub async fn update_asset_type(&self, id: i32, schema: UpdateAssetTypeSchema, content: &ContentApplicationService) -> Result<Option<AssetType>, CrudError> {
         
-        let attachment_url = match schema.picture {
-            Some(temp_file) => {
-                let attachment = content.upload_image_file_as_attachment(temp_file)
-                    .await?;
-
-                Some(attachment.url)
-            },
-            None => { None },
+        let attachment = match schema.picture {
+            Some(temp_file) => Some(content.upload_image_file_as_attachment(temp_file).await?),
+            None => None,
         };
 
         let update_asset_type = UpdateAssetType {
             brand: schema.brand,
             model: schema.model,
             description: schema.description,
-            cost: schema.cost,
-            picture: attachment_url,
+            cost: parse_cost(schema.cost)?,
+            useful_life_years: parse_useful_life(schema.useful_life_years)?,
+            // the picture follows the gallery's primary image
+            picture: None,
+            category_id: parse_id(schema.category_id)?,
         };
 
-        let asset_type = self.crud_repo.update_asset_type(id, update_asset_type)
+        let mut asset_type = self.crud_repo.update_asset_type(id, update_asset_type)
             .await?;
 
+        if let (Some(asset_type), Some(attachment)) = (asset_type.as_mut(), attachment) {
+            let image = self.crud_repo.add_asset_type_image(asset_type.id, attachment.id, true).await?;
+            asset_type.picture = Some(image.thumbnail_url().to_string());
+        }
+
         Ok(asset_type)
     }   
 
-    pub async fn delete_asset_type(&self, id: i32) -> Result<Option<i32>, CrudError> {
-        let asset_type = self.crud_repo.delete_asset_type(id)
-            .await?;
+    pub async fn delete_asset_type(&self, id: i32, schema: DeleteAssetTypeSchema) -> Result<Option<i32>, CrudError> {
+        // items referencing the type block the delete unless they are moved to another type
+        let asset_type = match parse_id(schema.reassign_to)? {
+            Some(reassign_to) if reassign_to != id => {
+                self.crud_repo.reassign_and_delete_asset_type(id, reassign_to).await?
+            },
+            _ => {
+                self.crud_repo.delete_asset_type(id).await?
+            },
+        };
 
         Ok(asset_type)
-    }  
+    }
+
+    pub async fn get_asset_item_count_for_type(&self, id: i32) -> Result<i64, CrudError> {
+        let count = self.crud_repo.get_asset_item_count_for_type(id).await?;
+
+        Ok(count)
+    }
+
+    pub async fn get_asset_type_images(&self, id: i32) -> Result<Vec<AssetImage>, CrudError> {
+        let images = self.crud_repo.get_asset_type_images(id).await?;
+
+        Ok(images)
+    }
 
-    pub async fn update_asset_type_picture(&self, id: i32, picture_url: String) -> Result<Option<AssetType>, CrudError> {
-        let asset_type = self.crud_repo.update_asset_type_picture(id, picture_url)
+    pub async fn add_asset_type_image(&self, id: i32, upload: FieldData<HashedTempFile>, content: &ContentApplicationService, make_primary: bool) -> Result<AssetImage, CrudError> {
+        let attachment = content.upload_image_file_as_attachment(upload)
             .await?;
 
-        Ok(asset_type)
+        let image = self.crud_repo.add_asset_type_image(id, attachment.id, make_primary).await?;
+
+        Ok(image)
+    }
+
+    pub async fn set_asset_type_primary_image(&self, id: i32, image_id: i32) -> Result<Option<i32>, CrudError> {
+        let image = self.crud_repo.set_asset_type_primary_image(id, image_id).await?;
+
+        Ok(image)
+    }
+
+    pub async fn reorder_asset_type_images(&self, id: i32, schema: ReorderImagesSchema) -> Result<Vec<AssetImage>, CrudError> {
+        let current = self.crud_repo.get_asset_type_images(id).await?;
+        let ids = reorder_positions(&current, &schema.ids);
+        self.crud_repo.reorder_asset_type_images(id, &ids).await?;
+
+        let images = self.crud_repo.get_asset_type_images(id).await?;
+
+        Ok(images)
+    }
+
+    pub async fn delete_asset_type_image(&self, id: i32, image_id: i32) -> Result<Option<i32>, CrudError> {
+        let image = self.crud_repo.delete_asset_type_image(id, image_id).await?;
+
+        Ok(image)
+    }
+
+    pub async fn get_asset_item_images(&self, id: i32) -> Result<Vec<AssetImage>, CrudError> {
+        let images = self.crud_repo.get_asset_item_images(id).await?;
+
+        Ok(images)
+    }
+
+    pub async fn add_asset_item_image(&self, id: i32, upload: FieldData<HashedTempFile>, content: &ContentApplicationService, make_primary: bool) -> Result<AssetImage, CrudError> {
+        let attachment = content.upload_image_file_as_attachment(upload)
+            .await?;
+
+        let image = self.crud_repo.add_asset_item_image(id, attachment.id, make_primary).await?;
+
+        Ok(image)
+    }
+
+    pub async fn set_asset_item_primary_image(&self, id: i32, image_id: i32) -> Result<Option<i32>, CrudError> {
+        let image = self.crud_repo.set_asset_item_primary_image(id, image_id).await?;
+
+        Ok(image)
+    }
+
+    pub async fn reorder_asset_item_images(&self, id: i32, schema: ReorderImagesSchema) -> Result<Vec<AssetImage>, CrudError> {
+        let current = self.crud_repo.get_asset_item_images(id).await?;
+        let ids = reorder_positions(&current, &schema.ids);
+        self.crud_repo.reorder_asset_item_images(id, &ids).await?;
+
+        let images = self.crud_repo.get_asset_item_images(id).await?;
+
+        Ok(images)
+    }
+
+    pub async fn delete_asset_item_image(&self, id: i32, image_id: i32) -> Result<Option<i32>, CrudError> {
+        let image = self.crud_repo.delete_asset_item_image(id, image_id).await?;
+
+        Ok(image)
     }
 
     pub async fn upload_asset_types(&self, mut schema: UploadAsetTypesSchema) -> Result<UploadResult, CrudError> {
@@ -129,7 +236,7 @@ impl CrudApplicationService {
         for record in rdr.deserialize() {
             // TODO: skip but log error
             let mut new_asset_type: NewAssetType = record.map_err(|e| CrudError::Unknown(e.into()))?;
-            new_asset_type.picture = Some("/static/images/empty-image.svg".to_string());
+            new_asset_type.picture = Some(EMPTY_IMAGE_URL.to_string());
             if new_asset_type.brand.trim().is_empty() || new_asset_type.model.trim().is_empty() {
                 continue;
             }
@@ -145,23 +252,64 @@ impl CrudApplicationService {
         Ok(upload_result)
     }
 
+    #[allow(dead_code)]
     pub async fn  get_asset_items(&self) -> Result<Vec<AssetItem>, CrudError> {
         let asset_items = self.crud_repo.get_asset_items().await?;
 
         Ok(asset_items)
    }
 
+    pub async fn get_asset_items_search(&self, schema: FilterSchema) -> Result<(Vec<AssetItem>, AssetItemFilter), CrudError> {
+        let asset_item_filter = asset_item_filter(schema)?;
+
+        let asset_items = self.crud_repo.get_asset_items_search(asset_item_filter.clone()).await?;
+
+        Ok((asset_items, asset_item_filter))
+    }
+
+    /// Resolves a scanned asset id, serial number or label link to an asset item
+    pub async fn lookup_asset_item(&self, schema: LookupSchema) -> Result<LookupResult, CrudError> {
+        let raw_code = schema.code.trim().to_string();
+
+        let code = match parse_lookup_code(&raw_code) {
+            Some(LookupCode::ItemId(id)) => {
+                let asset_item = self.crud_repo.get_asset_item_by_id(id).await?;
+                return Ok(match asset_item {
+                    Some(asset_item) => LookupResult::Found(asset_item.id),
+                    None => LookupResult::NotFound(raw_code, None),
+                });
+            },
+            Some(LookupCode::Code(code)) => code,
+            None => return Ok(LookupResult::NotFound(raw_code, None)),
+        };
+
+        let asset_items = self.crud_repo.get_asset_items_by_code(code.clone()).await?;
+
+        let result = match select_code_match(&code, &asset_items) {
+            Some(asset_item) => LookupResult::Found(asset_item.id),
+            None if asset_items.is_empty() => LookupResult::NotFound(raw_code, Some(code)),
+            None => LookupResult::Multiple(raw_code),
+        };
+
+        Ok(result)
+    }
+
    pub async fn add_asset_item(&self, schema: NewAssetItemSchema) -> Result<AssetItemID, CrudError> {
+        let asset_type_id = parse_id(schema.asset_type_id)?;
+        let custom_fields = self.parse_custom_fields(asset_type_id, &custom_form_values(&schema.custom_fields)).await?;
 
         let new_asset_item = NewAssetItem {
             asset_id: schema.asset_id.and_then(|s| if s.is_empty() { None } else { Some(s) }),
             name: schema.name.and_then(|s| if s.is_empty() { None } else { Some(s) }),
             serial_number: schema.serial_number.and_then(|s| if s.is_empty() { None } else { Some(s) }),
-            brand: schema.brand.and_then(|s| if s.is_empty() { None } else { Some(s) }),
-            model: schema.model.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            asset_type_id,
+            brand: None,
+            model: None,
             school: schema.school.and_then(|s| if s.is_empty() { None } else { Some(s) }),
             room: schema.room.and_then(|s| if s.is_empty() { None } else { Some(s) }),
             funding_source: schema.funding_source.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            purchase_date: parse_date(schema.purchase_date)?,
+            custom_fields,
         };
 
         let asset_item_id = self.crud_repo.add_asset_item(new_asset_item)
@@ -170,11 +318,360 @@ impl CrudApplicationService {
         Ok(asset_item_id)
     } 
 
+    pub async fn update_asset_item(&self, id: i32, schema: UpdateAssetItemSchema) -> Result<Option<i32>, CrudError> {
+        let asset_type_id = parse_id(schema.asset_type_id)?;
+
+        let update_asset_item = UpdateAssetItem {
+            asset_id: schema.asset_id.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            name: schema.name.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            serial_number: schema.serial_number.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            asset_type_id,
+            school: schema.school.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            room: schema.room.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            funding_source: schema.funding_source.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            purchase_date: parse_date(schema.purchase_date)?,
+            custom_fields: self.parse_custom_fields(asset_type_id, &custom_form_values(&schema.custom_fields)).await?,
+        };
+
+        let returned_id = self.crud_repo.update_asset_item(id, update_asset_item).await?;
+
+        Ok(returned_id)
+    }
+
+    /// Blank inputs leave the field alone, an update that sets nothing is rejected
+    pub async fn bulk_edit_asset_items(&self, schema: BulkEditSchema, changed_by: Uuid) -> Result<BulkEditSummary, CrudError> {
+        // should be validated in handler
+        let action = match schema.action.as_str() {
+            "delete" => BulkAssetItemAction::Delete,
+            _ => {
+                let update = BulkAssetItemUpdate {
+                    school: schema.school.and_then(|s| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) }),
+                    room: schema.room.and_then(|s| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) }),
+                    funding_source: schema.funding_source.and_then(|s| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) }),
+                    status: schema.status.and_then(|s| if s.is_empty() { None } else { Some(s) }),
+                    asset_type_id: parse_id(schema.asset_type_id)?,
+                };
+
+                if update.is_empty() {
+                    return Err(CrudError::NoChanges);
+                }
+
+                BulkAssetItemAction::Update(update)
+            },
+        };
+
+        let summary = self.crud_repo.bulk_update_asset_items(&schema.ids, action, changed_by).await?;
+
+        Ok(summary)
+    }
+
+    /// Goes through the bulk delete so a single delete leaves the same history entry
+    pub async fn delete_asset_item(&self, id: i32, changed_by: Uuid) -> Result<Option<i32>, CrudError> {
+        let summary = self.crud_repo.bulk_update_asset_items(&[id], BulkAssetItemAction::Delete, changed_by).await?;
+
+        Ok(summary.items.first().map(|i| i.asset_item_id))
+    }
+
+    pub async fn get_asset_item_history(&self, id: i32) -> Result<Vec<AssetItemHistoryEntry>, CrudError> {
+        let history = self.crud_repo.get_asset_item_history(id).await?;
+
+        Ok(history)
+    }
+
+    pub async fn get_asset_item_documents(&self, id: i32) -> Result<Vec<AssetDocument>, CrudError> {
+        let documents = self.crud_repo.get_asset_item_documents(id).await?;
+
+        Ok(documents)
+    }
+
+    pub async fn add_asset_item_document(&self, id: i32, schema: NewDocumentSchema, content: &ContentApplicationService, created_by: Uuid) -> Result<AssetDocument, CrudError> {
+        // should be validated in handler
+        let attachment = content.upload_document_file_as_attachment(schema.upload)
+            .await?;
+
+        let new_document = NewAssetDocument {
+            document_id: attachment.id,
+            kind: schema.kind,
+            description: schema.description.map(|s| s.trim().to_string()).and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            created_by,
+        };
+
+        let document = self.crud_repo.add_asset_item_document(id, new_document).await?;
+
+        Ok(document)
+    }
+
+    pub async fn delete_asset_item_document(&self, id: i32, document_id: i32, changed_by: Uuid) -> Result<Option<i32>, CrudError> {
+        let document = self.crud_repo.delete_asset_item_document(id, document_id, changed_by).await?;
+
+        Ok(document)
+    }
+
+    pub async fn get_asset_type_documents(&self, id: i32) -> Result<Vec<AssetDocument>, CrudError> {
+        let documents = self.crud_repo.get_asset_type_documents(id).await?;
+
+        Ok(documents)
+    }
+
+    pub async fn add_asset_type_document(&self, id: i32, schema: NewDocumentSchema, content: &ContentApplicationService, created_by: Uuid) -> Result<AssetDocument, CrudError> {
+        // should be validated in handler
+        let attachment = content.upload_document_file_as_attachment(schema.upload)
+            .await?;
+
+        let new_document = NewAssetDocument {
+            document_id: attachment.id,
+            kind: schema.kind,
+            description: schema.description.map(|s| s.trim().to_string()).and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            created_by,
+        };
+
+        let document = self.crud_repo.add_asset_type_document(id, new_document).await?;
+
+        Ok(document)
+    }
+
+    pub async fn delete_asset_type_document(&self, id: i32, document_id: i32) -> Result<Option<i32>, CrudError> {
+        let document = self.crud_repo.delete_asset_type_document(id, document_id).await?;
+
+        Ok(document)
+    }
+
+    /// Validates raw values against the asset type's field definitions, items without a type have no custom fields
+    async fn parse_custom_fields(&self, asset_type_id: Option<i32>, raw_values: &HashMap<String, String>) -> Result<Value, CrudError> {
+        let custom_fields = match asset_type_id {
+            Some(asset_type_id) => self.crud_repo.get_custom_fields(asset_type_id).await?,
+            None => Vec::new(),
+        };
+
+        Ok(parse_custom_values(&custom_fields, raw_values)?)
+    }
+
+    pub async fn upload_asset_items(&self, mut schema: UploadAssetItemsSchema) -> Result<UploadResult, CrudError> {
+        let custom_fields = self.crud_repo.get_all_custom_fields().await?;
+        let asset_types = self.crud_repo.get_asset_types().await?;
+
+        let mut rdr = csv::Reader::from_reader(schema.upload.contents.as_file_mut());
+        let headers = rdr.headers().map_err(|e| CrudError::Unknown(e.into()))?.clone();
+
+        let mut rows = Vec::new();
+        let mut row_errors = Vec::new();
+
+        for record in rdr.records() {
+            let record = record.map_err(|e| CrudError::Unknown(e.into()))?;
+            let mut new_asset_item: NewAssetItem = record.deserialize(Some(&headers)).map_err(|e| CrudError::Unknown(e.into()))?;
+
+            if let Err(e) = new_asset_item.resolve_asset_type(&asset_types) {
+                let line = record.position().map(|p| p.line()).unwrap_or_default();
+                row_errors.push(format!("line {}: {}", line, e));
+                continue;
+            }
+
+            // custom field columns are headed by the field name, only the row's asset type fields apply
+            let raw_values: HashMap<String, String> = headers.iter()
+                .zip(record.iter())
+                .map(|(header, value)| (header.to_string(), value.to_string()))
+                .collect();
+            let type_fields: Vec<CustomField> = custom_fields.iter()
+                .filter(|f| Some(f.asset_type_id) == new_asset_item.asset_type_id)
+                .cloned()
+                .collect();
+
+            new_asset_item.custom_fields = parse_custom_values(&type_fields, &raw_values)?;
+            rows.push(new_asset_item);
+        }
+
+        if !row_errors.is_empty() {
+            return Err(CrudError::ImportRows(row_errors));
+        }
+
+        let upload_result = self.crud_repo.bulk_add_asset_items(&rows).await?;
+
+        Ok(upload_result)
+    }
+
+    pub async fn get_custom_fields(&self, asset_type_id: i32) -> Result<Vec<CustomField>, CrudError> {
+        let custom_fields = self.crud_repo.get_custom_fields(asset_type_id).await?;
+
+        Ok(custom_fields)
+    }
+
+    pub async fn get_all_custom_fields(&self) -> Result<Vec<CustomField>, CrudError> {
+        let custom_fields = self.crud_repo.get_all_custom_fields().await?;
+
+        Ok(custom_fields)
+    }
+
+    pub async fn add_custom_field(&self, asset_type_id: i32, schema: NewCustomFieldSchema) -> Result<CustomField, CrudError> {
+        // should be validated in handler
+        let options = schema.options
+            .map(|o| o.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+
+        let new_custom_field = NewCustomField {
+            asset_type_id,
+            name: field_name_from_label(&schema.label)?,
+            label: schema.label.trim().to_string(),
+            field_type: schema.field_type,
+            options,
+            required: schema.required.is_some_and(|r| r == "on" || r == "true"),
+        };
+
+        let custom_field = self.crud_repo.add_custom_field(new_custom_field).await?;
+
+        Ok(custom_field)
+    }
+
+    pub async fn delete_custom_field(&self, asset_type_id: i32, id: i32) -> Result<Option<i32>, CrudError> {
+        let returned_id = self.crud_repo.delete_custom_field(asset_type_id, id).await?;
+
+        Ok(returned_id)
+    }
+
+    pub async fn get_categories(&self) -> Result<Vec<Category>, CrudError> {
+        let categories = self.crud_repo.get_categories().await?;
+
+        Ok(categories)
+    }
+
+    pub async fn get_category(&self, id: i32) -> Result<Option<Category>, CrudError> {
+        let category = self.crud_repo.get_category_by_id(id).await?;
+
+        Ok(category)
+    }
+
+    pub async fn add_category(&self, schema: NewCategorySchema) -> Result<i32, CrudError> {
+        // should be validated in handler
+        let new_category = NewCategory {
+            parent_id: parse_id(schema.parent_id)?,
+            name: schema.name.trim().to_string(),
+            useful_life_years: parse_useful_life(schema.useful_life_years)?,
+        };
+
+        let id = self.crud_repo.add_category(new_category).await?;
+
+        Ok(id)
+    }
+
+    pub async fn update_category(&self, id: i32, schema: UpdateCategorySchema) -> Result<Option<i32>, CrudError> {
+        let parent_id = parse_id(schema.parent_id)?;
+
+        // moving a category under one of its own descendants would detach the subtree
+        if let Some(parent_id) = parent_id {
+            let parent = self.crud_repo.get_category_by_id(parent_id).await?
+                .ok_or(CategoryError::UnknownParent)?;
+            validate_parent(id, Some(&parent))?;
+        }
+
+        let update_category = UpdateCategory {
+            parent_id,
+            name: schema.name.trim().to_string(),
+            useful_life_years: parse_useful_life(schema.useful_life_years)?,
+        };
+
+        let returned_id = self.crud_repo.update_category(id, update_category).await?;
+
+        Ok(returned_id)
+    }
+
+    pub async fn delete_category(&self, id: i32) -> Result<Option<i32>, CrudError> {
+        let returned_id = self.crud_repo.delete_category(id).await?;
+
+        Ok(returned_id)
+    }
+
+    pub async fn get_asset_type_count_for_category(&self, id: i32) -> Result<i64, CrudError> {
+        let count = self.crud_repo.get_asset_type_count_for_category(id).await?;
+
+        Ok(count)
+    }
+
+    pub async fn get_category_fields(&self, category_id: i32) -> Result<Vec<CategoryField>, CrudError> {
+        let category_fields = self.crud_repo.get_category_fields(category_id).await?;
+
+        Ok(category_fields)
+    }
+
+    pub async fn add_category_field(&self, category_id: i32, schema: NewCustomFieldSchema) -> Result<CategoryField, CrudError> {
+        // should be validated in handler
+        let options = schema.options
+            .map(|o| o.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+
+        let new_category_field = NewCategoryField {
+            category_id,
+            name: field_name_from_label(&schema.label)?,
+            label: schema.label.trim().to_string(),
+            field_type: schema.field_type,
+            options,
+            required: schema.required.is_some_and(|r| r == "on" || r == "true"),
+        };
+
+        let category_field = self.crud_repo.add_category_field(new_category_field).await?;
+
+        Ok(category_field)
+    }
+
+    pub async fn delete_category_field(&self, category_id: i32, id: i32) -> Result<Option<i32>, CrudError> {
+        let returned_id = self.crud_repo.delete_category_field(category_id, id).await?;
+
+        Ok(returned_id)
+    }
+
     pub async fn  get_asset_item(&self, id: i32) -> Result<Option<AssetItem>, CrudError> {
         let asset_item = self.crud_repo.get_asset_item_by_id(id).await?;
 
         Ok(asset_item)
     }
+
+    pub async fn get_depreciation_report(&self, schema: DepreciationReportSchema) -> Result<DepreciationReport, CrudError> {
+        // report on today's book value unless a filing date is given
+        let as_of = parse_date(schema.as_of)?.unwrap_or_else(|| Utc::now().date_naive());
+        let asset_items = self.crud_repo.get_depreciable_asset_items().await?;
+
+        Ok(DepreciationReport::build(&asset_items, as_of))
+    }
+}
+
+// form values are validated in the handler, these only convert them
+fn parse_cost(value: Option<String>) -> Result<Option<Decimal>, CrudError> {
+    match value {
+        Some(value) => parse_money(&value).map_err(|e| CrudError::Unknown(e.into())),
+        None => Ok(None),
+    }
+}
+
+fn parse_useful_life(value: Option<String>) -> Result<Option<i32>, CrudError> {
+    match value.as_deref().map(str::trim) {
+        Some("") | None => Ok(None),
+        Some(years) => years.parse::<i32>().map(Some).map_err(|e| CrudError::Unknown(e.into())),
+    }
+}
+
+/// Also used for saved views, which store the same filter as the asset items page
+pub fn asset_item_filter(schema: FilterSchema) -> Result<AssetItemFilter, CrudError> {
+    // the html input may return Some("") when empty
+    Ok(AssetItemFilter {
+        search: schema.search.and_then(|s| if s.is_empty() {None} else {Some(s)}),
+        sort: schema.sort.and_then(|s| if s.is_empty() {None} else {Some(s)}),
+        order: schema.order.and_then(|s| if s.is_empty() {None} else {Some(s.to_uppercase())}),
+        category_id: parse_id(schema.category_id)?,
+        school: schema.school.and_then(|s| if s.is_empty() {None} else {Some(s)}),
+        status: schema.status.and_then(|s| if s.is_empty() {None} else {Some(s)}),
+    })
+}
+
+fn parse_id(value: Option<String>) -> Result<Option<i32>, CrudError> {
+    match value.as_deref() {
+        Some("") | None => Ok(None),
+        Some(id) => id.parse::<i32>().map(Some).map_err(|e| CrudError::Unknown(e.into())),
+    }
+}
+
+fn parse_date(value: Option<String>) -> Result<Option<NaiveDate>, CrudError> {
+    match value.as_deref() {
+        Some("") | None => Ok(None),
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map(Some).map_err(|e| CrudError::Unknown(e.into())),
+    }
 }
 
 
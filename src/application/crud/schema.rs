@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+
 use axum_typed_multipart::{FieldData, TryFromMultipart};
 use serde::{Deserialize, Serialize};
 use tempfile::NamedTempFile;
 use garde::Validate;
 
+use crate::domain::{crud::model::{asset_items::ASSET_ITEM_STATUSES, custom_fields::CUSTOM_FIELD_TYPES, documents::DOCUMENT_KINDS, money::parse_money}, filesystem::models::HashedTempFile};
+
 
 #[derive(Debug, Validate, TryFromMultipart)]
 pub struct NewAssetTypeSchema {
@@ -12,11 +16,15 @@ pub struct NewAssetTypeSchema {
     pub model: String,
     #[garde(skip)]	
     pub description: Option<String>,
-    #[garde(skip)]	
+    #[garde(custom(validate_money))]
     pub cost: Option<String>,
+    #[garde(custom(validate_useful_life))]
+    pub useful_life_years: Option<String>,
+    #[garde(custom(validate_id))]
+    pub category_id: Option<String>,
     #[garde(skip)]	
-    #[form_data(limit = "5MiB")]
-    pub picture: Option<FieldData<NamedTempFile>>,
+    #[form_data(limit = "unlimited")]
+    pub picture: Option<FieldData<HashedTempFile>>,
 }
 
 #[derive(Debug, Validate, TryFromMultipart)]
@@ -27,17 +35,38 @@ pub struct UpdateAssetTypeSchema {
     pub model: String,
     #[garde(skip)]	
     pub description: Option<String>,
-    #[garde(skip)]	
+    #[garde(custom(validate_money))]
     pub cost: Option<String>,
+    #[garde(custom(validate_useful_life))]
+    pub useful_life_years: Option<String>,
+    #[garde(custom(validate_id))]
+    pub category_id: Option<String>,
     #[garde(skip)]	
-    #[form_data(limit = "5MiB")]
-    pub picture: Option<FieldData<NamedTempFile>>,
+    #[form_data(limit = "unlimited")]
+    pub picture: Option<FieldData<HashedTempFile>>,
+}
+
+fn validate_money(value: &Option<String>, _: &()) -> garde::Result {
+    match value.as_deref().map(parse_money) {
+        Some(Err(e)) => Err(garde::Error::new(e.to_string())),
+        _ => Ok(()),
+    }
+}
+
+fn validate_useful_life(value: &Option<String>, _: &()) -> garde::Result {
+    match value.as_deref().map(str::trim) {
+        Some("") | None => Ok(()),
+        Some(years) => match years.parse::<i32>() {
+            Ok(years) if years > 0 => Ok(()),
+            _ => Err(garde::Error::new("useful life must be a whole number of years")),
+        },
+    }
 }
 
 #[derive(Debug, Validate, TryFromMultipart)]
 pub struct UploadAsetTypesSchema {
     #[garde(skip)]	
-    #[form_data(limit = "5MiB")]
+    #[form_data(limit = "unlimited")]
     pub upload: FieldData<NamedTempFile>,
     #[garde(custom(is_valid_method))]
     pub method: String,
@@ -59,6 +88,27 @@ pub struct FilterSchema {
     pub sort: Option<String>,
     #[garde(custom(validate_order))]
     pub order: Option<String>,
+    #[garde(custom(validate_id))]
+    pub category_id: Option<String>,
+    #[garde(skip)]
+    pub school: Option<String>,
+    #[garde(custom(validate_status))]
+    pub status: Option<String>,
+    /// comma separated asset item columns to show, see ColumnSelection
+    #[garde(skip)]
+    pub columns: Option<String>,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct LookupSchema {
+    #[garde(length(min=1))]
+    pub code: String,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct AssetItemPrefillSchema {
+    #[garde(skip)]
+    pub serial_number: Option<String>,
 }
 
 fn validate_order(value: &Option<String>, _: &()) -> garde::Result {
@@ -82,16 +132,20 @@ pub struct NewAssetItemSchema {
     pub name: Option<String>,
     #[garde(skip)]	
     pub serial_number: Option<String>,
-    #[garde(skip)]	
-    pub brand: Option<String>,
-    #[garde(skip)]	
-    pub model: Option<String>,
+    #[garde(custom(validate_id))]
+    pub asset_type_id: Option<String>,
     #[garde(skip)]	
     pub school: Option<String>,
     #[garde(skip)]	
     pub room: Option<String>,
     #[garde(skip)]	
     pub funding_source: Option<String>,
+    #[garde(custom(validate_date))]
+    pub purchase_date: Option<String>,
+    /// "cf_" prefixed custom field inputs, validated against the asset type's definitions in the service
+    #[garde(skip)]
+    #[serde(flatten)]
+    pub custom_fields: HashMap<String, String>,
 }
 
 #[derive(Debug, Validate, Deserialize, Serialize)]
@@ -102,15 +156,156 @@ pub struct UpdateAssetItemSchema {
     pub name: Option<String>,
     #[garde(skip)]	
     pub serial_number: Option<String>,
-    #[garde(skip)]	
-    pub brand: Option<String>,
-    #[garde(skip)]	
-    pub model: Option<String>,
+    #[garde(custom(validate_id))]
+    pub asset_type_id: Option<String>,
     #[garde(skip)]	
     pub school: Option<String>,
     #[garde(skip)]	
     pub room: Option<String>,
     #[garde(skip)]	
     pub funding_source: Option<String>,
+    #[garde(custom(validate_date))]
+    pub purchase_date: Option<String>,
+    #[garde(skip)]
+    #[serde(flatten)]
+    pub custom_fields: HashMap<String, String>,
+}
+
+#[derive(Debug, Validate, TryFromMultipart)]
+pub struct UploadAssetItemsSchema {
+    #[garde(skip)]
+    #[form_data(limit = "unlimited")]
+    pub upload: FieldData<NamedTempFile>,
+}
+
+#[derive(Debug, Validate, TryFromMultipart)]
+pub struct NewDocumentSchema {
+    #[garde(custom(validate_document_kind))]
+    pub kind: String,
+    #[garde(skip)]
+    pub description: Option<String>,
+    #[garde(skip)]
+    #[form_data(limit = "unlimited")]
+    pub upload: FieldData<HashedTempFile>,
+}
+
+fn validate_document_kind(value: &str, _: &()) -> garde::Result {
+    match DOCUMENT_KINDS.contains(&value) {
+        true => Ok(()),
+        false => Err(garde::Error::new("invalid document kind")),
+    }
+}
+
+/// Submitted with axum_extra's Form so the repeated "ids" checkboxes collect into a list
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct BulkEditSchema {
+    #[garde(length(min=1))]
+    #[serde(default)]
+    pub ids: Vec<i32>,
+    #[garde(custom(validate_bulk_action))]
+    pub action: String,
+    #[garde(skip)]
+    pub school: Option<String>,
+    #[garde(skip)]
+    pub room: Option<String>,
+    #[garde(skip)]
+    pub funding_source: Option<String>,
+    #[garde(custom(validate_status))]
+    pub status: Option<String>,
+    #[garde(custom(validate_id))]
+    pub asset_type_id: Option<String>,
+}
+
+fn validate_bulk_action(value: &str, _: &()) -> garde::Result {
+    match value {
+        "update" => Ok(()),
+        "delete" => Ok(()),
+        _ => Err(garde::Error::new("invalid bulk action")),
+    }
+}
+
+fn validate_status(value: &Option<String>, _: &()) -> garde::Result {
+    match value.as_deref() {
+        Some("") | None => Ok(()),
+        Some(status) if ASSET_ITEM_STATUSES.contains(&status) => Ok(()),
+        Some(_) => Err(garde::Error::new("invalid status")),
+    }
+}
+
+/// Gallery entry ids in their new order, submitted with axum_extra's Form
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct ReorderImagesSchema {
+    #[garde(length(min=1))]
+    #[serde(default)]
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct NewCustomFieldSchema {
+    #[garde(length(min=1))]
+    pub label: String,
+    #[garde(custom(validate_field_type))]
+    pub field_type: String,
+    /// comma separated choices for select fields
+    #[garde(skip)]
+    pub options: Option<String>,
+    #[garde(skip)]
+    pub required: Option<String>,
 }
 
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct NewCategorySchema {
+    #[garde(length(min=1))]
+    pub name: String,
+    #[garde(custom(validate_id))]
+    pub parent_id: Option<String>,
+    #[garde(custom(validate_useful_life))]
+    pub useful_life_years: Option<String>,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct UpdateCategorySchema {
+    #[garde(length(min=1))]
+    pub name: String,
+    #[garde(custom(validate_id))]
+    pub parent_id: Option<String>,
+    #[garde(custom(validate_useful_life))]
+    pub useful_life_years: Option<String>,
+}
+
+fn validate_field_type(value: &str, _: &()) -> garde::Result {
+    match CUSTOM_FIELD_TYPES.contains(&value) {
+        true => Ok(()),
+        false => Err(garde::Error::new("invalid field type")),
+    }
+}
+
+fn validate_date(value: &Option<String>, _: &()) -> garde::Result {
+    match value.as_deref() {
+        Some("") | None => Ok(()),
+        Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(|_| ())
+            .map_err(|_| garde::Error::new("invalid date, expected YYYY-MM-DD")),
+    }
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct DepreciationReportSchema {
+    #[garde(custom(validate_date))]
+    pub as_of: Option<String>,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct DeleteAssetTypeSchema {
+    #[garde(custom(validate_id))]
+    pub reassign_to: Option<String>,
+}
+
+pub(crate) fn validate_id(value: &Option<String>, _: &()) -> garde::Result {
+    match value.as_deref() {
+        Some("") | None => Ok(()),
+        Some(id) => id.parse::<i32>()
+            .map(|_| ())
+            .map_err(|_| garde::Error::new("invalid id")),
+    }
+}
@@ -1,7 +1,6 @@
 use thiserror::Error;
 use axum::{http::StatusCode, response::{IntoResponse, Redirect, Response}};
 
-use super::identityaccess::identity_application_service::IdentityError;
 
 
 #[derive(Debug, Error)]
@@ -11,6 +10,7 @@ pub enum ApplicationError {
     #[error(transparent)]
     NotFound(anyhow::Error),
     #[error("{0}")]
+    #[allow(dead_code)]
     UnprocessableEntity(anyhow::Error, String),
     #[error("{0}")]
     BadRequest(anyhow::Error, String),
@@ -29,6 +29,7 @@ impl ApplicationError {
         ApplicationError::Forbidden(error, error_resp.to_string())
     }
 
+    #[allow(dead_code)]
     pub fn unprocessable_entity(error: anyhow::Error, error_resp: impl ToString) -> Self {
         ApplicationError::UnprocessableEntity(error, error_resp.to_string())
     }
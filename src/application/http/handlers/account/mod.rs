@@ -3,23 +3,31 @@ mod roles;
 mod users;
 mod user_create;
 mod user_edit;
+mod storage_checks;
 
 use axum::{extract::DefaultBodyLimit, middleware, response::Redirect, routing::{delete, get, post}, Router};
 
-use crate::application::{http::utils, state::AppState};
+use crate::{application::{http::utils, state::AppState}, settings::UploadsConfig};
 
-pub fn router() -> Router<AppState>
+pub fn router(uploads: &UploadsConfig) -> Router<AppState>
 {
+    let admin_router = Router::new()
+        .route("/settings/storage", get(self::storage_checks::get_storage_checks))
+        .route("/settings/storage/checks", post(self::storage_checks::post_storage_check))
+        .route("/settings/storage/checks/:check_id", get(self::storage_checks::get_storage_check))
+        .route_layer(middleware::from_fn(utils::admin_required));
+
     Router::new()
         .route("/", get(|| async { Redirect::permanent("/settings") }))
         .route("/settings", get(self::settings::get_settings))
         .route("/settings/roles", get(self::roles::get_roles))
         .route("/settings/users", get(self::users::get_users))
         .route("/settings/users/new", get(self::user_create::get_user_create))
-        .route("/settings/users/new", post(self::user_create::post_user_create).layer(DefaultBodyLimit::max(5242880)))
+        .route("/settings/users/new", post(self::user_create::post_user_create).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
         .route("/settings/users/:user_id/edit", get(self::user_edit::get_user_edit))
-        .route("/settings/users/:user_id/edit", post(self::user_edit::post_user_edit).layer(DefaultBodyLimit::max(5242880)))
+        .route("/settings/users/:user_id/edit", post(self::user_edit::post_user_edit).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
         .route("/settings/users/:user_id", delete(self::user_edit::delete_user))
-        .route("/settings/users/:user_id/change_picture", post(self::user_edit::post_change_user_picture).layer(DefaultBodyLimit::max(5242880))) //5MiB
+        .route("/settings/users/:user_id/change_picture", post(self::user_edit::post_change_user_picture).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
+        .merge(admin_router)
         .route_layer(middleware::from_fn(utils::login_required))
 }
\ No newline at end of file
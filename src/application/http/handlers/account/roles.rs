@@ -4,7 +4,7 @@ use axum_login::AuthSession;
 use serde::Deserialize;
 use tracing::instrument;
 
-use crate::{application::{errors::ApplicationError, identityaccess::identity_application_service::IdentityApplicationService, templates::partials::users::roles_list::RolesListTemplate}, domain::identityaccess::model::user_repository::UserRepository};
+use crate::application::{errors::ApplicationError, identityaccess::identity_application_service::IdentityApplicationService, templates::partials::users::roles_list::RolesListTemplate};
 
 
 #[derive(Deserialize)]
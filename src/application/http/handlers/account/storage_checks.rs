@@ -0,0 +1,80 @@
+use anyhow::anyhow;
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, Extension, Form};
+use axum_messages::Messages;
+use garde::Report;
+use tracing::instrument;
+
+use crate::{application::{content::{content_application_service::ContentError, schema::StorageCheckSchema}, errors::ApplicationError, state::AppState, templates::{pages::storage_checks::StorageChecksTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::users::SessionUser};
+
+
+#[instrument(skip_all)]
+pub async fn get_storage_checks(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let checks = state.content_service.get_storage_checks()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let selected = checks.first().cloned();
+
+    Ok(([("Cache-Control", "no-store")], StorageChecksTemplate::new(session_user, message, checks, selected)))
+}
+
+#[instrument(skip_all)]
+pub async fn get_storage_check(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Path(check_id): Path<i32>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let selected = match state.content_service.get_storage_check(check_id).await {
+        Ok(check) => check,
+        Err(ContentError::Missing) => return Err(ApplicationError::not_found(anyhow!("storage check not found"))),
+        Err(e) => return Err(ApplicationError::internal_server_error(anyhow!(e))),
+    };
+
+    let checks = state.content_service.get_storage_checks()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], StorageChecksTemplate::new(session_user, message, checks, Some(selected))))
+}
+
+#[instrument(skip_all)]
+pub async fn post_storage_check(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(storage_check): Form<StorageCheckSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    let check = match state.content_service.start_storage_check(storage_check.quarantine, Some(session_user.user.id)).await {
+        Ok(check) => check,
+        Err(ContentError::CheckRunning) => {
+            let mut report = Report::new();
+            report.append(garde::Path::new("check"), garde::Error::new("a storage check is already running, wait for it to finish"));
+            return Err(ApplicationError::bad_request(anyhow!("check running"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    };
+
+    messages.success("storage check started");
+    Ok(([("HX-Redirect", format!("/settings/storage/checks/{}", check.id))], "success"))
+}
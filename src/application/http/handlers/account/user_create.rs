@@ -3,11 +3,10 @@ use askama_axum::IntoResponse;
 use axum::{extract::State, Extension};
 use axum_login::AuthSession;
 use axum_messages::Messages;
-use axum_typed_multipart::TypedMultipart;
 use garde::{Report, Validate};
 use tracing::instrument;
 
-use crate::{application::{errors::ApplicationError, identityaccess::{identity_application_service::{IdentityApplicationService, IdentityError}, schema::NewUserSchema}, state::AppState, templates::{pages::user_create::UserCreateTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::{user_repository::UserRepositoryError, users::SessionUser}};
+use crate::{application::{http::multipart::UploadMultipart, content::content_application_service::ContentError, errors::ApplicationError, identityaccess::{identity_application_service::{IdentityApplicationService, IdentityError}, schema::NewUserSchema}, state::AppState, templates::{pages::user_create::UserCreateTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::{user_repository::UserRepositoryError, users::SessionUser}};
 
 
 #[instrument(skip_all)]
@@ -29,7 +28,7 @@ pub async fn post_user_create(
     auth_session: AuthSession<IdentityApplicationService>,
     messages: Messages,
     State(state): State<AppState>,
-    TypedMultipart(new_user): TypedMultipart<NewUserSchema>,
+    UploadMultipart { data: new_user, .. }: UploadMultipart<NewUserSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
 
     if let Err(report) = new_user.validate() {
@@ -38,6 +37,12 @@ pub async fn post_user_create(
 
     if let Err(e) = auth_session.backend.add_user(new_user, &state.content_service).await {
         match e {
+            IdentityError::Content(ContentError::InvalidContent(reason)) => {
+                let mut report = Report::new();
+                report.append(garde::Path::new("picture"), garde::Error::new(reason));
+
+                return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+            }
             IdentityError::User(UserRepositoryError::Duplicate) => {
                 let mut report = Report::new();
                 report.append(garde::Path::new("email"), garde::Error::new("duplicate email address"));
@@ -1,14 +1,13 @@
 use anyhow::anyhow;
 use askama_axum::IntoResponse;
-use axum::{extract::{Path, State}, Extension, Form};
+use axum::{extract::{Path, State}, Extension};
 use axum_login::{AuthSession, AuthnBackend};
 use axum_messages::Messages;
-use axum_typed_multipart::TypedMultipart;
 use garde::{Report, Validate};
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::{application::{content::schema::ImageSchema, errors::ApplicationError, identityaccess::{identity_application_service::{IdentityApplicationService, IdentityError}, schema::UpdateUserSchema}, state::AppState, templates::{pages::user_edit::UserEditTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::{user_repository::{UserRepository, UserRepositoryError}, users::SessionUser}};
+use crate::{application::{http::multipart::UploadMultipart, content::{content_application_service::ContentError, schema::ImageSchema}, errors::ApplicationError, identityaccess::{identity_application_service::{IdentityApplicationService, IdentityError}, schema::UpdateUserSchema}, state::AppState, templates::{pages::user_edit::UserEditTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::{user_repository::UserRepositoryError, users::SessionUser}};
 
 
 #[instrument(skip_all)]
@@ -39,7 +38,7 @@ pub async fn post_user_edit(
     Extension(session_user): Extension<SessionUser>,
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
-    TypedMultipart(update_user): TypedMultipart<UpdateUserSchema>,
+    UploadMultipart { data: update_user, .. }: UploadMultipart<UpdateUserSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
 
     if let Err(report) = update_user.validate() {
@@ -56,6 +55,12 @@ pub async fn post_user_edit(
         },
         Err(e) => {
             match e {
+                IdentityError::Content(ContentError::InvalidContent(reason)) => {
+                    let mut report = Report::new();
+                    report.append(garde::Path::new("picture"), garde::Error::new(reason));
+
+                    return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+                }
                 IdentityError::User(UserRepositoryError::Duplicate) => {
                     let mut report = Report::new();
                     report.append(garde::Path::new("email"), garde::Error::new("duplicate email address"));
@@ -77,13 +82,17 @@ pub async fn post_user_edit(
 pub async fn post_change_user_picture(
     Path(user_id): Path<Uuid>,
     State(state): State<AppState>,
-    TypedMultipart(ImageSchema{image} ): TypedMultipart<ImageSchema>,
+    UploadMultipart { data: ImageSchema{image}, .. }: UploadMultipart<ImageSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
     let attachment = state.content_service.upload_image_file_as_attachment(image)
         .await
         .map_err(|e| {
+            let message = match &e {
+                ContentError::InvalidContent(reason) => reason.clone(),
+                _ => "something went wrong during image upload".to_string(),
+            };
             let mut report = Report::new();
-            report.append(garde::Path::new("profile picture"), garde::Error::new("something went wrong during image upload"));
+            report.append(garde::Path::new("profile picture"), garde::Error::new(message));
 
             ApplicationError::bad_request(e.into(), FormAlertTemplate::global_new(report).to_string())
         })?;
@@ -113,6 +122,12 @@ pub async fn delete_user(
         },
         Err(e) => {
             match e {
+                IdentityError::Content(ContentError::InvalidContent(reason)) => {
+                    let mut report = Report::new();
+                    report.append(garde::Path::new("picture"), garde::Error::new(reason));
+
+                    return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+                }
                 IdentityError::User(UserRepositoryError::Duplicate) => {
                     let mut report = Report::new();
                     report.append(garde::Path::new("email"), garde::Error::new("duplicate email address"));
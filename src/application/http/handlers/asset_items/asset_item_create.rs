@@ -1,17 +1,19 @@
 use anyhow::anyhow;
 use askama_axum::IntoResponse;
-use axum::{extract::State, Extension, Form};
+use axum::{extract::{Query, State}, Extension, Form};
 use axum_messages::Messages;
 use garde::{Report, Validate};
 use tracing::instrument;
 
-use crate::{application::{crud::schema::NewAssetItemSchema, errors::ApplicationError, state::AppState, templates::{pages::asset_item_create::AssetItemCreateTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::{CrudRepository, CrudRepositoryError}, identityaccess::model::users::SessionUser}};
+use crate::{application::{crud::schema::{AssetItemPrefillSchema, NewAssetItemSchema}, errors::ApplicationError, state::AppState, templates::{pages::asset_item_create::AssetItemCreateTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
 
 
 #[instrument(skip_all)]
 pub async fn get_asset_item_create(
     messages: Messages,
+    State(state): State<AppState>,
     Extension(session_user): Extension<SessionUser>,
+    Query(prefill): Query<AssetItemPrefillSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
     let message = messages
         .into_iter()
@@ -19,7 +21,11 @@ pub async fn get_asset_item_create(
         .first()
         .map(|m| m.to_owned());
 
-    Ok(([("Cache-Control", "no-store")], AssetItemCreateTemplate::new(session_user, message)))
+    let asset_types = state.crud_service.get_asset_types()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], AssetItemCreateTemplate::new(session_user, message, asset_types, prefill.serial_number)))
 
 }
 
@@ -42,7 +48,11 @@ pub async fn post_asset_item_create(
                 return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
             },
             crate::application::crud::crud_application_service::CrudError::Repo(CrudRepositoryError::Reference) => {
-                report.append(garde::Path::new("brand/model"), garde::Error::new("reference asset type not found"));
+                report.append(garde::Path::new("asset_type_id"), garde::Error::new("reference asset type not found"));
+                return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+            },
+            crate::application::crud::crud_application_service::CrudError::CustomField(e) => {
+                report.append(garde::Path::new("custom_fields"), garde::Error::new(e.to_string()));
                 return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
             },
             _ => {
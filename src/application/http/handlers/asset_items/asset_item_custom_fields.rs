@@ -0,0 +1,33 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::extract::{Query, State};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tracing::instrument;
+
+use crate::application::{errors::ApplicationError, state::AppState, templates::partials::custom_fields_form::CustomFieldsFormTemplate};
+
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+    pub asset_type_id: Option<String>,
+}
+
+/// Swapped into the item create and edit forms when the asset type select changes
+#[instrument(skip_all)]
+pub async fn get_asset_item_custom_fields(
+    State(state): State<AppState>,
+    Query(params): Query<Params>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let custom_fields = match params.asset_type_id.as_deref().map(str::parse::<i32>) {
+        Some(Ok(asset_type_id)) => {
+            state.crud_service.get_custom_fields(asset_type_id)
+                .await
+                .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
+        },
+        _ => Vec::new(),
+    };
+
+    Ok(CustomFieldsFormTemplate::new(custom_fields, Value::Object(Map::new())))
+}
@@ -0,0 +1,70 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, Extension};
+use axum_messages::Messages;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{http::multipart::UploadMultipart, content::content_application_service::ContentError, crud::{crud_application_service::CrudError, schema::NewDocumentSchema}, errors::ApplicationError, state::AppState, templates::partials::form_alert::FormAlertTemplate}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
+
+
+#[instrument(skip_all)]
+pub async fn post_asset_item_document(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Path(id): Path<i32>,
+    UploadMultipart { data: new_document, .. }: UploadMultipart<NewDocumentSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    if let Err(report) = new_document.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    match state.crud_service.add_asset_item_document(id, new_document, &state.content_service, session_user.user.id).await {
+        Ok(_) => { },
+        Err(CrudError::Content(ContentError::InvalidContent(reason))) => {
+            report.append(garde::Path::new("upload"), garde::Error::new(reason));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Content(e)) => {
+            tracing::error!("{:?}", e);
+            report.append(garde::Path::new("upload"), garde::Error::new("the file could not be uploaded"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Duplicate)) => {
+            report.append(garde::Path::new("upload"), garde::Error::new("this document is already attached to the asset item"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
+
+    messages.success("document attached");
+    Ok(([("HX-Redirect", format!("/asset_items/{}", id))], "success"))
+}
+
+#[instrument(skip_all)]
+pub async fn delete_asset_item_document(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Path((id, document_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    let returned_id = state.crud_service.delete_asset_item_document(id, document_id, session_user.user.id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    if returned_id.is_none() {
+        let mut report = Report::new();
+        report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not remove document"));
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    messages.success("document removed");
+    Ok(([("HX-Redirect", format!("/asset_items/{}", id))], "success"))
+}
@@ -0,0 +1,101 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::extract::{Path, State};
+use axum_extra::extract::Form;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::application::{http::multipart::UploadMultipart, content::{content_application_service::ContentError, schema::ImageSchema}, crud::{crud_application_service::CrudError, schema::ReorderImagesSchema}, errors::ApplicationError, state::AppState, templates::partials::{form_alert::FormAlertTemplate, gallery::GalleryTemplate}};
+
+
+#[instrument(skip_all)]
+pub async fn post_asset_item_image(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    UploadMultipart { data: ImageSchema{image}, .. }: UploadMultipart<ImageSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    let mut report = Report::new();
+    match state.crud_service.add_asset_item_image(id, image, &state.content_service, false).await {
+        Ok(_) => { },
+        Err(CrudError::Content(ContentError::InvalidContent(reason))) => {
+            report.append(garde::Path::new("image"), garde::Error::new(reason));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Content(e)) => {
+            tracing::error!("{:?}", e);
+            report.append(garde::Path::new("image"), garde::Error::new("the image could not be uploaded"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
+
+    gallery(&state, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn post_asset_item_images_order(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(reorder): Form<ReorderImagesSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    if let Err(report) = reorder.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let images = state.crud_service.reorder_asset_item_images(id, reorder)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(GalleryTemplate::new(format!("/asset_items/{}", id), images))
+}
+
+#[instrument(skip_all)]
+pub async fn post_asset_item_primary_image(
+    State(state): State<AppState>,
+    Path((id, image_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    let returned_id = state.crud_service.set_asset_item_primary_image(id, image_id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    if returned_id.is_none() {
+        let mut report = Report::new();
+        report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not change the primary image"));
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    gallery(&state, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn delete_asset_item_image(
+    State(state): State<AppState>,
+    Path((id, image_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    let returned_id = state.crud_service.delete_asset_item_image(id, image_id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    if returned_id.is_none() {
+        let mut report = Report::new();
+        report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not remove image"));
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    gallery(&state, id).await
+}
+
+async fn gallery(state: &AppState, id: i32) -> Result<GalleryTemplate, ApplicationError> {
+    let images = state.crud_service.get_asset_item_images(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(GalleryTemplate::new(format!("/asset_items/{}", id), images))
+}
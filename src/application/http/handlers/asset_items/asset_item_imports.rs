@@ -0,0 +1,67 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::State, Extension};
+use axum_messages::Messages;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{http::multipart::UploadMultipart, content::content_application_service::UploadCategory, crud::{crud_application_service::CrudError, schema::UploadAssetItemsSchema}, errors::ApplicationError, state::AppState, templates::{pages::asset_items_imports_new::AssetItemsImportsNew, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
+
+
+#[instrument(skip_all)]
+pub async fn get_asset_item_imports_new(
+    messages: Messages,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    Ok(([("Cache-Control", "no-store")], AssetItemsImportsNew::new(session_user, message)))
+}
+
+#[instrument(skip_all)]
+pub async fn post_asset_item_imports_new(
+    messages: Messages,
+    State(state): State<AppState>,
+    UploadMultipart { data: upload_asset_items, .. }: UploadMultipart<UploadAssetItemsSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    if let Err(report) = upload_asset_items.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    if let Err(e) = state.content_service.check_file_upload(UploadCategory::Imports, &upload_asset_items.upload) {
+        let mut report = Report::new();
+        report.append(garde::Path::new("upload"), garde::Error::new(e.to_string()));
+        return Err(ApplicationError::bad_request(anyhow!("invalid upload"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    let res = match state.crud_service.upload_asset_items(upload_asset_items).await {
+        Ok(res) => res,
+        Err(CrudError::ImportRows(row_errors)) => {
+            for row_error in row_errors {
+                report.append(garde::Path::new("upload"), garde::Error::new(row_error));
+            }
+            return Err(ApplicationError::bad_request(anyhow!("invalid upload"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::CustomField(e)) => {
+            report.append(garde::Path::new("upload"), garde::Error::new(e.to_string()));
+            return Err(ApplicationError::bad_request(anyhow!("invalid upload"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Reference)) => {
+            report.append(garde::Path::new("upload"), garde::Error::new("an asset type id in the file does not exist"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid upload"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(e.into()));
+        },
+    };
+
+    messages.success(format!("upload completed - total: {} processed: {}", res.total, res.processed));
+    Ok(([("HX-Redirect", "/asset_items")], "success"))
+}
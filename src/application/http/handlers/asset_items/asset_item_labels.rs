@@ -0,0 +1,70 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, response::Redirect, Form};
+use axum_messages::Messages;
+use garde::Validate;
+use tracing::instrument;
+
+use crate::{application::{errors::ApplicationError, labels::{label_application_service::LabelError, schema::LabelSheetSchema}, state::AppState, templates::partials::form_alert::FormAlertTemplate}, domain::labels::symbols::parse_symbol_route};
+
+
+#[instrument(skip_all)]
+pub async fn get_asset_item_label(
+    Path((id, symbol)): Path<(i32, String)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let (symbology, format) = parse_symbol_route(&symbol)
+        .ok_or(ApplicationError::not_found(anyhow!("unknown label symbol")))?;
+
+    let asset_item = state.crud_service.get_asset_item(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
+        .ok_or(ApplicationError::not_found(anyhow!("asset item not found")))?;
+
+    let symbol = match state.label_service.get_asset_item_symbol(&asset_item, symbology, format).await {
+        Ok(symbol) => symbol,
+        Err(LabelError::NoBarcode) => {
+            return Err(ApplicationError::not_found(anyhow!(LabelError::NoBarcode)));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    };
+
+    Ok(([("Content-Type", format.content_type()), ("Cache-Control", "no-store")], symbol))
+}
+
+#[instrument(skip_all)]
+pub async fn get_asset_item_labels(
+    messages: Messages,
+    State(state): State<AppState>,
+    Form(label_sheet_schema): Form<LabelSheetSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = label_sheet_schema.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let (asset_items, _) = state.crud_service.get_asset_items_search(label_sheet_schema.filter)
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    let pdf = match state.label_service.get_label_sheet(&asset_items, label_sheet_schema.sheet).await {
+        Ok(pdf) => pdf,
+        Err(LabelError::NoItems) => {
+            messages.warning("no asset items match the current filter");
+            return Ok(Redirect::to("/asset_items").into_response());
+        },
+        Err(e) => {
+            return Err(ApplicationError::InternalServerError(anyhow!(e)));
+        },
+    };
+
+    Ok((
+        [
+            ("Content-Type", "application/pdf".to_string()),
+            ("Content-Disposition", "attachment;filename=asset-labels.pdf".to_string()),
+        ],
+        pdf
+    ).into_response())
+}
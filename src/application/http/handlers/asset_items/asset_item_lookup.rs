@@ -0,0 +1,48 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Query, State}, response::Redirect};
+use axum_messages::Messages;
+use garde::Validate;
+use tracing::instrument;
+
+use crate::{application::{crud::schema::LookupSchema, errors::ApplicationError, state::AppState}, domain::crud::model::lookup::LookupResult};
+
+
+#[instrument(skip_all)]
+pub async fn get_asset_item_lookup(
+    messages: Messages,
+    State(state): State<AppState>,
+    Query(lookup_schema): Query<LookupSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if lookup_schema.validate().is_err() {
+        messages.warning("enter or scan a code to look up");
+        return Ok(Redirect::to("/asset_items"));
+    }
+
+    let result = state.crud_service.lookup_asset_item(lookup_schema)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let redirect = match result {
+        LookupResult::Found(id) => Redirect::to(&format!("/asset_items/{}", id)),
+        LookupResult::Multiple(code) => {
+            messages.info(format!("multiple asset items match {}", code));
+            Redirect::to(&format!("/asset_items?search={}", encode_query_value(&code)))
+        },
+        LookupResult::NotFound(code, Some(serial_number)) => {
+            messages.warning(format!("no asset item found for {}, create it below", code));
+            Redirect::to(&format!("/asset_items/new?serial_number={}", encode_query_value(&serial_number)))
+        },
+        LookupResult::NotFound(code, None) => {
+            messages.warning(format!("no asset item found for {}", code));
+            Redirect::to("/asset_items")
+        },
+    };
+
+    Ok(redirect)
+}
+
+fn encode_query_value(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
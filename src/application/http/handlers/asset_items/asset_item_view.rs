@@ -26,5 +26,24 @@ pub async fn get_asset_item_view(
         .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
         .ok_or(ApplicationError::internal_server_error(anyhow!("asset item not found")))?;
 
-    Ok(([("Cache-Control", "no-store") ], AssetItemViewTemplate::new(session_user, message, asset_item)))
+    let custom_fields = match asset_item.asset_type_id {
+        Some(asset_type_id) => state.crud_service.get_custom_fields(asset_type_id)
+            .await
+            .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?,
+        None => Vec::new(),
+    };
+
+    let history = state.crud_service.get_asset_item_history(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let documents = state.crud_service.get_asset_item_documents(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let images = state.crud_service.get_asset_item_images(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store") ], AssetItemViewTemplate::new(session_user, message, asset_item, custom_fields, history, documents, images)))
 }
\ No newline at end of file
@@ -6,7 +6,7 @@ use axum_messages::Messages;
 use garde::Validate;
 use tracing::instrument;
 
-use crate::{application::{crud::schema::FilterSchema, errors::ApplicationError, state::AppState, templates::pages::asset_items::AssetItemsTemplate}, domain::identityaccess::model::users::SessionUser};
+use crate::{application::{crud::schema::FilterSchema, errors::ApplicationError, state::AppState, templates::{pages::asset_items::AssetItemsTemplate, partials::{form_alert::FormAlertTemplate, saved_views::SavedViewsTemplate}}}, domain::{identityaccess::model::users::SessionUser, saved_views::model::saved_views::ColumnSelection}};
 
 
 #[instrument(skip_all)]
@@ -22,9 +22,105 @@ pub async fn get_asset_items(
         .first()
         .map(|m| m.to_owned());
     
-    let asset_items = state.crud_service.get_asset_items()
+    if let Err(report) = asset_items_filter_schema.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let columns = ColumnSelection::parse(asset_items_filter_schema.columns.as_deref());
+
+    let (asset_items, asset_item_filter) = state.crud_service.get_asset_items_search(asset_items_filter_schema)
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    let categories = state.crud_service.get_categories()
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    // choices for the bulk action panel
+    let asset_types = state.crud_service.get_asset_types()
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    let saved_views = state.saved_view_service.get_saved_views(&session_user)
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    // roles a view can be shared with
+    let roles = state.identity_service.get_roles()
         .await
         .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
 
-    Ok(([("Cache-Control", "no-store")], AssetItemsTemplate::new(session_user, message, asset_items)))
+    let saved_views = SavedViewsTemplate::new(session_user.user.id, saved_views, roles);
+
+    Ok(([("Cache-Control", "no-store")], AssetItemsTemplate::new(session_user, message, asset_items, asset_item_filter, columns, categories, asset_types, saved_views)))
+}
+
+#[instrument(skip_all)]
+pub async fn get_asset_items_export(
+    State(state): State<AppState>,
+    Form(asset_items_filter_schema): Form<FilterSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = asset_items_filter_schema.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let (asset_items, _) = state.crud_service.get_asset_items_search(asset_items_filter_schema)
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    let custom_fields = state.crud_service.get_all_custom_fields()
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    // one column per custom field name, types sharing a name share the column
+    let mut custom_columns: Vec<String> = Vec::new();
+    for custom_field in custom_fields.iter() {
+        if !custom_columns.contains(&custom_field.name) {
+            custom_columns.push(custom_field.name.clone());
+        }
+    }
+
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+
+    let mut header = vec!["Asset ID", "Serial #", "Name", "Asset Type ID", "Brand", "Model", "School", "Room", "Funding Source", "Purchase Date", "Status"];
+    header.extend(custom_columns.iter().map(String::as_str));
+    wtr.write_record(&header)
+        .map_err(|e| ApplicationError::InternalServerError(e.into()))?;
+
+    for asset_item in asset_items.iter() {
+        let mut record = vec![
+            asset_item.asset_id.clone().unwrap_or_default(),
+            asset_item.serial_number.clone().unwrap_or_default(),
+            asset_item.name.clone().unwrap_or_default(),
+            asset_item.asset_type_id.map(|id| id.to_string()).unwrap_or_default(),
+            asset_item.brand.clone().unwrap_or_default(),
+            asset_item.model.clone().unwrap_or_default(),
+            asset_item.school.clone().unwrap_or_default(),
+            asset_item.room.clone().unwrap_or_default(),
+            asset_item.funding_source.clone().unwrap_or_default(),
+            asset_item.purchase_date_display(),
+            asset_item.status.clone(),
+        ];
+
+        for column in custom_columns.iter() {
+            let value = custom_fields.iter()
+                .find(|f| &f.name == column && Some(f.asset_type_id) == asset_item.asset_type_id)
+                .map(|f| f.value_display(&asset_item.custom_fields))
+                .unwrap_or_default();
+            record.push(value);
+        }
+
+        wtr.write_record(&record)
+            .map_err(|e| ApplicationError::InternalServerError(e.into()))?;
+    }
+
+    let data = wtr.into_inner().map_err(|e| ApplicationError::InternalServerError(anyhow!(e.to_string())))?;
+
+    Ok((
+        [
+            ("Content-Type", "text/csv"),
+            ("Content-Disposition", "attachment;filename=asset-items.csv"),
+        ],
+        data
+    ))
 }
@@ -0,0 +1,40 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::State, Extension};
+use axum_extra::extract::Form;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{crud::{crud_application_service::CrudError, schema::BulkEditSchema}, errors::ApplicationError, state::AppState, templates::partials::{bulk_edit_summary::BulkEditSummaryTemplate, form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
+
+
+#[instrument(skip_all)]
+pub async fn post_asset_items_bulk(
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(bulk_edit): Form<BulkEditSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = bulk_edit.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    let summary = match state.crud_service.bulk_edit_asset_items(bulk_edit, session_user.user.id).await {
+        Ok(summary) => summary,
+        Err(CrudError::NoChanges) => {
+            report.append(garde::Path::new(""), garde::Error::new("choose at least one field to change"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Reference)) => {
+            report.append(garde::Path::new("asset_type_id"), garde::Error::new("asset type does not exist"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    };
+
+    // the table listens for this event to reload with the new values
+    Ok(([("HX-Trigger", "assetItemsChanged")], BulkEditSummaryTemplate::new(summary)))
+}
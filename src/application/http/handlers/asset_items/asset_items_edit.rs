@@ -1,12 +1,12 @@
 use anyhow::anyhow;
 
 use askama_axum::IntoResponse;
-use axum::{debug_handler, extract::{Path, State}, Extension, Form};
+use axum::{extract::{Path, State}, Extension, Form};
 use axum_messages::Messages;
 use garde::{Report, Validate};
 use tracing::instrument;
 
-use crate::{application::{crud::schema::UpdateAssetItemSchema, errors::ApplicationError, state::AppState, templates::{pages::{asset_item_edit::AssetItemEditTemplate, asset_item_view::AssetItemViewTemplate}, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::users::SessionUser};
+use crate::{application::{crud::{crud_application_service::CrudError, schema::UpdateAssetItemSchema}, errors::ApplicationError, state::AppState, templates::{pages::asset_item_edit::AssetItemEditTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
 
 
 #[instrument(skip_all)]
@@ -27,14 +27,25 @@ pub async fn get_asset_item_edit(
         .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
         .ok_or(ApplicationError::internal_server_error(anyhow!("asset type not found")))?;
 
-    Ok(([("Cache-Control", "no-store")], AssetItemEditTemplate::new(session_user, message, asset_item)))
+    let asset_types = state.crud_service.get_asset_types()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let custom_fields = match asset_item.asset_type_id {
+        Some(asset_type_id) => state.crud_service.get_custom_fields(asset_type_id)
+            .await
+            .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?,
+        None => Vec::new(),
+    };
+
+    Ok(([("Cache-Control", "no-store")], AssetItemEditTemplate::new(session_user, message, asset_item, asset_types, custom_fields)))
 }
 
 #[instrument(skip_all)]
 pub async fn post_asset_item_edit(
     messages: Messages,
     State(state): State<AppState>,
-    Extension(session_user): Extension<SessionUser>,
+    Extension(_session_user): Extension<SessionUser>,
     Path(id): Path<i32>,
     Form(update_asset_item ): Form<UpdateAssetItemSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
@@ -43,12 +54,28 @@ pub async fn post_asset_item_edit(
         return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
     }
 
-    //TODO: perform edit
-    let asset_item = state.crud_service.get_asset_item(id)
-        .await
-        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
-        .ok_or(ApplicationError::internal_server_error(anyhow!("asset type not found")))?;
-
+    let mut report = Report::new();
+    match state.crud_service.update_asset_item(id, update_asset_item).await {
+        Ok(Some(_)) => {},
+        Ok(None) => {
+            return Err(ApplicationError::not_found(anyhow!("asset item not found")));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Duplicate)) => {
+            report.append(garde::Path::new("asset_id/serial_number"), garde::Error::new("duplicate asset item"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Reference)) => {
+            report.append(garde::Path::new("asset_type_id"), garde::Error::new("reference asset type not found"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::CustomField(e)) => {
+            report.append(garde::Path::new("custom_fields"), garde::Error::new(e.to_string()));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
 
     messages.success("asset item saved");
 
@@ -58,10 +85,20 @@ pub async fn post_asset_item_edit(
 pub async fn delete_asset_item(
     messages: Messages,
     State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
     Path(id): Path<i32>,
 ) -> Result<impl IntoResponse, ApplicationError> {
-
-    
+    match state.crud_service.delete_asset_item(id, session_user.user.id).await {
+        Ok(Some(_)) => { },
+        Ok(None) => {
+            let mut report = Report::new();
+            report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not delete asset item"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
 
     messages.success("asset item deleted");
     Ok(([("HX-Redirect", "/asset_items")], "success"))
@@ -2,20 +2,42 @@ pub mod asset_items;
 pub mod asset_item_create;
 pub mod asset_item_view;
 pub mod asset_items_edit;
+pub mod asset_item_labels;
+pub mod asset_item_lookup;
+pub mod asset_item_custom_fields;
+pub mod asset_item_imports;
+pub mod asset_items_bulk;
+pub mod asset_item_documents;
+pub mod asset_item_images;
 
-use axum::{middleware, routing::{delete, get, post}, Router};
+use axum::{extract::DefaultBodyLimit, middleware, routing::{delete, get, post}, Router};
 
-use crate::application::{http::utils, state::AppState};
+use crate::{application::{http::utils, state::AppState}, settings::UploadsConfig};
 
 
-pub fn router() -> Router<AppState>
+pub fn router(uploads: &UploadsConfig) -> Router<AppState>
 {
     Router::new()
         .route("/asset_items", get(self::asset_items::get_asset_items))
         .route("/asset_items/new", get(self::asset_item_create::get_asset_item_create))
         .route("/asset_items/new", post(self::asset_item_create::post_asset_item_create))
+        .route("/asset_items/bulk", post(self::asset_items_bulk::post_asset_items_bulk))
+        .route("/asset_items/export", get(self::asset_items::get_asset_items_export))
+        .route("/asset_items/imports/new", get(self::asset_item_imports::get_asset_item_imports_new))
+        .route("/asset_items/imports/new", post(self::asset_item_imports::post_asset_item_imports_new).layer(DefaultBodyLimit::max(uploads.imports.body_limit())))
+        .route("/asset_items/custom_fields", get(self::asset_item_custom_fields::get_asset_item_custom_fields))
+        .route("/asset_items/lookup", get(self::asset_item_lookup::get_asset_item_lookup))
+        .route("/asset_items/labels", get(self::asset_item_labels::get_asset_item_labels))
         .route("/asset_items/:id", get(self::asset_item_view::get_asset_item_view))
+        .route("/asset_items/:id", delete(self::asset_items_edit::delete_asset_item))
         .route("/asset_items/:id/edit", get(self::asset_items_edit::get_asset_item_edit))
         .route("/asset_items/:id/edit", post(self::asset_items_edit::post_asset_item_edit))
+        .route("/asset_items/:id/documents", post(self::asset_item_documents::post_asset_item_document).layer(DefaultBodyLimit::max(uploads.documents.body_limit())))
+        .route("/asset_items/:id/documents/:document_id", delete(self::asset_item_documents::delete_asset_item_document))
+        .route("/asset_items/:id/images", post(self::asset_item_images::post_asset_item_image).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
+        .route("/asset_items/:id/images/order", post(self::asset_item_images::post_asset_item_images_order))
+        .route("/asset_items/:id/images/:image_id/primary", post(self::asset_item_images::post_asset_item_primary_image))
+        .route("/asset_items/:id/images/:image_id", delete(self::asset_item_images::delete_asset_item_image))
+        .route("/asset_items/:id/label/:symbol", get(self::asset_item_labels::get_asset_item_label))
         .route_layer(middleware::from_fn(utils::login_required))
 }
\ No newline at end of file
@@ -1,19 +1,17 @@
 use anyhow::anyhow;
 use askama_axum::IntoResponse;
-use axum::{extract::State, Extension, Form};
-use axum_login::AuthSession;
+use axum::{extract::State, Extension};
 use axum_messages::Messages;
-use axum_typed_multipart::TypedMultipart;
 use garde::{Report, Validate};
-use serde::Deserialize;
 use tracing::instrument;
 
-use crate::{application::{crud::schema::NewAssetTypeSchema, errors::ApplicationError, identityaccess::{identity_application_service::{IdentityApplicationService, IdentityError}, schema::NewUserSchema}, state::AppState, templates::{pages::{asset_type_create::AssetTypeCreateTemplate, user_create::UserCreateTemplate, users::UsersTemplate}, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::{CrudRepository, CrudRepositoryError}, identityaccess::model::{user_repository::{UserRepository, UserRepositoryError}, users::SessionUser}}};
+use crate::{application::{http::multipart::UploadMultipart, crud::schema::NewAssetTypeSchema, errors::ApplicationError, state::AppState, templates::{pages::asset_type_create::AssetTypeCreateTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
 
 
 #[instrument(skip_all)]
 pub async fn get_asset_type_create(
     messages: Messages,
+    State(state): State<AppState>,
     Extension(session_user): Extension<SessionUser>,
 ) -> Result<impl IntoResponse, ApplicationError> {
     let message = messages
@@ -22,7 +20,11 @@ pub async fn get_asset_type_create(
         .first()
         .map(|m| m.to_owned());
 
-    Ok(([("Cache-Control", "no-store")], AssetTypeCreateTemplate::new(session_user, message)))
+    let categories = state.crud_service.get_categories()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], AssetTypeCreateTemplate::new(session_user, message, categories)))
 
 }
 
@@ -30,7 +32,7 @@ pub async fn get_asset_type_create(
 pub async fn post_asset_type_create(
     messages: Messages,
     State(state): State<AppState>,
-    TypedMultipart(new_asset_type ): TypedMultipart<NewAssetTypeSchema>,
+    UploadMultipart { data: new_asset_type, .. }: UploadMultipart<NewAssetTypeSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
 
     if let Err(report) = new_asset_type.validate() {
@@ -40,6 +42,10 @@ pub async fn post_asset_type_create(
     let mut report = Report::new();
     if let Err(e) = state.crud_service.add_asset_type(new_asset_type, &state.content_service).await {
         match e {
+            crate::application::crud::crud_application_service::CrudError::Content(crate::application::content::content_application_service::ContentError::InvalidContent(reason)) => {
+                report.append(garde::Path::new("image"), garde::Error::new(reason));
+                return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+            },
             crate::application::crud::crud_application_service::CrudError::Content(_content_error) => {
                 report.append(garde::Path::new("image"), garde::Error::new("image could not be uploaded"));
                 return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
@@ -48,6 +54,10 @@ pub async fn post_asset_type_create(
                 report.append(garde::Path::new("brand/model"), garde::Error::new("duplicate asset type"));
                 return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
             },
+            crate::application::crud::crud_application_service::CrudError::Repo(CrudRepositoryError::Reference) => {
+                report.append(garde::Path::new("category_id"), garde::Error::new("category does not exist"));
+                return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+            },
             _ => {
                 return Err(ApplicationError::internal_server_error(anyhow!(e)));
             },
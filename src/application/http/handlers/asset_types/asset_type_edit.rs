@@ -1,13 +1,12 @@
 use anyhow::anyhow;
 
 use askama_axum::IntoResponse;
-use axum::{debug_handler, extract::{Path, State}, Extension};
+use axum::{extract::{Path, Query, State}, Extension};
 use axum_messages::Messages;
-use axum_typed_multipart::TypedMultipart;
 use garde::{Report, Validate};
 use tracing::instrument;
 
-use crate::{application::{content::schema::ImageSchema, crud::{crud_application_service::CrudError, schema::UpdateAssetTypeSchema}, errors::ApplicationError, state::AppState, templates::{pages::asset_type_edit::AssetTypeEditTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
+use crate::{application::{http::multipart::UploadMultipart, content::{content_application_service::ContentError, schema::ImageSchema}, crud::{crud_application_service::CrudError, schema::{DeleteAssetTypeSchema, UpdateAssetTypeSchema}}, errors::ApplicationError, state::AppState, templates::{pages::asset_type_edit::AssetTypeEditTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
 
 
 #[instrument(skip_all)]
@@ -28,7 +27,11 @@ pub async fn get_asset_type_edit(
         .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
         .ok_or(ApplicationError::internal_server_error(anyhow!("asset type not found")))?;
 
-    Ok(([("Cache-Control", "no-store")], AssetTypeEditTemplate::new(session_user, message, asset_type)))
+    let categories = state.crud_service.get_categories()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], AssetTypeEditTemplate::new(session_user, message, asset_type, categories)))
 }
 
 #[instrument(skip_all)]
@@ -36,7 +39,7 @@ pub async fn post_asset_type_edit(
     messages: Messages,
     State(state): State<AppState>,
     Path(id): Path<i32>,
-    TypedMultipart(update_asset_type ): TypedMultipart<UpdateAssetTypeSchema>,
+    UploadMultipart { data: update_asset_type, .. }: UploadMultipart<UpdateAssetTypeSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
 
     if let Err(report) = update_asset_type.validate() {
@@ -46,6 +49,10 @@ pub async fn post_asset_type_edit(
     let mut report = Report::new();
     if let Err(e) = state.crud_service.update_asset_type(id, update_asset_type, &state.content_service).await {
         match e {
+            CrudError::Content(ContentError::InvalidContent(reason)) => {
+                report.append(garde::Path::new("image"), garde::Error::new(reason));
+                return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+            },
             CrudError::Content(_content_error) => {
                 report.append(garde::Path::new("image"), garde::Error::new("image could not be uploaded"));
                 return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
@@ -54,6 +61,10 @@ pub async fn post_asset_type_edit(
                 report.append(garde::Path::new("brand/model"), garde::Error::new("duplicate asset type"));
                 return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
             },
+            CrudError::Repo(CrudRepositoryError::Reference) => {
+                report.append(garde::Path::new("category_id"), garde::Error::new("category does not exist"));
+                return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+            },
             _ => {
                 return Err(ApplicationError::internal_server_error(anyhow!(e)));
             },
@@ -68,21 +79,25 @@ pub async fn post_asset_type_edit(
 pub async fn post_change_asset_type_picture(
     Path(id): Path<i32>,
     State(state): State<AppState>,
-    TypedMultipart(ImageSchema{image} ): TypedMultipart<ImageSchema>,
+    UploadMultipart { data: ImageSchema{image}, .. }: UploadMultipart<ImageSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
-    let attachment = state.content_service.upload_image_file_as_attachment(image)
+    // the new picture joins the gallery as its primary image
+    let image = state.crud_service.add_asset_type_image(id, image, &state.content_service, true)
         .await
-        .map_err(|e| {
-            let mut report = Report::new();
-            report.append(garde::Path::new("content picture"), garde::Error::new("something went wrong during image upload"));
-
-            ApplicationError::bad_request(e.into(), FormAlertTemplate::global_new(report).to_string())
+        .map_err(|e| match e {
+            CrudError::Content(e) => {
+                let message = match &e {
+                    ContentError::InvalidContent(reason) => reason.clone(),
+                    _ => "something went wrong during image upload".to_string(),
+                };
+                let mut report = Report::new();
+                report.append(garde::Path::new("content picture"), garde::Error::new(message));
+
+                ApplicationError::bad_request(e.into(), FormAlertTemplate::global_new(report).to_string())
+            },
+            e => ApplicationError::InternalServerError(e.into()),
         })?;
 
-    let _asset_type = state.crud_service.update_asset_type_picture(id, attachment.url.clone())
-        .await
-        .map_err(|e| ApplicationError::InternalServerError(e.into()))?;
-
     Ok(format!(
         r#"
         <img 
@@ -91,7 +106,7 @@ pub async fn post_change_asset_type_picture(
             src="{}"
             referrerpolicy="no-referrer"
             hx-swap-oob="true" />
-        "#, attachment.url
+        "#, image.thumbnail_url()
     ))
 }
 
@@ -100,21 +115,25 @@ pub async fn delete_asset_type(
     messages: Messages,
     State(state): State<AppState>,
     Path(id): Path<i32>,
+    Query(delete_asset_type): Query<DeleteAssetTypeSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
 
-    match state.crud_service.delete_asset_type(id).await {
+    if let Err(report) = delete_asset_type.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    match state.crud_service.delete_asset_type(id, delete_asset_type).await {
         Ok(Some(_)) => { },
         Ok(None) => {
             let mut report = Report::new();
-            report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not delete user"));
+            report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not delete asset type"));
             return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
         },
         Err(e) => {
             match e {
-                CrudError::Repo(_) => {
-                    // TODO: check for foreign key violation
+                CrudError::Repo(CrudRepositoryError::Reference) => {
                     let mut report = Report::new();
-                    report.append(garde::Path::new(""), garde::Error::new("asset type referenced by assets, could not delete"));
+                    report.append(garde::Path::new(""), garde::Error::new("asset type referenced by asset items, reassign them to another asset type before deleting"));
     
                     return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
                 }
@@ -0,0 +1,64 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, Form};
+use axum_messages::Messages;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{crud::{crud_application_service::CrudError, schema::NewCustomFieldSchema}, errors::ApplicationError, state::AppState, templates::partials::form_alert::FormAlertTemplate}, domain::crud::crud_repository::CrudRepositoryError};
+
+
+#[instrument(skip_all)]
+pub async fn post_asset_type_field(
+    messages: Messages,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(new_custom_field): Form<NewCustomFieldSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    if let Err(report) = new_custom_field.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    match state.crud_service.add_custom_field(id, new_custom_field).await {
+        Ok(_) => { },
+        Err(CrudError::CustomField(e)) => {
+            report.append(garde::Path::new("label"), garde::Error::new(e.to_string()));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Duplicate)) => {
+            report.append(garde::Path::new("label"), garde::Error::new("a field with this name already exists for the asset type"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
+
+    messages.success("custom field added");
+    Ok(([("HX-Redirect", format!("/asset_types/{}", id))], "success"))
+}
+
+#[instrument(skip_all)]
+pub async fn delete_asset_type_field(
+    messages: Messages,
+    State(state): State<AppState>,
+    Path((id, field_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, ApplicationError> {
+
+    let returned_id = state.crud_service.delete_custom_field(id, field_id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    if returned_id.is_none() {
+        let mut report = Report::new();
+        report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not delete custom field"));
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    // values stay on the items, they are only hidden once the definition is gone
+    messages.success("custom field deleted");
+    Ok(([("HX-Redirect", format!("/asset_types/{}", id))], "success"))
+}
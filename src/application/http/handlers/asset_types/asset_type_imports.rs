@@ -1,13 +1,12 @@
 use anyhow::anyhow;
 
 use askama_axum::IntoResponse;
-use axum::{debug_handler, extract::State, Extension};
+use axum::{extract::State, Extension};
 use axum_messages::Messages;
-use axum_typed_multipart::TypedMultipart;
-use garde::Validate;
+use garde::{Report, Validate};
 use tracing::instrument;
 
-use crate::{application::{crud::schema::UploadAsetTypesSchema, errors::ApplicationError, state::AppState, templates::{pages::asset_types_imports_new::AssetTypesImportsNew, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::users::SessionUser};
+use crate::{application::{http::multipart::UploadMultipart, content::content_application_service::UploadCategory, crud::schema::UploadAsetTypesSchema, errors::ApplicationError, state::AppState, templates::{pages::asset_types_imports_new::AssetTypesImportsNew, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::users::SessionUser};
 
 
 #[instrument(skip_all)]
@@ -28,13 +27,19 @@ pub async fn get_asset_type_imports_new(
 pub async fn post_asset_type_imports_new(
     messages: Messages,
     State(state): State<AppState>,
-    TypedMultipart(upload_asset_type ): TypedMultipart<UploadAsetTypesSchema>,
+    UploadMultipart { data: upload_asset_type, .. }: UploadMultipart<UploadAsetTypesSchema>,
 ) -> Result<impl IntoResponse, ApplicationError> {
 
     if let Err(report) = upload_asset_type.validate() {
         return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
     }
 
+    if let Err(e) = state.content_service.check_file_upload(UploadCategory::Imports, &upload_asset_type.upload) {
+        let mut report = Report::new();
+        report.append(garde::Path::new("upload"), garde::Error::new(e.to_string()));
+        return Err(ApplicationError::bad_request(anyhow!("invalid upload"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
     let res = state.crud_service.upload_asset_types(upload_asset_type)
         .await
         .map_err(|e| ApplicationError::internal_server_error(e.into()))?;
@@ -6,7 +6,7 @@ use axum_messages::Messages;
 use garde::Validate;
 use tracing::instrument;
 
-use crate::{application::{crud::schema::FilterSchema, errors::ApplicationError, state::AppState, templates::{pages::asset_types::AssetTypesTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::model::asset_types::AssetTypeFilter, identityaccess::model::users::SessionUser}};
+use crate::{application::{crud::schema::FilterSchema, errors::ApplicationError, state::AppState, templates::{pages::asset_types::AssetTypesTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::users::SessionUser};
 
 
 #[instrument(skip_all)]
@@ -30,5 +30,16 @@ pub async fn get_asset_types(
         .await
         .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
 
-    Ok(([("Cache-Control", "no-store")], AssetTypesTemplate::new(session_user, message, asset_types, asset_type_filter)))
+    let suggestion = match (asset_types.is_empty(), asset_type_filter.search.as_deref()) {
+        (true, Some(search)) => state.crud_service.get_asset_type_search_suggestion(search)
+            .await
+            .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?,
+        _ => None,
+    };
+
+    let categories = state.crud_service.get_categories()
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], AssetTypesTemplate::new(session_user, message, asset_types, asset_type_filter, suggestion, categories)))
 }
@@ -5,7 +5,7 @@ use axum::{extract::{Path, State}, Extension};
 use axum_messages::Messages;
 use tracing::instrument;
 
-use crate::{application::{errors::ApplicationError, state::AppState, templates::pages::{asset_type_view::AssetTypeViewTemplate, asset_types::AssetTypesTemplate}}, domain::identityaccess::model::users::SessionUser};
+use crate::{application::{errors::ApplicationError, state::AppState, templates::pages::asset_type_view::AssetTypeViewTemplate}, domain::identityaccess::model::users::SessionUser};
 
 
 #[instrument(skip_all)]
@@ -26,5 +26,36 @@ pub async fn get_asset_type_view(
         .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
         .ok_or(ApplicationError::internal_server_error(anyhow!("asset type not found")))?;
 
-    Ok(([("Cache-Control", "no-store") ], AssetTypeViewTemplate::new(session_user, message, asset_type)))
+    let category = match asset_type.category_id {
+        Some(category_id) => state.crud_service.get_category(category_id)
+            .await
+            .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?,
+        None => None,
+    };
+
+    let item_count = state.crud_service.get_asset_item_count_for_type(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    // other types the items can be moved to before deleting
+    let reassign_types = state.crud_service.get_asset_types()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
+        .into_iter()
+        .filter(|t| t.id != id)
+        .collect();
+
+    let custom_fields = state.crud_service.get_custom_fields(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let documents = state.crud_service.get_asset_type_documents(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let images = state.crud_service.get_asset_type_images(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store") ], AssetTypeViewTemplate::new(session_user, message, asset_type, category, item_count, reassign_types, custom_fields, documents, images)))
 }
\ No newline at end of file
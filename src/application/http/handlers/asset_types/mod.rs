@@ -3,23 +3,34 @@ pub mod asset_type_create;
 pub mod asset_types_view;
 pub mod asset_type_edit;
 pub mod asset_type_imports;
+pub mod asset_type_fields;
+pub mod asset_type_documents;
+pub mod asset_type_images;
 
 use axum::{extract::DefaultBodyLimit, middleware, routing::{delete, get, post}, Router};
 
-use crate::application::{http::utils, state::AppState};
+use crate::{application::{http::utils, state::AppState}, settings::UploadsConfig};
 
-pub fn router() -> Router<AppState>
+pub fn router(uploads: &UploadsConfig) -> Router<AppState>
 {
     Router::new()
         .route("/asset_types/:id", get(self::asset_types_view::get_asset_type_view))
         .route("/asset_types/:id", delete(self::asset_type_edit::delete_asset_type))
         .route("/asset_types/:id/edit", get(self::asset_type_edit::get_asset_type_edit))
-        .route("/asset_types/:id/edit", post(self::asset_type_edit::post_asset_type_edit).layer(DefaultBodyLimit::max(5242880)))
-        .route("/asset_types/:id/change_picture", post(self::asset_type_edit::post_change_asset_type_picture).layer(DefaultBodyLimit::max(5242880)))
+        .route("/asset_types/:id/edit", post(self::asset_type_edit::post_asset_type_edit).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
+        .route("/asset_types/:id/change_picture", post(self::asset_type_edit::post_change_asset_type_picture).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
+        .route("/asset_types/:id/fields", post(self::asset_type_fields::post_asset_type_field))
+        .route("/asset_types/:id/fields/:field_id", delete(self::asset_type_fields::delete_asset_type_field))
+        .route("/asset_types/:id/documents", post(self::asset_type_documents::post_asset_type_document).layer(DefaultBodyLimit::max(uploads.documents.body_limit())))
+        .route("/asset_types/:id/documents/:document_id", delete(self::asset_type_documents::delete_asset_type_document))
+        .route("/asset_types/:id/images", post(self::asset_type_images::post_asset_type_image).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
+        .route("/asset_types/:id/images/order", post(self::asset_type_images::post_asset_type_images_order))
+        .route("/asset_types/:id/images/:image_id/primary", post(self::asset_type_images::post_asset_type_primary_image))
+        .route("/asset_types/:id/images/:image_id", delete(self::asset_type_images::delete_asset_type_image))
         .route("/asset_types/new", get(self::asset_type_create::get_asset_type_create))
-        .route("/asset_types/new", post(self::asset_type_create::post_asset_type_create).layer(DefaultBodyLimit::max(5242880)))
+        .route("/asset_types/new", post(self::asset_type_create::post_asset_type_create).layer(DefaultBodyLimit::max(uploads.images.body_limit())))
         .route("/asset_types", get(self::asset_types::get_asset_types))
         .route("/asset_types/imports/new", get(self::asset_type_imports::get_asset_type_imports_new))
-        .route("/asset_types/imports/new", post(self::asset_type_imports::post_asset_type_imports_new))
+        .route("/asset_types/imports/new", post(self::asset_type_imports::post_asset_type_imports_new).layer(DefaultBodyLimit::max(uploads.imports.body_limit())))
         .route_layer(middleware::from_fn(utils::login_required))
 }
\ No newline at end of file
@@ -0,0 +1,105 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, Extension, Form};
+use axum_messages::Messages;
+use garde::Validate;
+use tracing::instrument;
+
+use crate::{application::{audits::{audit_application_service::AuditError, schema::AuditScanSchema}, errors::ApplicationError, state::AppState, templates::{pages::audit_view::AuditViewTemplate, partials::{alert::AlertTemplate, form_alert::FormAlertTemplate}}}, domain::identityaccess::model::users::SessionUser};
+
+
+fn audit_error(e: AuditError) -> ApplicationError {
+    match e {
+        AuditError::NotFound => ApplicationError::not_found(anyhow!(e)),
+        AuditError::Completed => ApplicationError::bad_request(anyhow!("audit completed"), AlertTemplate::error("global_alert_message", "this audit is already completed").to_string()),
+        _ => ApplicationError::internal_server_error(anyhow!(e)),
+    }
+}
+
+/// Renders the audit page, scan and action requests select the results section out of it
+async fn render_audit(state: &AppState, session_user: SessionUser, message: Option<axum_messages::Message>, id: i32) -> Result<impl IntoResponse, ApplicationError> {
+    let audit = state.audit_service.get_audit(id)
+        .await
+        .map_err(audit_error)?;
+
+    let reconciliation = state.audit_service.get_audit_reconciliation(&audit)
+        .await
+        .map_err(audit_error)?;
+
+    Ok(([("Cache-Control", "no-store")], AuditViewTemplate::new(session_user, message, audit, reconciliation)))
+}
+
+#[instrument(skip_all)]
+pub async fn get_audit_view(
+    messages: Messages,
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    render_audit(&state, session_user, message, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn post_audit_scan(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(audit_scan): Form<AuditScanSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = audit_scan.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    state.audit_service.add_audit_scan(id, audit_scan)
+        .await
+        .map_err(audit_error)?;
+
+    render_audit(&state, session_user, None, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn post_audit_relocate_item(
+    Path((id, item_id)): Path<(i32, i32)>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    state.audit_service.relocate_asset_item(id, item_id, session_user.user.id)
+        .await
+        .map_err(audit_error)?;
+
+    render_audit(&state, session_user, None, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn post_audit_mark_missing(
+    Path((id, item_id)): Path<(i32, i32)>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    state.audit_service.mark_asset_item_missing(id, item_id, session_user.user.id)
+        .await
+        .map_err(audit_error)?;
+
+    render_audit(&state, session_user, None, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn post_audit_complete(
+    messages: Messages,
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    state.audit_service.complete_audit(id)
+        .await
+        .map_err(audit_error)?;
+
+    messages.success("audit completed");
+    Ok(([("HX-Redirect", format!("/audits/{}", id))], "success"))
+}
@@ -0,0 +1,48 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::State, Extension, Form};
+use axum_messages::Messages;
+use garde::Validate;
+use tracing::instrument;
+
+use crate::{application::{audits::schema::NewAuditSchema, errors::ApplicationError, state::AppState, templates::{pages::audits::AuditsTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::users::SessionUser};
+
+
+#[instrument(skip_all)]
+pub async fn get_audits(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let audits = state.audit_service.get_audits()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], AuditsTemplate::new(session_user, message, audits)))
+}
+
+#[instrument(skip_all)]
+pub async fn post_audit_create(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(new_audit): Form<NewAuditSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = new_audit.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let audit = state.audit_service.add_audit(new_audit, session_user.user.id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    messages.success("audit started");
+    Ok(([("HX-Redirect", format!("/audits/{}", audit.id))], "success"))
+}
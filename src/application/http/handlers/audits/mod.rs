@@ -0,0 +1,20 @@
+pub mod audits;
+pub mod audit_view;
+
+use axum::{middleware, routing::{get, post}, Router};
+
+use crate::application::{http::utils, state::AppState};
+
+
+pub fn router() -> Router<AppState>
+{
+    Router::new()
+        .route("/audits", get(self::audits::get_audits))
+        .route("/audits", post(self::audits::post_audit_create))
+        .route("/audits/:id", get(self::audit_view::get_audit_view))
+        .route("/audits/:id/scans", post(self::audit_view::post_audit_scan))
+        .route("/audits/:id/items/:item_id/relocate", post(self::audit_view::post_audit_relocate_item))
+        .route("/audits/:id/items/:item_id/missing", post(self::audit_view::post_audit_mark_missing))
+        .route("/audits/:id/complete", post(self::audit_view::post_audit_complete))
+        .route_layer(middleware::from_fn(utils::login_required))
+}
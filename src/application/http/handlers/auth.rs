@@ -1,13 +1,11 @@
 use anyhow::anyhow;
 use askama_axum::IntoResponse;
-use axum::{extract::{Query, State}, http::{HeaderValue, StatusCode}, middleware, response::Redirect, routing::{get, post}, Form, Router};
-use axum_login::{tower_sessions::Session, AuthSession};
+use axum::{middleware, response::Redirect, routing::{get, post}, Form, Router};
+use axum_login::AuthSession;
 use axum_messages::Messages;
-use oauth2::CsrfToken;
-use serde::Deserialize;
 use tracing::instrument;
 
-use crate::{application::{errors::ApplicationError, http::utils, identityaccess::{identity_application_service::IdentityApplicationService, schema::AuthSchema}, state::AppState, templates::{pages::login::LoginTemplate, partials::alert::AlertTemplate}}, domain::identityaccess::model::{credentials::{Credentials, PasswordCredentials}, user_repository::UserRepository}};
+use crate::{application::{errors::ApplicationError, http::utils, identityaccess::{identity_application_service::IdentityApplicationService, schema::AuthSchema}, state::AppState, templates::{pages::login::LoginTemplate, partials::alert::AlertTemplate}}, domain::identityaccess::model::credentials::{Credentials, PasswordCredentials}};
 
 
 pub fn router() -> Router<AppState>
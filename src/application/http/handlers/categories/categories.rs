@@ -0,0 +1,59 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::State, Extension, Form};
+use axum_messages::Messages;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{crud::{crud_application_service::CrudError, schema::NewCategorySchema}, errors::ApplicationError, state::AppState, templates::{pages::categories::CategoriesTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
+
+
+#[instrument(skip_all)]
+pub async fn get_categories(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let categories = state.crud_service.get_categories()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], CategoriesTemplate::new(session_user, message, categories)))
+}
+
+#[instrument(skip_all)]
+pub async fn post_category_create(
+    messages: Messages,
+    State(state): State<AppState>,
+    Form(new_category): Form<NewCategorySchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = new_category.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    match state.crud_service.add_category(new_category).await {
+        Ok(_) => { },
+        Err(CrudError::Repo(CrudRepositoryError::Duplicate)) => {
+            report.append(garde::Path::new("name"), garde::Error::new("a category with this name already exists under the same parent"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Reference)) => {
+            report.append(garde::Path::new("parent_id"), garde::Error::new("parent category does not exist"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
+
+    messages.success("category added");
+    Ok(([("HX-Redirect", "/categories")], "success"))
+}
@@ -0,0 +1,158 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, Extension, Form};
+use axum_messages::Messages;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{crud::{crud_application_service::CrudError, schema::{NewCustomFieldSchema, UpdateCategorySchema}}, errors::ApplicationError, state::AppState, templates::{pages::category_view::CategoryViewTemplate, partials::form_alert::FormAlertTemplate}}, domain::{crud::crud_repository::CrudRepositoryError, identityaccess::model::users::SessionUser}};
+
+
+#[instrument(skip_all)]
+pub async fn get_category_view(
+    messages: Messages,
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let category = state.crud_service.get_category(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
+        .ok_or(ApplicationError::not_found(anyhow!("category not found")))?;
+
+    // a category can only move under categories outside its own subtree
+    let parent_choices = state.crud_service.get_categories()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?
+        .into_iter()
+        .filter(|c| !c.is_within(id))
+        .collect();
+
+    let category_fields = state.crud_service.get_category_fields(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let asset_type_count = state.crud_service.get_asset_type_count_for_category(id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], CategoryViewTemplate::new(session_user, message, category, parent_choices, category_fields, asset_type_count)))
+}
+
+#[instrument(skip_all)]
+pub async fn post_category_edit(
+    messages: Messages,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(update_category): Form<UpdateCategorySchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = update_category.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    match state.crud_service.update_category(id, update_category).await {
+        Ok(Some(_)) => { },
+        Ok(None) => {
+            return Err(ApplicationError::not_found(anyhow!("category not found")));
+        },
+        Err(CrudError::Category(e)) => {
+            report.append(garde::Path::new("parent_id"), garde::Error::new(e.to_string()));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Duplicate)) => {
+            report.append(garde::Path::new("name"), garde::Error::new("a category with this name already exists under the same parent"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
+
+    messages.success("category updated");
+    Ok(([("HX-Redirect", format!("/categories/{}", id))], "success"))
+}
+
+#[instrument(skip_all)]
+pub async fn delete_category(
+    messages: Messages,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let mut report = Report::new();
+    match state.crud_service.delete_category(id).await {
+        Ok(Some(_)) => { },
+        Ok(None) => {
+            report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not delete category"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Reference)) => {
+            report.append(garde::Path::new(""), garde::Error::new("category has subcategories, move or delete them first"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
+
+    messages.success("category deleted");
+    Ok(([("HX-Redirect", "/categories")], "success"))
+}
+
+#[instrument(skip_all)]
+pub async fn post_category_field(
+    messages: Messages,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Form(new_custom_field): Form<NewCustomFieldSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = new_custom_field.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    match state.crud_service.add_category_field(id, new_custom_field).await {
+        Ok(_) => { },
+        Err(CrudError::CustomField(e)) => {
+            report.append(garde::Path::new("label"), garde::Error::new(e.to_string()));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(CrudError::Repo(CrudRepositoryError::Duplicate)) => {
+            report.append(garde::Path::new("label"), garde::Error::new("a field with this name already exists for the category"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    }
+
+    messages.success("category field added");
+    Ok(([("HX-Redirect", format!("/categories/{}", id))], "success"))
+}
+
+#[instrument(skip_all)]
+pub async fn delete_category_field(
+    messages: Messages,
+    State(state): State<AppState>,
+    Path((id, field_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let returned_id = state.crud_service.delete_category_field(id, field_id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    if returned_id.is_none() {
+        let mut report = Report::new();
+        report.append(garde::Path::new(""), garde::Error::new("something went wrong, could not delete category field"));
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    messages.success("category field deleted");
+    Ok(([("HX-Redirect", format!("/categories/{}", id))], "success"))
+}
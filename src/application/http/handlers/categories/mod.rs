@@ -0,0 +1,20 @@
+pub mod categories;
+pub mod category_view;
+
+use axum::{middleware, routing::{delete, get, post}, Router};
+
+use crate::application::{http::utils, state::AppState};
+
+
+pub fn router() -> Router<AppState>
+{
+    Router::new()
+        .route("/categories", get(self::categories::get_categories))
+        .route("/categories", post(self::categories::post_category_create))
+        .route("/categories/:id", get(self::category_view::get_category_view))
+        .route("/categories/:id", delete(self::category_view::delete_category))
+        .route("/categories/:id/edit", post(self::category_view::post_category_edit))
+        .route("/categories/:id/fields", post(self::category_view::post_category_field))
+        .route("/categories/:id/fields/:field_id", delete(self::category_view::delete_category_field))
+        .route_layer(middleware::from_fn(utils::login_required))
+}
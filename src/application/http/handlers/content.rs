@@ -1,18 +1,19 @@
 use anyhow::anyhow;
-use askama_axum::IntoResponse;
-use axum::{body::Body, extract::{Path, State}, middleware, routing::get, Router};
-
-use futures::{stream, AsyncRead};
-use tokio_util::io::ReaderStream;
+use axum::{body::Body, extract::{Path, Request, State}, http::{header, HeaderMap, StatusCode}, middleware, response::{IntoResponse, Redirect, Response}, routing::get, Router};
+use tower_http::services::ServeFile;
 use tracing::instrument;
 
-use crate::{application::{errors::ApplicationError, http::utils, state::AppState}, domain::identityaccess::model::user_repository::UserRepository};
+use crate::{application::{errors::ApplicationError, http::utils, state::AppState}, domain::filesystem::persistence_service::StoredFile};
+
 
+/// Stored names are derived from the content hash and never point at different bytes, so clients keep them for good.
+/// Private since every file sits behind the login.
+const CACHE_CONTROL: &str = "private, max-age=31536000, immutable";
 
 pub fn router() -> Router<AppState>
 {
     let content_router = Router::<AppState>::new()
-        .route("/content/:folder/:hash", get(self::get_content))
+        .route("/content/:folder/:name", get(self::get_content))
         .route_layer(middleware::from_fn(utils::login_required));
 
     content_router
@@ -21,17 +22,19 @@ pub fn router() -> Router<AppState>
 #[instrument(skip_all)]
 pub async fn get_content(
     State(state): State<AppState>,
-    Path((folder, hash)): Path<(String, String)>,
-) -> Result<impl IntoResponse, ApplicationError> {
+    Path((folder, name)): Path<(String, String)>,
+    request: Request,
+) -> Result<Response, ApplicationError> {
 
+    let range = requested_range(request.headers());
     let (file, filename, content_type) = match folder.as_str() {
         "images" => {
-            state.content_service.retrieve_image_file(hash)
+            state.content_service.retrieve_image_file(name.clone(), range)
                 .await
                 .map_err(|e| ApplicationError::not_found(e.into()))?
         }
         "documents" => {
-            state.content_service.retrieve_document_file(hash)
+            state.content_service.retrieve_document_file(name.clone(), range)
                 .await
                 .map_err(|e| ApplicationError::not_found(e.into()))?
         }
@@ -40,15 +43,85 @@ pub async fn get_content(
         }
     };
 
-    // convert the `AsyncRead` into a `Stream` e.g tokio::file
-    let stream = ReaderStream::new(file);    
-    let content_disposition = format!("inline;filename={}", filename);
+    let etag = format!("\"{}\"", name);
+    let headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+        // uploads are untrusted, never let them run scripts or be sniffed into something executable
+        (header::CONTENT_SECURITY_POLICY, "sandbox".to_string()),
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+    ];
+
+    if is_not_modified(request.headers(), &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, headers).into_response());
+    }
 
-     Ok((
+    let response = match file {
+        StoredFile::Local(filepath) => {
+            // ServeFile answers Range and If-Modified-Since requests with 206/304 on its own
+            ServeFile::new(&filepath)
+                .try_call(request)
+                .await
+                .map_err(|e| ApplicationError::not_found(e.into()))?
+                .map(Body::new)
+        }
+        StoredFile::Stream(stream) => {
+            (
+                [(header::ACCEPT_RANGES, "bytes")],
+                Body::from_stream(stream),
+            ).into_response()
+        }
+        StoredFile::Partial { data, content_range } => {
+            (
+                StatusCode::PARTIAL_CONTENT,
+                [(header::ACCEPT_RANGES, "bytes".to_string()), (header::CONTENT_RANGE, content_range)],
+                data,
+            ).into_response()
+        }
+        StoredFile::Redirect(url) => {
+            // the presigned url expires, so the redirect itself must not be cached. Type and disposition
+            // are signed into the url as response overrides
+            return Ok((
+                [(header::CACHE_CONTROL, "no-store")],
+                Redirect::temporary(&url),
+            ).into_response());
+        }
+    };
+
+    Ok((
+        headers,
         [
-            ("Content-Type", content_type.to_string()),
-            ("Content-Disposition", content_disposition)
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, filename.content_disposition()),
         ],
-        Body::from_stream(stream)
-    ))  
-}
\ No newline at end of file
+        response,
+    ).into_response())
+}
+
+/// If-None-Match uses the weak comparison, so W/ prefixed tags match too
+fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers.get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+/// A single `bytes=start-end` or `bytes=start-` range, for the object store to fetch.
+/// Suffix and multipart ranges get the whole file, which a client has to accept.
+fn requested_range(headers: &HeaderMap) -> Option<(u64, Option<u64>)> {
+    let value = headers.get(header::RANGE)?.to_str().ok()?;
+    let (start, end) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+    if end.contains(',') {
+        return None;
+    }
+
+    let start = start.trim().parse::<u64>().ok()?;
+    let end = match end.trim() {
+        "" => None,
+        end => Some(end.parse::<u64>().ok()?),
+    };
+
+    Some((start, end))
+}
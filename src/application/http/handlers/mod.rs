@@ -3,3 +3,10 @@ pub mod oauth;
 pub mod auth;
 pub mod asset_types;
 pub mod asset_items;
+pub mod reports;
+pub mod audits;
+pub mod categories;
+pub mod transfers;
+pub mod search;
+pub mod saved_views;
+pub mod content;
@@ -1,13 +1,11 @@
 use anyhow::anyhow;
 use askama_axum::IntoResponse;
-use axum::{extract::{Query, State}, http::StatusCode, middleware, response::Redirect, routing::{get, post}, Router};
+use axum::{extract::Query, middleware, response::Redirect, routing::{get, post}, Router};
 use axum_login::{tower_sessions::Session, AuthSession};
 use axum_messages::Messages;
-use oauth2::CsrfToken;
-use serde::Deserialize;
 use tracing::instrument;
 
-use crate::{application::{errors::ApplicationError, http::utils, identityaccess::{identity_application_service::{IdentityApplicationService, CSRF_STATE_KEY}, schema::OauthSchema}, state::AppState}, domain::identityaccess::model::{credentials::{Credentials, OauthCredentials}, user_repository::UserRepository}, infastructure::services::postgres_user_repository::PostgresUserRepository};
+use crate::{application::{errors::ApplicationError, http::utils, identityaccess::{identity_application_service::{IdentityApplicationService, CSRF_STATE_KEY}, schema::OauthSchema}, state::AppState}, domain::identityaccess::model::credentials::{Credentials, OauthCredentials}};
 
 
 pub fn router() -> Router<AppState>
@@ -41,11 +39,11 @@ pub async fn google_oauth_callback(
                 new_state: state,
             })
         },
-        OauthSchema::Error { error, state } => {
+        OauthSchema::Error { error, state: _ } => {
             messages.error("Failed to sign in with Google");
 
             return Err(
-                ApplicationError::redirect(anyhow!("oauth login failure - csrf mismatch"), "/sessions/login")
+                ApplicationError::redirect(anyhow!("oauth login failure - {}", error), "/sessions/login")
              ); 
         },
     };
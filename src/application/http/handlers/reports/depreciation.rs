@@ -0,0 +1,117 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::State, Extension, Form};
+use axum_messages::Messages;
+use garde::Validate;
+use tracing::instrument;
+
+use crate::{application::{crud::schema::DepreciationReportSchema, errors::ApplicationError, state::AppState, templates::{pages::depreciation_report::DepreciationReportTemplate, partials::form_alert::FormAlertTemplate}}, domain::identityaccess::model::users::SessionUser};
+
+
+#[instrument(skip_all)]
+pub async fn get_depreciation_report(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(report_schema): Form<DepreciationReportSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    if let Err(report) = report_schema.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let report = state.crud_service.get_depreciation_report(report_schema)
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], DepreciationReportTemplate::new(session_user, message, report)))
+}
+
+#[instrument(skip_all)]
+pub async fn get_depreciation_report_export(
+    State(state): State<AppState>,
+    Form(report_schema): Form<DepreciationReportSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = report_schema.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let report = state.crud_service.get_depreciation_report(report_schema)
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    wtr.write_record(["School", "Funding Source", "Items", "Cost", "Accumulated Depreciation", "Book Value"])
+        .map_err(|e| ApplicationError::InternalServerError(e.into()))?;
+
+    for row in report.rows.iter() {
+        wtr.write_record([
+            row.school.clone(),
+            row.funding_source.clone(),
+            row.item_count.to_string(),
+            row.total_cost_display(),
+            row.accumulated_depreciation_display(),
+            row.book_value_display(),
+        ])
+        .map_err(|e| ApplicationError::InternalServerError(e.into()))?;
+    }
+
+    let data = wtr.into_inner().map_err(|e| ApplicationError::InternalServerError(anyhow!(e.to_string())))?;
+    let content_disposition = format!("attachment;filename=depreciation-{}.csv", report.as_of);
+
+    Ok((
+        [
+            ("Content-Type", "text/csv".to_string()),
+            ("Content-Disposition", content_disposition),
+        ],
+        data
+    ))
+}
+
+#[instrument(skip_all)]
+pub async fn get_depreciation_category_export(
+    State(state): State<AppState>,
+    Form(report_schema): Form<DepreciationReportSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = report_schema.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let report = state.crud_service.get_depreciation_report(report_schema)
+        .await
+        .map_err(|e| ApplicationError::InternalServerError(anyhow!(e)))?;
+
+    // parent rows include their subcategories, so only top level rows add up to the report total
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    wtr.write_record(["Category", "Level", "Items", "Cost", "Accumulated Depreciation", "Book Value"])
+        .map_err(|e| ApplicationError::InternalServerError(e.into()))?;
+
+    for row in report.category_rows.iter() {
+        wtr.write_record([
+            row.path(),
+            (row.depth() + 1).to_string(),
+            row.item_count.to_string(),
+            row.total_cost_display(),
+            row.accumulated_depreciation_display(),
+            row.book_value_display(),
+        ])
+        .map_err(|e| ApplicationError::InternalServerError(e.into()))?;
+    }
+
+    let data = wtr.into_inner().map_err(|e| ApplicationError::InternalServerError(anyhow!(e.to_string())))?;
+    let content_disposition = format!("attachment;filename=depreciation-categories-{}.csv", report.as_of);
+
+    Ok((
+        [
+            ("Content-Type", "text/csv".to_string()),
+            ("Content-Disposition", content_disposition),
+        ],
+        data
+    ))
+}
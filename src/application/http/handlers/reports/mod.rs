@@ -0,0 +1,15 @@
+pub mod depreciation;
+
+use axum::{middleware, routing::get, Router};
+
+use crate::application::{http::utils, state::AppState};
+
+
+pub fn router() -> Router<AppState>
+{
+    Router::new()
+        .route("/reports/depreciation", get(self::depreciation::get_depreciation_report))
+        .route("/reports/depreciation/export", get(self::depreciation::get_depreciation_report_export))
+        .route("/reports/depreciation/categories/export", get(self::depreciation::get_depreciation_category_export))
+        .route_layer(middleware::from_fn(utils::login_required))
+}
@@ -0,0 +1,14 @@
+pub mod saved_views;
+
+use axum::{middleware, routing::{delete, post}, Router};
+
+use crate::application::{http::utils, state::AppState};
+
+
+pub fn router() -> Router<AppState>
+{
+    Router::new()
+        .route("/saved_views", post(self::saved_views::post_saved_view))
+        .route("/saved_views/:id", delete(self::saved_views::delete_saved_view))
+        .route_layer(middleware::from_fn(utils::login_required))
+}
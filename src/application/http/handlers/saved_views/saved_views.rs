@@ -0,0 +1,58 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, Extension, Form};
+use axum_messages::Messages;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{errors::ApplicationError, saved_views::{saved_view_application_service::SavedViewError, schema::NewSavedViewSchema}, state::AppState, templates::partials::form_alert::FormAlertTemplate}, domain::identityaccess::model::users::SessionUser};
+
+
+#[instrument(skip_all)]
+pub async fn post_saved_view(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(new_saved_view): Form<NewSavedViewSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = new_saved_view.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let saved_view = match state.saved_view_service.add_saved_view(new_saved_view, session_user.user.id).await {
+        Ok(saved_view) => saved_view,
+        Err(SavedViewError::NameTaken) => {
+            let mut report = Report::new();
+            report.append(garde::Path::new("view_name"), garde::Error::new("you already have a view with this name"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    };
+
+    messages.success(format!("view {} saved", saved_view.name));
+    Ok(([("HX-Redirect", saved_view.url())], "success"))
+}
+
+#[instrument(skip_all)]
+pub async fn delete_saved_view(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let returned_id = state.saved_view_service.delete_saved_view(id, session_user.user.id)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    if returned_id.is_none() {
+        let mut report = Report::new();
+        report.append(garde::Path::new(""), garde::Error::new("only the owner can delete a view"));
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    messages.success("view deleted");
+    Ok(([("HX-Redirect", "/asset_items".to_string())], "success"))
+}
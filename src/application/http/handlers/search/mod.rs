@@ -0,0 +1,14 @@
+pub mod search;
+
+use axum::{middleware, routing::get, Router};
+
+use crate::application::{http::utils, state::AppState};
+
+
+pub fn router() -> Router<AppState>
+{
+    Router::new()
+        .route("/search", get(self::search::get_search))
+        .route("/search/suggestions", get(self::search::get_search_suggestions))
+        .route_layer(middleware::from_fn(utils::login_required))
+}
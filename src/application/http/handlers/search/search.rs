@@ -0,0 +1,45 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Query, State}, Extension};
+use axum_messages::Messages;
+use tracing::instrument;
+
+use crate::{application::{errors::ApplicationError, search::{schema::SearchSchema, search_application_service::{PAGE_LIMIT, SUGGESTION_LIMIT}}, state::AppState, templates::{pages::search::SearchTemplate, partials::search_suggestions::SearchSuggestionsTemplate}}, domain::identityaccess::model::users::SessionUser};
+
+
+/// Searches asset types, items, users, locations and the text of attached documents, grouped by kind
+#[instrument(skip_all)]
+pub async fn get_search(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Query(search): Query<SearchSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let query = search.q.unwrap_or_default();
+    let results = state.search_service.search(&query, PAGE_LIMIT)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], SearchTemplate::new(session_user, message, query, results)))
+}
+
+/// The navbar dropdown, a few of the best matches of each kind
+#[instrument(skip_all)]
+pub async fn get_search_suggestions(
+    State(state): State<AppState>,
+    Query(search): Query<SearchSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let query = search.q.unwrap_or_default();
+    let results = state.search_service.search(&query, SUGGESTION_LIMIT)
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], SearchSuggestionsTemplate::new(query, results)))
+}
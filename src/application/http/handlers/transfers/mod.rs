@@ -0,0 +1,21 @@
+pub mod transfers;
+pub mod transfer_create;
+pub mod transfer_view;
+
+use axum::{middleware, routing::{get, post}, Router};
+
+use crate::application::{http::utils, state::AppState};
+
+
+pub fn router() -> Router<AppState>
+{
+    Router::new()
+        .route("/transfers", get(self::transfers::get_transfers))
+        .route("/transfers", post(self::transfer_create::post_transfer_create))
+        .route("/transfers/new", get(self::transfer_create::get_transfer_create))
+        .route("/transfers/:id", get(self::transfer_view::get_transfer_view))
+        .route("/transfers/:id/scans", post(self::transfer_view::post_transfer_scan))
+        .route("/transfers/:id/receive", post(self::transfer_view::post_transfer_receive))
+        .route("/transfers/:id/cancel", post(self::transfer_view::post_transfer_cancel))
+        .route_layer(middleware::from_fn(utils::login_required))
+}
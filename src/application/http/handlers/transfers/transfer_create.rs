@@ -0,0 +1,75 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Query, State}, Extension};
+use axum_extra::extract::Form;
+use axum_messages::Messages;
+use garde::{Report, Validate};
+use tracing::instrument;
+
+use crate::{application::{errors::ApplicationError, state::AppState, templates::{pages::transfer_create::TransferCreateTemplate, partials::form_alert::FormAlertTemplate}, transfers::{schema::{NewTransferSchema, SchoolSchema}, transfer_application_service::TransferError}}, domain::identityaccess::model::users::SessionUser};
+
+
+#[instrument(skip_all)]
+pub async fn get_transfer_create(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Query(school): Query<SchoolSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let schools = state.transfer_service.get_schools()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let source_school = school.school
+        .map(|s| s.trim().to_string())
+        .and_then(|s| if s.is_empty() { None } else { Some(s) });
+
+    let asset_items = match &source_school {
+        Some(source_school) => state.transfer_service.get_school_asset_items(source_school)
+            .await
+            .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?,
+        None => Vec::new(),
+    };
+
+    Ok(([("Cache-Control", "no-store")], TransferCreateTemplate::new(session_user, message, schools, source_school, asset_items)))
+}
+
+#[instrument(skip_all)]
+pub async fn post_transfer_create(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(new_transfer): Form<NewTransferSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = new_transfer.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    let mut report = Report::new();
+    let transfer = match state.transfer_service.add_transfer(new_transfer, session_user.user.id).await {
+        Ok(transfer) => transfer,
+        Err(TransferError::SameSchool) => {
+            report.append(garde::Path::new("destination_school"), garde::Error::new("destination must be a different school"));
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(TransferError::InvalidItems(problems)) => {
+            for problem in problems {
+                report.append(garde::Path::new("ids"), garde::Error::new(problem));
+            }
+            return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+        },
+        Err(e) => {
+            return Err(ApplicationError::internal_server_error(anyhow!(e)));
+        },
+    };
+
+    messages.success(format!("transfer of {} item(s) created", transfer.item_count));
+    Ok(([("HX-Redirect", format!("/transfers/{}", transfer.id))], "success"))
+}
@@ -0,0 +1,95 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Path, State}, Extension, Form};
+use axum_messages::Messages;
+use garde::Validate;
+use tracing::instrument;
+
+use crate::{application::{errors::ApplicationError, state::AppState, templates::{pages::transfer_view::TransferViewTemplate, partials::{alert::AlertTemplate, form_alert::FormAlertTemplate}}, transfers::{schema::TransferScanSchema, transfer_application_service::TransferError}}, domain::identityaccess::model::users::SessionUser};
+
+
+fn transfer_error(e: TransferError) -> ApplicationError {
+    match e {
+        TransferError::NotFound => ApplicationError::not_found(anyhow!(e)),
+        TransferError::NotPending => ApplicationError::bad_request(anyhow!("transfer not pending"), AlertTemplate::error("global_alert_message", "this transfer is no longer pending").to_string()),
+        TransferError::NotInTransfer => ApplicationError::bad_request(anyhow!("not in transfer"), AlertTemplate::warning("global_alert_message", "the scanned item is not part of this transfer").to_string()),
+        _ => ApplicationError::internal_server_error(anyhow!(e)),
+    }
+}
+
+/// Renders the transfer page, scan requests select the item list out of it
+async fn render_transfer(state: &AppState, session_user: SessionUser, message: Option<axum_messages::Message>, id: i32) -> Result<impl IntoResponse, ApplicationError> {
+    let transfer = state.transfer_service.get_transfer(id)
+        .await
+        .map_err(transfer_error)?;
+
+    let transfer_items = state.transfer_service.get_transfer_items(id)
+        .await
+        .map_err(transfer_error)?;
+
+    Ok(([("Cache-Control", "no-store")], TransferViewTemplate::new(session_user, message, transfer, transfer_items)))
+}
+
+#[instrument(skip_all)]
+pub async fn get_transfer_view(
+    messages: Messages,
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    render_transfer(&state, session_user, message, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn post_transfer_scan(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Form(transfer_scan): Form<TransferScanSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    if let Err(report) = transfer_scan.validate() {
+        return Err(ApplicationError::bad_request(anyhow!("invalid form"), FormAlertTemplate::global_new(report).to_string()));
+    }
+
+    state.transfer_service.scan_transfer_item(id, transfer_scan)
+        .await
+        .map_err(transfer_error)?;
+
+    render_transfer(&state, session_user, None, id).await
+}
+
+#[instrument(skip_all)]
+pub async fn post_transfer_receive(
+    messages: Messages,
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let transfer = state.transfer_service.receive_transfer(id, session_user.user.id)
+        .await
+        .map_err(transfer_error)?;
+
+    messages.success(format!("{} item(s) received at {}", transfer.item_count, transfer.destination_display()));
+    Ok(([("HX-Redirect", format!("/transfers/{}", id))], "success"))
+}
+
+#[instrument(skip_all)]
+pub async fn post_transfer_cancel(
+    messages: Messages,
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    state.transfer_service.cancel_transfer(id)
+        .await
+        .map_err(transfer_error)?;
+
+    messages.success("transfer cancelled");
+    Ok(([("HX-Redirect", format!("/transfers/{}", id))], "success"))
+}
@@ -0,0 +1,41 @@
+use anyhow::anyhow;
+
+use askama_axum::IntoResponse;
+use axum::{extract::{Query, State}, Extension};
+use axum_messages::Messages;
+use tracing::instrument;
+
+use crate::{application::{errors::ApplicationError, state::AppState, templates::pages::transfers::TransfersTemplate, transfers::schema::SchoolSchema}, domain::identityaccess::model::users::SessionUser};
+
+
+/// Lists every transfer, picking a school turns the page into that school's dashboard of pending transfers
+#[instrument(skip_all)]
+pub async fn get_transfers(
+    messages: Messages,
+    State(state): State<AppState>,
+    Extension(session_user): Extension<SessionUser>,
+    Query(school): Query<SchoolSchema>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let message = messages
+        .into_iter()
+        .collect::<Vec<_>>()
+        .first()
+        .map(|m| m.to_owned());
+
+    let schools = state.transfer_service.get_schools()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    let school_transfers = match school.school.as_deref().map(str::trim) {
+        Some("") | None => None,
+        Some(school) => Some(state.transfer_service.get_school_transfers(school)
+            .await
+            .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?),
+    };
+
+    let transfers = state.transfer_service.get_transfers()
+        .await
+        .map_err(|e| ApplicationError::internal_server_error(anyhow!(e)))?;
+
+    Ok(([("Cache-Control", "no-store")], TransfersTemplate::new(session_user, message, schools, school_transfers, transfers)))
+}
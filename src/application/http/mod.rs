@@ -1,3 +1,4 @@
 pub mod server;
 pub mod handlers;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod multipart;
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use axum_typed_multipart::{BaseMultipart, FieldMetadata, TryFromChunks, TypedMultipartError};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use garde::Report;
+
+use crate::{application::{errors::ApplicationError, templates::partials::form_alert::FormAlertTemplate}, domain::filesystem::{models::HashedTempFile, persistence_service::{HashingWriter, PersistenceError}}};
+
+
+/// Uploads are hashed chunk by chunk while they are written, instead of reading the temp file back afterwards.
+/// The route's body limit from `[uploads]` is enforced by the chunk stream, an oversized upload errors before
+/// the rest is received and the partial temp file is removed on drop.
+#[async_trait]
+impl TryFromChunks for HashedTempFile {
+    async fn try_from_chunks(
+        mut chunks: impl Stream<Item = Result<Bytes, TypedMultipartError>> + Send + Sync + Unpin,
+        _: FieldMetadata,
+    ) -> Result<Self, TypedMultipartError> {
+        let mut writer = HashingWriter::new().map_err(upload_error)?;
+
+        while let Some(chunk) = chunks.next().await {
+            writer.write(&chunk?).await.map_err(upload_error)?;
+        }
+
+        writer.finish().await.map_err(upload_error)
+    }
+}
+
+fn upload_error(e: PersistenceError) -> TypedMultipartError {
+    TypedMultipartError::Other { source: anyhow::Error::new(e).context("could not store upload") }
+}
+
+/// TypedMultipart that reports a rejected upload in the form alert, like any other invalid form, instead of a bare status
+pub type UploadMultipart<T> = BaseMultipart<T, UploadRejection>;
+
+pub struct UploadRejection(TypedMultipartError);
+
+impl From<TypedMultipartError> for UploadRejection {
+    fn from(e: TypedMultipartError) -> Self {
+        Self(e)
+    }
+}
+
+impl IntoResponse for UploadRejection {
+    fn into_response(self) -> Response {
+        let message = match &self.0 {
+            // the body limit of the route cut the upload off
+            TypedMultipartError::InvalidRequestBody { source } if source.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                "the file is too large".to_string()
+            },
+            TypedMultipartError::FieldTooLarge { .. } => "the file is too large".to_string(),
+            TypedMultipartError::MissingField { field_name } => format!("{} is required", field_name.replace('_', " ")),
+            _ => "the upload could not be read, try again".to_string(),
+        };
+
+        let mut report = Report::new();
+        report.append(garde::Path::new("upload"), garde::Error::new(message));
+
+        ApplicationError::bad_request(anyhow::Error::new(self.0), FormAlertTemplate::global_new(report).to_string()).into_response()
+    }
+}
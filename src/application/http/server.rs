@@ -2,15 +2,14 @@ use anyhow::{Context, Result};
 use axum::{http::StatusCode, response::IntoResponse, routing::get, Json};
 use axum_extra::extract::cookie::SameSite;
 use axum_login::{tower_sessions::{Expiry, SessionManagerLayer}, AuthManagerLayerBuilder};
-use tower_sessions::cookie::Key;
 use time::Duration;
 use tokio::net::TcpListener;
 use tower_http::{compression::{predicate::{NotForContentType, SizeAbove}, Predicate}, services::ServeDir};
 use tracing::instrument;
 use axum_messages::MessagesManagerLayer;
 
-use crate::{application::state::AppState, settings::ApplicationConfig};
-use super::handlers::{account, asset_items, asset_types, auth, oauth};
+use crate::{application::state::AppState, settings::{ApplicationConfig, UploadsConfig}};
+use super::handlers::{account, asset_items, asset_types, audits, auth, categories, content, oauth, reports, saved_views, search, transfers};
 
 
 #[instrument]
@@ -26,7 +25,7 @@ pub struct AppHttpServer {
 }
 
 impl AppHttpServer {
-    pub async fn new(config: &ApplicationConfig, app_state: AppState) -> Result<Self> {
+    pub async fn new(config: &ApplicationConfig, uploads: &UploadsConfig, app_state: AppState) -> Result<Self> {
         let trace_layer = tower_http::trace::TraceLayer::new_for_http();
 
         let compression_predicate = SizeAbove::new(256)
@@ -53,13 +52,19 @@ impl AppHttpServer {
 
         let router = axum::Router::new()
             .route("/healthchecker", get(health_checker_handler))
-            .merge(account::router())
+            .merge(account::router(uploads))
             .merge(oauth::router())
             .merge(auth::router())
-            .merge(asset_types::router())
-            .merge(asset_items::router())
+            .merge(asset_types::router(uploads))
+            .merge(asset_items::router(uploads))
+            .merge(reports::router())
+            .merge(audits::router())
+            .merge(categories::router())
+            .merge(transfers::router())
+            .merge(search::router())
+            .merge(saved_views::router())
+            .merge(content::router())
             .nest_service("/static", ServeDir::new("static").precompressed_gzip())
-            .nest_service("/content", ServeDir::new("content"))
             .layer(compression_layer)
             .layer(trace_layer)
             .layer(MessagesManagerLayer)
@@ -69,6 +74,7 @@ impl AppHttpServer {
         Ok(Self {port, router, listener})
     }
 
+    #[allow(dead_code)]
     pub fn port(&self) -> u16 {
         self.port
     }
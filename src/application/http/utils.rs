@@ -1,12 +1,12 @@
 use anyhow::anyhow;
 use askama_axum::IntoResponse;
 use axum::{
-    extract::Request, http::{HeaderMap, StatusCode}, middleware::{self, Next}, response::{Redirect, Response}, routing::get, RequestExt, Router
+    extract::Request, http::StatusCode, middleware::Next, response::{Redirect, Response}, RequestExt
 };
 use axum_login::AuthSession;
 use axum_messages::Messages;
 
-use crate::{application::{errors::ApplicationError, identityaccess::identity_application_service::IdentityApplicationService}, domain::identityaccess::model::{user_repository::UserRepository, users::SessionUser}};
+use crate::{application::{errors::ApplicationError, identityaccess::identity_application_service::IdentityApplicationService, templates::partials::alert::AlertTemplate}, domain::identityaccess::model::users::SessionUser};
 
 
 pub async fn login_required(
@@ -33,6 +33,24 @@ pub async fn login_required(
    }
 }
 
+/// Must run after login_required, which puts the session user on the request
+pub async fn admin_required(
+    request: Request,
+    next: Next,
+) -> Result<Response, ApplicationError> {
+    let is_admin = request.extensions()
+        .get::<SessionUser>()
+        .is_some_and(|session_user| session_user.is_admin());
+
+    if !is_admin {
+        let alert = AlertTemplate::error("global_alert_message", "You need the admin role to do this.");
+        return Err(ApplicationError::forbidden(anyhow!("admin role required"), alert.to_string()));
+    }
+
+    let response = next.run(request).await;
+    Ok(response)
+}
+
 pub async fn public_only(
     auth_session: AuthSession<IdentityApplicationService>,
     mut request: Request,
@@ -139,6 +139,7 @@ impl IdentityApplicationService
             .map_err(|e| e.into())
     }
 
+    #[allow(dead_code)]
     pub fn google_client_id(&self) -> String {
         self.google_oauth.client_id()
     }
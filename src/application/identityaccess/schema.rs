@@ -2,7 +2,8 @@ use axum_typed_multipart::{FieldData, TryFromMultipart};
 use oauth2::CsrfToken;
 use serde::{Deserialize};
 use garde::Validate;
-use tempfile::NamedTempFile;
+
+use crate::domain::filesystem::models::HashedTempFile;
 
 
 #[derive(Debug, TryFromMultipart)]
@@ -21,8 +22,8 @@ pub struct NewUserSchema {
     #[garde(skip)]	
     pub role_id: i32,
     #[garde(skip)]	
-    #[form_data(limit = "5MiB")]
-    pub picture: Option<FieldData<NamedTempFile>>,
+    #[form_data(limit = "unlimited")]
+    pub picture: Option<FieldData<HashedTempFile>>,
 }
 
 #[derive(Debug, TryFromMultipart)]
@@ -35,15 +36,15 @@ pub struct UpdateUserSchema {
     #[garde(skip)]	
     pub role_id: i32,
     #[garde(skip)]	
-    #[form_data(limit = "5MiB")]
-    pub picture: Option<FieldData<NamedTempFile>>,
+    #[form_data(limit = "unlimited")]
+    pub picture: Option<FieldData<HashedTempFile>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum OauthSchema {
     Success { code: String, state: CsrfToken },
-    Error { error: String, state: CsrfToken },
+    Error { error: String, #[allow(dead_code)] state: CsrfToken },
 }
 
 #[derive(Debug, Clone, Deserialize)]
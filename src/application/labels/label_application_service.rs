@@ -0,0 +1,102 @@
+use crate::{domain::{crud::model::asset_items::AssetItem, labels::{code128, sheets::{find_sheet, render_label_sheet, LabelData, AVERY_SHEETS}, symbols::{barcode_png, barcode_svg, qr_png, qr_svg, LabelFormat, LabelSymbology}}}, telemetry::spawn_blocking_with_tracing};
+
+
+#[derive(Debug, thiserror::Error)]
+pub enum LabelError {
+    #[error("unknown label sheet")]
+    UnknownSheet,
+    #[error("no asset items to print")]
+    NoItems,
+    #[error("the asset id has characters a barcode cannot hold")]
+    NoBarcode,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct LabelApplicationService {
+    base_url: String,
+}
+
+impl LabelApplicationService {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Absolute link encoded in the qr code so a phone camera opens the item page
+    pub fn asset_item_link(&self, asset_item: &AssetItem) -> String {
+        format!("{}/asset_items/{}", self.base_url, asset_item.id)
+    }
+
+    pub async fn get_asset_item_symbol(&self, asset_item: &AssetItem, symbology: LabelSymbology, format: LabelFormat) -> Result<Vec<u8>, LabelError> {
+        let value = match symbology {
+            LabelSymbology::Barcode => {
+                let value = barcode_value(asset_item);
+                if !code128::can_encode(&value) {
+                    return Err(LabelError::NoBarcode);
+                }
+                value
+            },
+            LabelSymbology::Qr => self.asset_item_link(asset_item),
+        };
+
+        let symbol = spawn_blocking_with_tracing(move || {
+            match (symbology, format) {
+                (LabelSymbology::Barcode, LabelFormat::Svg) => barcode_svg(&value).map(String::into_bytes),
+                (LabelSymbology::Barcode, LabelFormat::Png) => barcode_png(&value),
+                (LabelSymbology::Qr, LabelFormat::Svg) => qr_svg(&value).map(String::into_bytes),
+                (LabelSymbology::Qr, LabelFormat::Png) => qr_png(&value),
+            }
+        })
+        .await
+        .map_err(anyhow::Error::from)??;
+
+        Ok(symbol)
+    }
+
+    pub async fn get_label_sheet(&self, asset_items: &[AssetItem], sheet: Option<String>) -> Result<Vec<u8>, LabelError> {
+        let sheet = match sheet.as_deref() {
+            None | Some("") => AVERY_SHEETS[0],
+            Some(sheet) => find_sheet(sheet).ok_or(LabelError::UnknownSheet)?,
+        };
+
+        if asset_items.is_empty() {
+            return Err(LabelError::NoItems);
+        }
+
+        let labels: Vec<LabelData> = asset_items.iter()
+            .map(|asset_item| LabelData {
+                title: label_title(asset_item),
+                barcode_value: barcode_value(asset_item),
+                link: self.asset_item_link(asset_item),
+            })
+            .collect();
+
+        // pdf layout is cpu bound and the printpdf document is not Send
+        let pdf = spawn_blocking_with_tracing(move || render_label_sheet(&sheet, &labels))
+            .await
+            .map_err(anyhow::Error::from)??;
+
+        Ok(pdf)
+    }
+}
+
+/// Items without an asset tag fall back to the database id so every item can be labeled
+fn barcode_value(asset_item: &AssetItem) -> String {
+    asset_item.asset_id.clone()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| asset_item.id.to_string())
+}
+
+fn label_title(asset_item: &AssetItem) -> String {
+    if let Some(name) = asset_item.name.as_ref().filter(|s| !s.is_empty()) {
+        return name.clone();
+    }
+
+    match (&asset_item.brand, &asset_item.model) {
+        (Some(brand), Some(model)) => format!("{} {}", brand, model),
+        _ => asset_item.serial_number.clone().unwrap_or_default(),
+    }
+}
@@ -0,0 +1,2 @@
+pub mod label_application_service;
+pub mod schema;
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use garde::Validate;
+
+use crate::{application::crud::schema::FilterSchema, domain::labels::sheets::find_sheet};
+
+
+/// The asset items filter form plus the sheet to print on, every item matching the filter gets a label
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct LabelSheetSchema {
+    #[garde(custom(validate_sheet))]
+    pub sheet: Option<String>,
+    #[garde(dive)]
+    #[serde(flatten)]
+    pub filter: FilterSchema,
+}
+
+fn validate_sheet(value: &Option<String>, _: &()) -> garde::Result {
+    match value.as_deref() {
+        None | Some("") => garde::Result::Ok(()),
+        Some(sheet) => match find_sheet(sheet) {
+            Some(_) => garde::Result::Ok(()),
+            None => garde::Result::Err(garde::Error::new("unknown label sheet")),
+        },
+    }
+}
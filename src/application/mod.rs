@@ -2,6 +2,11 @@ pub mod http;
 pub mod identityaccess;
 pub mod content;
 pub mod crud;
+pub mod labels;
+pub mod audits;
+pub mod transfers;
+pub mod search;
+pub mod saved_views;
 pub mod state;
 pub mod errors;
 pub mod templates;
\ No newline at end of file
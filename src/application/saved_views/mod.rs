@@ -0,0 +1,2 @@
+pub mod saved_view_application_service;
+pub mod schema;
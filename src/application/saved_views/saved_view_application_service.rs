@@ -0,0 +1,64 @@
+use uuid::Uuid;
+
+use crate::{application::crud::crud_application_service::{asset_item_filter, CrudError}, domain::{identityaccess::model::users::SessionUser, saved_views::{model::saved_views::{ColumnSelection, NewSavedView, SavedView}, saved_view_repository::{SavedViewRepository, SavedViewRepositoryError}}}, infastructure::services::postgres_saved_view_repository::PostgresSavedViewRepository};
+
+use super::schema::NewSavedViewSchema;
+
+
+#[derive(Debug, thiserror::Error)]
+pub enum SavedViewError {
+    #[error(transparent)]
+    Repo(#[from] SavedViewRepositoryError),
+    #[error(transparent)]
+    Crud(#[from] CrudError),
+    #[error("a view with this name already exists")]
+    NameTaken,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct SavedViewApplicationService {
+    saved_view_repo: PostgresSavedViewRepository,
+}
+
+impl SavedViewApplicationService {
+    pub fn new(saved_view_repo: PostgresSavedViewRepository) -> Self {
+        Self{saved_view_repo}
+    }
+
+    /// Views listed in the sidebar, the user's own and the ones shared with their role
+    pub async fn get_saved_views(&self, session_user: &SessionUser) -> Result<Vec<SavedView>, SavedViewError> {
+        let saved_views = self.saved_view_repo.get_saved_views(session_user.user.id, session_user.user.role.clone()).await?;
+
+        Ok(saved_views)
+    }
+
+    pub async fn add_saved_view(&self, schema: NewSavedViewSchema, user_id: Uuid) -> Result<SavedView, SavedViewError> {
+        let columns = ColumnSelection::parse(schema.filter.columns.as_deref());
+        let shared_role_id = match schema.shared_role_id.as_deref() {
+            Some("") | None => None,
+            Some(id) => Some(id.parse::<i32>().map_err(|e| SavedViewError::Unknown(e.into()))?),
+        };
+
+        let new_saved_view = NewSavedView {
+            user_id,
+            name: schema.view_name.trim().to_string(),
+            filter: asset_item_filter(schema.filter)?,
+            columns,
+            shared_role_id,
+        };
+
+        match self.saved_view_repo.add_saved_view(new_saved_view).await {
+            Ok(saved_view) => Ok(saved_view),
+            Err(SavedViewRepositoryError::Conflict) => Err(SavedViewError::NameTaken),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn delete_saved_view(&self, id: i32, user_id: Uuid) -> Result<Option<i32>, SavedViewError> {
+        let returned_id = self.saved_view_repo.delete_saved_view(id, user_id).await?;
+
+        Ok(returned_id)
+    }
+}
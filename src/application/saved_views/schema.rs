@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use garde::Validate;
+
+use crate::application::crud::schema::{validate_id, FilterSchema};
+
+
+/// Posted together with the asset items filter form, so the view stores whatever is filtered right now
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct NewSavedViewSchema {
+    #[garde(length(min=1, max=100))]
+    pub view_name: String,
+    #[garde(custom(validate_id))]
+    pub shared_role_id: Option<String>,
+    #[garde(dive)]
+    #[serde(flatten)]
+    pub filter: FilterSchema,
+}
@@ -0,0 +1,2 @@
+pub mod search_application_service;
+pub mod schema;
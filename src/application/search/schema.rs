@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use garde::Validate;
+
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct SearchSchema {
+    #[garde(skip)]
+    pub q: Option<String>,
+}
@@ -0,0 +1,48 @@
+use crate::{domain::search::{model::search::{prefix_tsquery, SearchResults}, search_repository::{SearchRepository, SearchRepositoryError}}, infastructure::services::postgres_search_repository::PostgresSearchRepository};
+
+
+/// Results per group in the navbar dropdown
+pub const SUGGESTION_LIMIT: i64 = 5;
+/// Results per group on the search page, a document attached to several assets shows once per asset
+pub const PAGE_LIMIT: i64 = 25;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SearchError {
+    #[error(transparent)]
+    Repo(#[from] SearchRepositoryError),
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchApplicationService {
+    search_repo: PostgresSearchRepository,
+}
+
+impl SearchApplicationService {
+    pub fn new(search_repo: PostgresSearchRepository) -> Self {
+        Self{search_repo}
+    }
+
+    /// Searches every kind of record at once, up to limit of each
+    pub async fn search(&self, query: &str, limit: i64) -> Result<SearchResults, SearchError> {
+        let text = query.trim();
+        let Some(tsquery) = prefix_tsquery(text) else {
+            return Ok(SearchResults::default());
+        };
+
+        let (asset_types, asset_items, users, locations, documents) = tokio::try_join!(
+            self.search_repo.search_asset_types(tsquery.clone(), text.to_string(), limit),
+            self.search_repo.search_asset_items(tsquery.clone(), text.to_string(), limit),
+            self.search_repo.search_users(tsquery.clone(), text.to_string(), limit),
+            self.search_repo.search_locations(tsquery, text.to_string(), limit),
+            self.search_repo.search_documents(text.to_string(), limit),
+        )?;
+
+        Ok(SearchResults {
+            asset_types,
+            asset_items,
+            users,
+            locations: locations.into_iter().map(Into::into).collect(),
+            documents,
+        })
+    }
+}
@@ -1,9 +1,7 @@
 use axum::extract::FromRef;
-use reqwest::Client;
 
-use crate::domain::identityaccess::model::user_repository::UserRepository;
 
-use super::{content::content_application_service::ContentApplicationService, crud::crud_application_service::CrudApplicationService, identityaccess::identity_application_service::IdentityApplicationService};
+use super::{audits::audit_application_service::AuditApplicationService, content::content_application_service::ContentApplicationService, crud::crud_application_service::CrudApplicationService, identityaccess::identity_application_service::IdentityApplicationService, labels::label_application_service::LabelApplicationService, saved_views::saved_view_application_service::SavedViewApplicationService, search::search_application_service::SearchApplicationService, transfers::transfer_application_service::TransferApplicationService};
 
 
 #[derive(Debug, Clone)]
@@ -11,6 +9,11 @@ pub struct AppState {
     pub identity_service: IdentityApplicationService,
     pub content_service: ContentApplicationService,
     pub crud_service: CrudApplicationService,
+    pub label_service: LabelApplicationService,
+    pub audit_service: AuditApplicationService,
+    pub transfer_service: TransferApplicationService,
+    pub search_service: SearchApplicationService,
+    pub saved_view_service: SavedViewApplicationService,
 }
 
 impl FromRef<AppState> for IdentityApplicationService
@@ -34,13 +37,53 @@ impl FromRef<AppState> for CrudApplicationService
     }
 }
 
+impl FromRef<AppState> for LabelApplicationService
+{
+    fn from_ref(input: &AppState) -> Self {
+        input.label_service.clone()
+    }
+}
+
+impl FromRef<AppState> for AuditApplicationService
+{
+    fn from_ref(input: &AppState) -> Self {
+        input.audit_service.clone()
+    }
+}
+
+impl FromRef<AppState> for TransferApplicationService
+{
+    fn from_ref(input: &AppState) -> Self {
+        input.transfer_service.clone()
+    }
+}
+
+impl FromRef<AppState> for SearchApplicationService
+{
+    fn from_ref(input: &AppState) -> Self {
+        input.search_service.clone()
+    }
+}
+
+impl FromRef<AppState> for SavedViewApplicationService
+{
+    fn from_ref(input: &AppState) -> Self {
+        input.saved_view_service.clone()
+    }
+}
+
 impl AppState
 {
-    pub fn new(identity_service: IdentityApplicationService, crud_service: CrudApplicationService, content_service: ContentApplicationService) -> Self {
+    pub fn new(identity_service: IdentityApplicationService, crud_service: CrudApplicationService, content_service: ContentApplicationService, label_service: LabelApplicationService, audit_service: AuditApplicationService, transfer_service: TransferApplicationService, search_service: SearchApplicationService, saved_view_service: SavedViewApplicationService) -> Self {
         Self {
             identity_service,
             content_service,
             crud_service,
+            label_service,
+            audit_service,
+            transfer_service,
+            search_service,
+            saved_view_service,
         }
     }
 }
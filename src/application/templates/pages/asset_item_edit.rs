@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::asset_items::AssetItem, identityaccess::model::users::SessionUser}};
+use crate::{application::templates::partials::{alert::AlertTemplate, custom_fields_form::CustomFieldsFormTemplate, navbar::NavbarTemplate}, domain::{crud::model::{asset_items::AssetItem, asset_types::AssetType, custom_fields::CustomField}, identityaccess::model::users::SessionUser}};
 
 
 #[derive(Template)]
@@ -10,12 +10,15 @@ pub struct AssetItemEditTemplate {
     navbar: NavbarTemplate,
     alert: Option<AlertTemplate>,
     asset_item: AssetItem,
+    asset_types: Vec<AssetType>,
+    custom_fields_form: CustomFieldsFormTemplate,
 }
 
 impl AssetItemEditTemplate {
-    pub fn new(session_user: SessionUser, message: Option<Message>, asset_item: AssetItem) -> Self {
+    pub fn new(session_user: SessionUser, message: Option<Message>, asset_item: AssetItem, asset_types: Vec<AssetType>, custom_fields: Vec<CustomField>) -> Self {
         let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
         let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
-        Self {navbar, alert, asset_item}
+        let custom_fields_form = CustomFieldsFormTemplate::new(custom_fields, asset_item.custom_fields.clone());
+        Self {navbar, alert, asset_item, asset_types, custom_fields_form}
     }
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::{asset_items::AssetItem, asset_types::AssetType}, identityaccess::model::users::{SessionUser, UserDescriptor}}};
+use crate::{application::templates::partials::{alert::AlertTemplate, documents::DocumentsTemplate, gallery::GalleryTemplate, navbar::NavbarTemplate}, domain::{crud::model::{documents::AssetDocument, images::AssetImage, asset_items::AssetItem, custom_fields::{display_custom_values, CustomField, CustomFieldValue}, history::AssetItemHistoryEntry}, identityaccess::model::users::SessionUser}};
 
 
 #[derive(Template)]
@@ -10,12 +10,19 @@ pub struct AssetItemViewTemplate {
     navbar: NavbarTemplate,
     alert: Option<AlertTemplate>,
     asset_item: AssetItem,
+    custom_values: Vec<CustomFieldValue>,
+    history: Vec<AssetItemHistoryEntry>,
+    documents: DocumentsTemplate,
+    gallery: GalleryTemplate,
 }
 
 impl AssetItemViewTemplate {
-    pub fn new(session_user: SessionUser, message: Option<Message>, asset_item: AssetItem) -> Self {
+    pub fn new(session_user: SessionUser, message: Option<Message>, asset_item: AssetItem, custom_fields: Vec<CustomField>, history: Vec<AssetItemHistoryEntry>, documents: Vec<AssetDocument>, images: Vec<AssetImage>) -> Self {
         let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
         let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
-        Self {navbar, alert, asset_item}
+        let custom_values = display_custom_values(&custom_fields, &asset_item.custom_fields);
+        let documents = DocumentsTemplate::new(format!("/asset_items/{}", asset_item.id), documents);
+        let gallery = GalleryTemplate::new(format!("/asset_items/{}", asset_item.id), images);
+        Self {navbar, alert, asset_item, custom_values, history, documents, gallery}
     }
 }
\ No newline at end of file
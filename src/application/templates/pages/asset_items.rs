@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::{asset_items::AssetItem, asset_types::{AssetType, AssetTypeFilter}}, identityaccess::model::users::SessionUser}};
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate, saved_views::SavedViewsTemplate}, domain::{crud::model::{asset_items::{AssetItem, AssetItemFilter, ASSET_ITEM_STATUSES}, asset_types::AssetType, categories::Category}, identityaccess::model::users::SessionUser, labels::sheets::{LabelSheet, AVERY_SHEETS}, saved_views::model::saved_views::{ColumnSelection, ASSET_ITEM_COLUMNS}}};
 
 
 #[derive(Template)]
@@ -10,12 +10,47 @@ pub struct AssetItemsTemplate {
     navbar: NavbarTemplate,
     alert: Option<AlertTemplate>,
     asset_items: Vec<AssetItem>,
+    filter: AssetItemFilter,
+    label_sheets: Vec<LabelSheet>,
+    categories: Vec<Category>,
+    asset_types: Vec<AssetType>,
+    statuses: Vec<&'static str>,
+    columns: ColumnSelection,
+    column_choices: Vec<(&'static str, &'static str)>,
+    saved_views: SavedViewsTemplate,
 }
 
 impl AssetItemsTemplate {
-    pub fn new(session_user: SessionUser, message: Option<Message>, asset_items: Vec<AssetItem>) -> Self {
+    pub fn new(session_user: SessionUser, message: Option<Message>, asset_items: Vec<AssetItem>, filter: AssetItemFilter, columns: ColumnSelection, categories: Vec<Category>, asset_types: Vec<AssetType>, saved_views: SavedViewsTemplate) -> Self {
         let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
         let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
-        Self {navbar, alert, asset_items}
+        let label_sheets = AVERY_SHEETS.to_vec();
+        let statuses = ASSET_ITEM_STATUSES.to_vec();
+        let column_choices = ASSET_ITEM_COLUMNS.to_vec();
+        Self {navbar, alert, asset_items, filter, label_sheets, categories, asset_types, statuses, columns, column_choices, saved_views}
+    }
+
+    pub fn is_filtered_category(&self, id: &i32) -> bool {
+        self.filter.category_id == Some(*id)
+    }
+
+    pub fn is_filtered_status(&self, status: &str) -> bool {
+        self.filter.status.as_deref() == Some(status)
+    }
+
+    /// Hidden columns stay in the page so they can be shown again without a reload
+    pub fn column_class(&self, column: &str) -> &'static str {
+        match self.columns.shows(column) {
+            true => "",
+            false => "hidden",
+        }
+    }
+
+    pub fn sort_icon(&self, row: &str) -> &'static str {
+        match (self.filter.sort.as_deref(), self.filter.order.as_deref()) {
+            (Some(sort), Some("ASC")) if sort == row => "↑",
+            (Some(sort), Some("DESC")) if sort == row => "↓",
+            _ => "",
+        }
     }
 }
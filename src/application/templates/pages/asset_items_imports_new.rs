@@ -0,0 +1,20 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::identityaccess::model::users::SessionUser};
+
+
+#[derive(Template)]
+#[template(path = "pages/asset_items_imports_new.html", escape = "none")]
+pub struct AssetItemsImportsNew {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+}
+
+impl AssetItemsImportsNew {
+    pub fn new(session_user: SessionUser, message: Option<Message>) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert}
+    }
+}
@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::identityaccess::model::users::{SessionUser, UserDescriptor}};
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::categories::Category, identityaccess::model::users::SessionUser}};
 
 
 #[derive(Template)]
@@ -9,12 +9,13 @@ use crate::{application::templates::partials::{alert::AlertTemplate, navbar::Nav
 pub struct AssetTypeCreateTemplate {
     navbar: NavbarTemplate,
     alert: Option<AlertTemplate>,
+    categories: Vec<Category>,
 }
 
 impl AssetTypeCreateTemplate {
-    pub fn new(session_user: SessionUser, message: Option<Message>) -> Self {
+    pub fn new(session_user: SessionUser, message: Option<Message>, categories: Vec<Category>) -> Self {
         let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
         let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
-        Self {navbar, alert}
+        Self {navbar, alert, categories}
     }
 }
\ No newline at end of file
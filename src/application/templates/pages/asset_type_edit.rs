@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::asset_types::AssetType, identityaccess::model::users::{SessionUser, UserDescriptor}}};
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::{asset_types::AssetType, categories::Category}, identityaccess::model::users::SessionUser}};
 
 
 #[derive(Template)]
@@ -10,12 +10,17 @@ pub struct AssetTypeEditTemplate {
     navbar: NavbarTemplate,
     alert: Option<AlertTemplate>,
     asset_type: AssetType,
+    categories: Vec<Category>,
 }
 
 impl AssetTypeEditTemplate {
-    pub fn new(session_user: SessionUser, message: Option<Message>, asset_type: AssetType) -> Self {
+    pub fn new(session_user: SessionUser, message: Option<Message>, asset_type: AssetType, categories: Vec<Category>) -> Self {
         let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
         let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
-        Self {navbar, alert, asset_type}
+        Self {navbar, alert, asset_type, categories}
+    }
+
+    fn is_category(&self, id: &i32) -> bool {
+        self.asset_type.category_id == Some(*id)
     }
 }
\ No newline at end of file
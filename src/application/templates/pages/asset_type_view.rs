@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::asset_types::AssetType, identityaccess::model::users::{SessionUser, UserDescriptor}}};
+use crate::{application::templates::partials::{alert::AlertTemplate, documents::DocumentsTemplate, gallery::GalleryTemplate, navbar::NavbarTemplate}, domain::{crud::model::{asset_types::AssetType, categories::Category, custom_fields::{CustomField, CUSTOM_FIELD_TYPES}, documents::AssetDocument, images::AssetImage}, identityaccess::model::users::SessionUser}};
 
 
 #[derive(Template)]
@@ -10,12 +10,22 @@ pub struct AssetTypeViewTemplate {
     navbar: NavbarTemplate,
     alert: Option<AlertTemplate>,
     asset_type: AssetType,
+    category: Option<Category>,
+    item_count: i64,
+    reassign_types: Vec<AssetType>,
+    custom_fields: Vec<CustomField>,
+    field_types: Vec<&'static str>,
+    documents: DocumentsTemplate,
+    gallery: GalleryTemplate,
 }
 
 impl AssetTypeViewTemplate {
-    pub fn new(session_user: SessionUser, message: Option<Message>, asset_type: AssetType) -> Self {
+    pub fn new(session_user: SessionUser, message: Option<Message>, asset_type: AssetType, category: Option<Category>, item_count: i64, reassign_types: Vec<AssetType>, custom_fields: Vec<CustomField>, documents: Vec<AssetDocument>, images: Vec<AssetImage>) -> Self {
         let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
         let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
-        Self {navbar, alert, asset_type}
+        let field_types = CUSTOM_FIELD_TYPES.to_vec();
+        let documents = DocumentsTemplate::new(format!("/asset_types/{}", asset_type.id), documents);
+        let gallery = GalleryTemplate::new(format!("/asset_types/{}", asset_type.id), images);
+        Self {navbar, alert, asset_type, category, item_count, reassign_types, custom_fields, field_types, documents, gallery}
     }
 }
\ No newline at end of file
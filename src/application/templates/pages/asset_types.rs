@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::asset_types::{AssetType, AssetTypeFilter}, identityaccess::model::users::SessionUser}};
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::{asset_types::{AssetType, AssetTypeFilter}, categories::{category_path_display, Category}}, identityaccess::model::users::SessionUser}};
 
 
 #[derive(Template)]
@@ -11,15 +11,25 @@ pub struct AssetTypesTemplate {
     alert: Option<AlertTemplate>,
     asset_types: Vec<AssetType>,
     filter: AssetTypeFilter,
+    suggestion: Option<String>,
+    categories: Vec<Category>,
 }
 
 impl AssetTypesTemplate {
-    pub fn new(session_user: SessionUser, message: Option<Message>, asset_types: Vec<AssetType>, filter: AssetTypeFilter) -> Self {
+    pub fn new(session_user: SessionUser, message: Option<Message>, asset_types: Vec<AssetType>, filter: AssetTypeFilter, suggestion: Option<String>, categories: Vec<Category>) -> Self {
         let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
         let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
-        Self {navbar, alert, asset_types, filter}
+        Self {navbar, alert, asset_types, filter, suggestion, categories}
     }
     
+    pub fn category_path(&self, category_id: &Option<i32>) -> String {
+        category_path_display(&self.categories, *category_id)
+    }
+
+    pub fn is_filtered_category(&self, id: &i32) -> bool {
+        self.filter.category_id == Some(*id)
+    }
+
     pub fn sort_icon(&self, row: &str) -> &'static str {
         let order: Option<&str> = self.filter.order.as_deref();
         let sort: Option<&str> = self.filter.sort.as_deref();
@@ -0,0 +1,22 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{audits::model::audits::{Audit, AuditReconciliation}, identityaccess::model::users::SessionUser}};
+
+
+#[derive(Template)]
+#[template(path = "pages/audit_view.html", escape = "none")]
+pub struct AuditViewTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    audit: Audit,
+    reconciliation: AuditReconciliation,
+}
+
+impl AuditViewTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, audit: Audit, reconciliation: AuditReconciliation) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, audit, reconciliation}
+    }
+}
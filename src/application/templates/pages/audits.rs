@@ -0,0 +1,21 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{audits::model::audits::Audit, identityaccess::model::users::SessionUser}};
+
+
+#[derive(Template)]
+#[template(path = "pages/audits.html", escape = "none")]
+pub struct AuditsTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    audits: Vec<Audit>,
+}
+
+impl AuditsTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, audits: Vec<Audit>) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, audits}
+    }
+}
@@ -0,0 +1,21 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::categories::Category, identityaccess::model::users::SessionUser}};
+
+
+#[derive(Template)]
+#[template(path = "pages/categories.html", escape = "none")]
+pub struct CategoriesTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    categories: Vec<Category>,
+}
+
+impl CategoriesTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, categories: Vec<Category>) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, categories}
+    }
+}
@@ -0,0 +1,30 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::{categories::{Category, CategoryField}, custom_fields::CUSTOM_FIELD_TYPES}, identityaccess::model::users::SessionUser}};
+
+
+#[derive(Template)]
+#[template(path = "pages/category_view.html", escape = "none")]
+pub struct CategoryViewTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    category: Category,
+    parent_choices: Vec<Category>,
+    category_fields: Vec<CategoryField>,
+    asset_type_count: i64,
+    field_types: Vec<&'static str>,
+}
+
+impl CategoryViewTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, category: Category, parent_choices: Vec<Category>, category_fields: Vec<CategoryField>, asset_type_count: i64) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        let field_types = CUSTOM_FIELD_TYPES.to_vec();
+        Self {navbar, alert, category, parent_choices, category_fields, asset_type_count, field_types}
+    }
+
+    fn is_parent(&self, id: &i32) -> bool {
+        self.category.parent_id == Some(*id)
+    }
+}
@@ -0,0 +1,21 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::depreciation::DepreciationReport, identityaccess::model::users::SessionUser}};
+
+
+#[derive(Template)]
+#[template(path = "pages/depreciation_report.html", escape = "none")]
+pub struct DepreciationReportTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    report: DepreciationReport,
+}
+
+impl DepreciationReportTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, report: DepreciationReport) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, report}
+    }
+}
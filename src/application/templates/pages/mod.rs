@@ -11,4 +11,15 @@ pub mod asset_types_imports_new;
 pub mod asset_items;
 pub mod asset_item_create;
 pub mod asset_item_view;
-pub mod asset_item_edit;
\ No newline at end of file
+pub mod asset_item_edit;
+pub mod depreciation_report;
+pub mod audits;
+pub mod audit_view;
+pub mod asset_items_imports_new;
+pub mod categories;
+pub mod category_view;
+pub mod transfers;
+pub mod transfer_create;
+pub mod transfer_view;
+pub mod storage_checks;
+pub mod search;
@@ -0,0 +1,22 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{identityaccess::model::users::SessionUser, search::model::search::SearchResults}};
+
+
+#[derive(Template)]
+#[template(path = "pages/search.html", escape = "none")]
+pub struct SearchTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    query: String,
+    results: SearchResults,
+}
+
+impl SearchTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, query: String, results: SearchResults) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, query, results}
+    }
+}
@@ -1,8 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
-use tower_sessions::Session;
 
-use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::identityaccess::model::users::{SessionUser, UserDescriptor}};
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::identityaccess::model::users::SessionUser};
 
 
 #[derive(Template)]
@@ -0,0 +1,22 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{filesystem::storage_check::StorageCheck, identityaccess::model::users::SessionUser}};
+
+
+#[derive(Template)]
+#[template(path = "pages/storage_checks.html", escape = "none")]
+pub struct StorageChecksTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    checks: Vec<StorageCheck>,
+    selected: Option<StorageCheck>,
+}
+
+impl StorageChecksTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, checks: Vec<StorageCheck>, selected: Option<StorageCheck>) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, checks, selected}
+    }
+}
@@ -0,0 +1,28 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{crud::model::asset_items::AssetItem, identityaccess::model::users::SessionUser}};
+
+
+#[derive(Template)]
+#[template(path = "pages/transfer_create.html", escape = "none")]
+pub struct TransferCreateTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    schools: Vec<String>,
+    source_school: Option<String>,
+    /// Items at the source school that are not already on their way somewhere
+    asset_items: Vec<AssetItem>,
+}
+
+impl TransferCreateTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, schools: Vec<String>, source_school: Option<String>, asset_items: Vec<AssetItem>) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, schools, source_school, asset_items}
+    }
+
+    pub fn is_source_school(&self, school: &str) -> bool {
+        self.source_school.as_deref() == Some(school)
+    }
+}
@@ -0,0 +1,22 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{identityaccess::model::users::SessionUser, transfers::model::transfers::{Transfer, TransferItem}}};
+
+
+#[derive(Template)]
+#[template(path = "pages/transfer_view.html", escape = "none")]
+pub struct TransferViewTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    transfer: Transfer,
+    transfer_items: Vec<TransferItem>,
+}
+
+impl TransferViewTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, transfer: Transfer, transfer_items: Vec<TransferItem>) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, transfer, transfer_items}
+    }
+}
@@ -0,0 +1,28 @@
+use askama::Template;
+use axum_messages::Message;
+
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::{identityaccess::model::users::SessionUser, transfers::model::transfers::{SchoolTransfers, Transfer}}};
+
+
+#[derive(Template)]
+#[template(path = "pages/transfers.html", escape = "none")]
+pub struct TransfersTemplate {
+    navbar: NavbarTemplate,
+    alert: Option<AlertTemplate>,
+    schools: Vec<String>,
+    /// Set when a school is picked, its pending transfers in both directions
+    school_transfers: Option<SchoolTransfers>,
+    transfers: Vec<Transfer>,
+}
+
+impl TransfersTemplate {
+    pub fn new(session_user: SessionUser, message: Option<Message>, schools: Vec<String>, school_transfers: Option<SchoolTransfers>, transfers: Vec<Transfer>) -> Self {
+        let navbar = NavbarTemplate::new(session_user.user.picture.to_string());
+        let alert = message.map(|m| AlertTemplate::new("global_alert_message", m));
+        Self {navbar, alert, schools, school_transfers, transfers}
+    }
+
+    pub fn is_selected_school(&self, school: &str) -> bool {
+        self.school_transfers.as_ref().map(|s| s.school.as_str()) == Some(school)
+    }
+}
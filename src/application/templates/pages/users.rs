@@ -1,7 +1,7 @@
 use askama::Template;
 use axum_messages::Message;
 
-use crate::{application::templates::{pages::users, partials::{alert::AlertTemplate, navbar::NavbarTemplate}}, domain::identityaccess::model::users::{SessionUser, UserDescriptor}};
+use crate::{application::templates::partials::{alert::AlertTemplate, navbar::NavbarTemplate}, domain::identityaccess::model::users::{SessionUser, UserDescriptor}};
 
 
 #[derive(Template)]
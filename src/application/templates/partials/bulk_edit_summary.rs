@@ -0,0 +1,16 @@
+use askama::Template;
+
+use crate::domain::crud::model::bulk::BulkEditSummary;
+
+
+#[derive(Template)]
+#[template(path = "partials/bulk_edit_summary.html", escape = "none")]
+pub struct BulkEditSummaryTemplate {
+    summary: BulkEditSummary,
+}
+
+impl BulkEditSummaryTemplate {
+    pub fn new(summary: BulkEditSummary) -> Self {
+        Self {summary}
+    }
+}
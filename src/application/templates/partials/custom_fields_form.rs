@@ -0,0 +1,18 @@
+use askama::Template;
+use serde_json::Value;
+
+use crate::domain::crud::model::custom_fields::CustomField;
+
+
+#[derive(Template)]
+#[template(path = "partials/custom_fields_form.html", escape = "none")]
+pub struct CustomFieldsFormTemplate {
+    custom_fields: Vec<CustomField>,
+    values: Value,
+}
+
+impl CustomFieldsFormTemplate {
+    pub fn new(custom_fields: Vec<CustomField>, values: Value) -> Self {
+        Self {custom_fields, values}
+    }
+}
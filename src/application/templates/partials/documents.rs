@@ -0,0 +1,21 @@
+use askama::Template;
+
+use crate::domain::crud::model::documents::{AssetDocument, DOCUMENT_KINDS};
+
+
+/// Document list and upload form shared by the asset item and asset type views
+#[derive(Template)]
+#[template(path = "partials/documents.html", escape = "none")]
+pub struct DocumentsTemplate {
+    /// e.g. /asset_items/42, the documents routes hang off of it
+    base_path: String,
+    documents: Vec<AssetDocument>,
+    kinds: Vec<&'static str>,
+}
+
+impl DocumentsTemplate {
+    pub fn new(base_path: impl Into<String>, documents: Vec<AssetDocument>) -> Self {
+        let kinds = DOCUMENT_KINDS.to_vec();
+        Self {base_path: base_path.into(), documents, kinds}
+    }
+}
@@ -10,6 +10,7 @@ pub struct FormAlertTemplate {
 }
 
 impl FormAlertTemplate {
+    #[allow(dead_code)]
     pub fn new(alert_id: impl Into<String>, report: Report) -> Self {
         Self {
             alert_id: alert_id.into(),
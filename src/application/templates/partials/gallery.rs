@@ -0,0 +1,19 @@
+use askama::Template;
+
+use crate::domain::crud::model::images::AssetImage;
+
+
+/// Image gallery shared by the asset item and asset type views, handlers re-render it after every change
+#[derive(Template)]
+#[template(path = "partials/gallery.html", escape = "none")]
+pub struct GalleryTemplate {
+    /// e.g. /asset_types/42, the images routes hang off of it
+    base_path: String,
+    images: Vec<AssetImage>,
+}
+
+impl GalleryTemplate {
+    pub fn new(base_path: impl Into<String>, images: Vec<AssetImage>) -> Self {
+        Self {base_path: base_path.into(), images}
+    }
+}
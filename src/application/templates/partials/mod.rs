@@ -2,3 +2,9 @@ pub mod alert;
 pub mod form_alert;
 pub mod navbar;
 pub mod users;
+pub mod custom_fields_form;
+pub mod bulk_edit_summary;
+pub mod documents;
+pub mod gallery;
+pub mod search_suggestions;
+pub mod saved_views;
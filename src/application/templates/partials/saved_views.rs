@@ -0,0 +1,28 @@
+use askama::Template;
+use uuid::Uuid;
+
+use crate::domain::{identityaccess::model::roles::Role, saved_views::model::saved_views::SavedView};
+
+
+/// Sidebar on the asset items page, lists saved views and saves the current filter as a new one
+#[derive(Template)]
+#[template(path = "partials/saved_views.html", escape = "none")]
+pub struct SavedViewsTemplate {
+    user_id: Uuid,
+    saved_views: Vec<SavedView>,
+    roles: Vec<Role>,
+}
+
+impl SavedViewsTemplate {
+    pub fn new(user_id: Uuid, saved_views: Vec<SavedView>, roles: Vec<Role>) -> Self {
+        Self {user_id, saved_views, roles}
+    }
+
+    pub fn own_views(&self) -> Vec<&SavedView> {
+        self.saved_views.iter().filter(|v| v.is_owned_by(&self.user_id)).collect()
+    }
+
+    pub fn shared_views(&self) -> Vec<&SavedView> {
+        self.saved_views.iter().filter(|v| !v.is_owned_by(&self.user_id)).collect()
+    }
+}
@@ -0,0 +1,18 @@
+use askama::Template;
+
+use crate::domain::search::model::search::SearchResults;
+
+
+/// Dropdown under the navbar search box, re-rendered as the user types
+#[derive(Template)]
+#[template(path = "partials/search_suggestions.html", escape = "none")]
+pub struct SearchSuggestionsTemplate {
+    query: String,
+    results: SearchResults,
+}
+
+impl SearchSuggestionsTemplate {
+    pub fn new(query: String, results: SearchResults) -> Self {
+        Self {query, results}
+    }
+}
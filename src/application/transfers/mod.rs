@@ -0,0 +1,2 @@
+pub mod transfer_application_service;
+pub mod schema;
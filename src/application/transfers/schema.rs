@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use garde::Validate;
+
+
+/// Submitted with axum_extra's Form so the repeated "ids" checkboxes collect into a list
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct NewTransferSchema {
+    #[garde(length(min=1))]
+    pub source_school: String,
+    #[garde(length(min=1))]
+    pub destination_school: String,
+    #[garde(skip)]
+    pub destination_room: Option<String>,
+    #[garde(skip)]
+    pub notes: Option<String>,
+    #[garde(length(min=1))]
+    #[serde(default)]
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct TransferScanSchema {
+    #[garde(length(min=1))]
+    pub code: String,
+}
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct SchoolSchema {
+    #[garde(skip)]
+    pub school: Option<String>,
+}
@@ -0,0 +1,166 @@
+use uuid::Uuid;
+
+use crate::{domain::{crud::{crud_repository::{CrudRepository, CrudRepositoryError}, model::{asset_items::AssetItem, lookup::{parse_lookup_code, select_code_match, LookupCode}}}, transfers::{model::transfers::{validate_transfer_items, NewTransfer, SchoolTransfers, Transfer, TransferItem}, transfer_repository::{TransferRepository, TransferRepositoryError}}}, infastructure::services::{postgres_crud_repository::PostgresCrudRepository, postgres_transfer_repository::PostgresTransferRepository}};
+
+use super::schema::{NewTransferSchema, TransferScanSchema};
+
+
+#[derive(Debug, thiserror::Error)]
+pub enum TransferError {
+    #[error(transparent)]
+    Repo(#[from] TransferRepositoryError),
+    #[error(transparent)]
+    Crud(#[from] CrudRepositoryError),
+    #[error("transfer not found")]
+    NotFound,
+    #[error("transfer is no longer pending")]
+    NotPending,
+    #[error("destination must be a different school")]
+    SameSchool,
+    /// One message per selected item that can not be sent
+    #[error("some items can not be transferred")]
+    InvalidItems(Vec<String>),
+    #[error("scanned code is not part of this transfer")]
+    NotInTransfer,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferApplicationService {
+    transfer_repo: PostgresTransferRepository,
+    crud_repo: PostgresCrudRepository,
+}
+
+impl TransferApplicationService {
+    pub fn new(transfer_repo: PostgresTransferRepository, crud_repo: PostgresCrudRepository) -> Self {
+        Self{transfer_repo, crud_repo}
+    }
+
+    pub async fn get_transfers(&self) -> Result<Vec<Transfer>, TransferError> {
+        let transfers = self.transfer_repo.get_transfers().await?;
+
+        Ok(transfers)
+    }
+
+    pub async fn get_transfer(&self, id: i32) -> Result<Transfer, TransferError> {
+        self.transfer_repo.get_transfer_by_id(id)
+            .await?
+            .ok_or(TransferError::NotFound)
+    }
+
+    pub async fn get_transfer_items(&self, id: i32) -> Result<Vec<TransferItem>, TransferError> {
+        let transfer_items = self.transfer_repo.get_transfer_items(id).await?;
+
+        Ok(transfer_items)
+    }
+
+    pub async fn get_schools(&self) -> Result<Vec<String>, TransferError> {
+        let schools = self.transfer_repo.get_schools().await?;
+
+        Ok(schools)
+    }
+
+    /// Items a school can send, anything already on its way somewhere is left out
+    pub async fn get_school_asset_items(&self, school: &str) -> Result<Vec<AssetItem>, TransferError> {
+        let asset_items = self.transfer_repo.get_school_asset_items(school.to_string()).await?;
+
+        Ok(asset_items)
+    }
+
+    /// Pending transfers split by direction for the school's dashboard
+    pub async fn get_school_transfers(&self, school: &str) -> Result<SchoolTransfers, TransferError> {
+        let (incoming, outgoing) = self.transfer_repo.get_pending_transfers_for_school(school.to_string())
+            .await?
+            .into_iter()
+            .partition(|t| t.destination_school == school);
+
+        Ok(SchoolTransfers { school: school.to_string(), incoming, outgoing })
+    }
+
+    pub async fn add_transfer(&self, schema: NewTransferSchema, created_by: Uuid) -> Result<Transfer, TransferError> {
+        // should be validated in handler
+        let source_school = schema.source_school.trim().to_string();
+        let destination_school = schema.destination_school.trim().to_string();
+        if source_school == destination_school {
+            return Err(TransferError::SameSchool);
+        }
+
+        let mut ids = schema.ids;
+        ids.sort();
+        ids.dedup();
+
+        let asset_items = self.transfer_repo.get_transfer_candidates(&ids).await?;
+        let pending_ids = self.transfer_repo.get_pending_asset_item_ids(&ids).await?;
+
+        let mut problems: Vec<String> = validate_transfer_items(&source_school, &asset_items, &pending_ids)
+            .into_iter()
+            .map(|(label, problem)| format!("{} {}", label, problem.as_str()))
+            .collect();
+        if asset_items.len() != ids.len() {
+            problems.push(format!("{} selected item(s) no longer exist", ids.len() - asset_items.len()));
+        }
+        if !problems.is_empty() {
+            return Err(TransferError::InvalidItems(problems));
+        }
+
+        let new_transfer = NewTransfer {
+            source_school,
+            destination_school,
+            destination_room: schema.destination_room.map(|s| s.trim().to_string()).and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            notes: schema.notes.map(|s| s.trim().to_string()).and_then(|s| if s.is_empty() { None } else { Some(s) }),
+            created_by,
+            asset_items,
+        };
+
+        let transfer = self.transfer_repo.add_transfer(new_transfer).await?;
+
+        Ok(transfer)
+    }
+
+    /// Checks off an item as it arrives, the same lookup rules as the navbar scan box apply
+    pub async fn scan_transfer_item(&self, transfer_id: i32, schema: TransferScanSchema) -> Result<(), TransferError> {
+        let transfer = self.get_pending_transfer(transfer_id).await?;
+
+        let asset_item_id = match parse_lookup_code(&schema.code) {
+            Some(LookupCode::ItemId(id)) => Some(id),
+            Some(LookupCode::Code(code)) => {
+                let asset_items = self.crud_repo.get_asset_items_by_code(code.clone()).await?;
+                select_code_match(&code, &asset_items).map(|i| i.id)
+            },
+            None => None,
+        };
+
+        let asset_item_id = asset_item_id.ok_or(TransferError::NotInTransfer)?;
+        match self.transfer_repo.scan_transfer_item(transfer.id, asset_item_id).await? {
+            true => Ok(()),
+            false => Err(TransferError::NotInTransfer),
+        }
+    }
+
+    /// The receiving school confirms the items arrived, only now do the items change location
+    pub async fn receive_transfer(&self, transfer_id: i32, received_by: Uuid) -> Result<Transfer, TransferError> {
+        let transfer = self.get_pending_transfer(transfer_id).await?;
+
+        self.transfer_repo.receive_transfer(transfer.id, received_by)
+            .await?
+            .ok_or(TransferError::NotPending)
+    }
+
+    pub async fn cancel_transfer(&self, transfer_id: i32) -> Result<Transfer, TransferError> {
+        let transfer = self.get_pending_transfer(transfer_id).await?;
+
+        self.transfer_repo.cancel_transfer(transfer.id)
+            .await?
+            .ok_or(TransferError::NotPending)
+    }
+
+    async fn get_pending_transfer(&self, transfer_id: i32) -> Result<Transfer, TransferError> {
+        let transfer = self.get_transfer(transfer_id).await?;
+        if !transfer.is_pending() {
+            return Err(TransferError::NotPending);
+        }
+
+        Ok(transfer)
+    }
+}
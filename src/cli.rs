@@ -0,0 +1,152 @@
+use anyhow::{bail, Context};
+
+use crate::{application::content::content_application_service::ContentApplicationService, domain::filesystem::persistence_service::STORAGE_FOLDERS, infastructure::services::{local_persistence_service::LocalPersistenceService, postgres_attachment_repository::PostgresAttachmentRepository, s3_persistence_service::S3PersistenceService, storage_persistence_service::StoragePersistenceService}, settings::Settings};
+
+
+/// Maintenance commands, run as `crate <command>` instead of starting the server
+pub async fn run(config: &Settings, args: &[String]) -> anyhow::Result<()> {
+    match args.first().map(String::as_str) {
+        Some("migrate-storage") => migrate_storage(config).await,
+        Some("gc-attachments") => gc_attachments(config, args[1..].iter().any(|arg| arg == "--dry-run")).await,
+        Some("fsck") => fsck(config, args[1..].iter().any(|arg| arg == "--quarantine")).await,
+        Some("extract-text") => extract_text(config).await,
+        Some(command) => bail!("unknown command {}, available commands: migrate-storage, gc-attachments [--dry-run], fsck [--quarantine], extract-text", command),
+        None => bail!("no command given"),
+    }
+}
+
+/// Copies everything under the local content folder into the configured s3 bucket.
+/// Files already in the bucket are skipped, so the command can be rerun after a failure.
+async fn migrate_storage(config: &Settings) -> anyhow::Result<()> {
+    let s3_config = config.s3.as_ref().context("the [s3] settings are required to migrate storage")?;
+
+    let local = LocalPersistenceService::new(&config.local_storage.route_path, &config.local_storage.serve_path)?;
+    let s3 = S3PersistenceService::new(&config.local_storage.route_path, s3_config)?;
+
+    let mut uploaded = 0;
+    let mut skipped = 0;
+
+    for folder in STORAGE_FOLDERS {
+        let mut entries = tokio::fs::read_dir(local.folder_path(folder))
+            .await
+            .context(format!("could not read local {} folder", folder))?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_file() {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            let content_type = mime_guess::from_path(&name).first_or_octet_stream().to_string();
+
+            let is_uploaded = s3.upload_local_file(folder, &name, &entry.path(), &content_type)
+                .await
+                .context(format!("could not migrate {}/{}", folder, name))?;
+
+            if is_uploaded {
+                uploaded += 1;
+            } else {
+                skipped += 1;
+            }
+        }
+    }
+
+    println!("migrated storage to s3: {} uploaded, {} already present", uploaded, skipped);
+
+    Ok(())
+}
+
+fn content_service(config: &Settings) -> anyhow::Result<ContentApplicationService> {
+    let attachment_repo = PostgresAttachmentRepository::new(&config.database)?;
+    let persistence = StoragePersistenceService::new(config)?;
+
+    ContentApplicationService::new(attachment_repo, persistence, &config.uploads)
+}
+
+/// Runs the attachment gc once with the configured grace period, with --dry-run it only lists what would be deleted
+async fn gc_attachments(config: &Settings, dry_run: bool) -> anyhow::Result<()> {
+    let content_service = content_service(config)?;
+
+    let report = content_service.collect_unreferenced_attachments(config.attachment_gc.grace_period_hours, dry_run)
+        .await
+        .context("attachment gc failed")?;
+
+    let action = if report.dry_run { "would delete" } else { "deleted" };
+    let collected = [("image", &report.collected.images), ("document", &report.collected.documents)];
+
+    for (kind, attachments) in collected {
+        for attachment in attachments.iter() {
+            println!(
+                "{} {} {} \"{}\" unreferenced since {}, files: {}",
+                action,
+                kind,
+                attachment.id,
+                attachment.filename,
+                attachment.unreferenced_since.format("%Y-%m-%d %H:%M"),
+                attachment.stored_names().join(", "),
+            );
+        }
+    }
+
+    println!(
+        "{} images and {} documents {}, {} newly unreferenced attachments wait out the {}h grace period",
+        report.collected.images.len(),
+        report.collected.documents.len(),
+        action,
+        report.newly_unreferenced,
+        config.attachment_gc.grace_period_hours,
+    );
+
+    if !report.dry_run {
+        println!("{} files deleted, {} could not be deleted", report.deleted_files, report.failed_files);
+    }
+
+    Ok(())
+}
+
+/// Runs the storage integrity check and prints its issues, the result also shows on the storage settings page
+async fn fsck(config: &Settings, quarantine: bool) -> anyhow::Result<()> {
+    let content_service = content_service(config)?;
+
+    let check = content_service.run_storage_check(quarantine)
+        .await
+        .context("storage check failed")?;
+
+    if let Some(error) = &check.error {
+        bail!("storage check {} failed: {}", check.id, error);
+    }
+
+    for issue in check.issues.iter() {
+        let attachment = issue.attachment_id.map(|id| format!(" (attachment {})", id)).unwrap_or_default();
+        let quarantined = if issue.quarantined { ", quarantined" } else { "" };
+        println!("{}: {}/{}{}{}", issue.kind, issue.folder, issue.name, attachment, quarantined);
+    }
+
+    println!("storage check {}: {} files hashed, {}", check.id, check.checked_files, check.status());
+
+    Ok(())
+}
+
+/// Indexes the text of every document still waiting for the extraction job, e.g. after importing old documents
+async fn extract_text(config: &Settings) -> anyhow::Result<()> {
+    let content_service = content_service(config)?;
+
+    let mut extracted = 0;
+    let mut failed = 0;
+    loop {
+        let report = content_service.extract_document_texts(config.text_extraction.batch_size)
+            .await
+            .context("text extraction failed")?;
+
+        if report.processed() == 0 {
+            break;
+        }
+
+        extracted += report.extracted;
+        failed += report.failed;
+    }
+
+    println!("text extraction: {} documents indexed, {} could not be read", extracted, failed);
+
+    Ok(())
+}
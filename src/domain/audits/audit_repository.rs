@@ -0,0 +1,69 @@
+use std::future::Future;
+
+use thiserror::Error;
+
+use crate::domain::crud::model::asset_items::AssetItem;
+
+use super::model::audits::{Audit, AuditResult, AuditScan, NewAudit};
+
+
+#[derive(Error, Debug)]
+pub enum AuditRepositoryError {
+    #[error("related item in foreign table does not exist")]
+    Reference,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+pub trait AuditRepository: Send + Sync + Clone + 'static {
+
+    fn add_audit(
+        &self,
+        new_audit: NewAudit,
+    ) -> impl Future<Output = Result<Audit, AuditRepositoryError>> + Send;
+
+    fn get_audits(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Audit>, AuditRepositoryError>> + Send;
+
+    fn get_audit_by_id(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<Audit>, AuditRepositoryError>> + Send;
+
+    /// Returns None when the code was already scanned in this audit
+    fn add_audit_scan(
+        &self,
+        audit_id: i32,
+        code: String,
+        asset_item_id: Option<i32>,
+    ) -> impl Future<Output = Result<Option<AuditScan>, AuditRepositoryError>> + Send;
+
+    fn get_audit_scans(
+        &self,
+        audit_id: i32,
+    ) -> impl Future<Output = Result<Vec<AuditScan>, AuditRepositoryError>> + Send;
+
+    fn get_audit_scanned_items(
+        &self,
+        audit_id: i32,
+    ) -> impl Future<Output = Result<Vec<AssetItem>, AuditRepositoryError>> + Send;
+
+    fn get_audit_expected_items(
+        &self,
+        school: String,
+        room: Option<String>,
+    ) -> impl Future<Output = Result<Vec<AssetItem>, AuditRepositoryError>> + Send;
+
+    /// Stores the report lines and closes the audit in one transaction
+    fn complete_audit(
+        &self,
+        audit_id: i32,
+        results: &[AuditResult],
+    ) -> impl Future<Output = Result<Option<Audit>, AuditRepositoryError>> + Send;
+
+    fn get_audit_results(
+        &self,
+        audit_id: i32,
+    ) -> impl Future<Output = Result<Vec<AuditResult>, AuditRepositoryError>> + Send;
+}
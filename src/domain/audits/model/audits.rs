@@ -0,0 +1,292 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::crud::model::asset_items::AssetItem;
+
+
+pub const AUDIT_STATUS_OPEN: &str = "open";
+
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Audit {
+    pub id: i32,
+    pub school: String,
+    /// None when the whole school is counted at once
+    pub room: Option<String>,
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub auditor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Audit {
+    pub fn is_open(&self) -> bool {
+        self.status == AUDIT_STATUS_OPEN
+    }
+
+    pub fn location_display(&self) -> String {
+        match &self.room {
+            Some(room) => format!("{} / {}", self.school, room),
+            None => format!("{} (all rooms)", self.school),
+        }
+    }
+
+    pub fn created_at_display(&self) -> String {
+        self.created_at.format("%Y-%m-%d %H:%M").to_string()
+    }
+
+    pub fn completed_at_display(&self) -> String {
+        self.completed_at.map(|d| d.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+    }
+
+    /// Whether an item recorded at this location is expected to be scanned
+    pub fn expects(&self, asset_item: &AssetItem) -> bool {
+        let same_school = asset_item.school.as_deref() == Some(self.school.as_str());
+        let same_room = match &self.room {
+            Some(room) => asset_item.room.as_deref() == Some(room.as_str()),
+            None => true,
+        };
+
+        same_school && same_room
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewAudit {
+    pub school: String,
+    pub room: Option<String>,
+    pub created_by: Uuid,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuditScan {
+    pub id: i32,
+    pub audit_id: i32,
+    pub code: String,
+    pub asset_item_id: Option<i32>,
+    pub scanned_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuditOutcome {
+    Found,
+    Missing,
+    /// recorded at another location but scanned here
+    Unexpected,
+    /// the scanned code does not match any asset item
+    Unknown,
+}
+
+impl AuditOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Found => "found",
+            AuditOutcome::Missing => "missing",
+            AuditOutcome::Unexpected => "unexpected",
+            AuditOutcome::Unknown => "unknown",
+        }
+    }
+}
+
+/// One line of an audit report, kept denormalized so completed reports survive later edits
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuditResult {
+    pub outcome: String,
+    pub asset_item_id: Option<i32>,
+    pub code: Option<String>,
+    pub asset_id: Option<String>,
+    pub name: Option<String>,
+    pub serial_number: Option<String>,
+    pub recorded_school: Option<String>,
+    pub recorded_room: Option<String>,
+}
+
+impl AuditResult {
+    fn from_asset_item(outcome: AuditOutcome, asset_item: &AssetItem, code: Option<String>) -> Self {
+        Self {
+            outcome: outcome.as_str().to_string(),
+            asset_item_id: Some(asset_item.id),
+            code,
+            asset_id: asset_item.asset_id.clone(),
+            name: asset_item.name.clone(),
+            serial_number: asset_item.serial_number.clone(),
+            recorded_school: asset_item.school.clone(),
+            recorded_room: asset_item.room.clone(),
+        }
+    }
+
+    pub fn recorded_location_display(&self) -> String {
+        match (&self.recorded_school, &self.recorded_room) {
+            (Some(school), Some(room)) => format!("{} / {}", school, room),
+            (Some(school), None) => school.clone(),
+            (None, Some(room)) => room.clone(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AuditReconciliation {
+    pub found: Vec<AuditResult>,
+    pub missing: Vec<AuditResult>,
+    pub unexpected: Vec<AuditResult>,
+    pub unknown: Vec<AuditResult>,
+}
+
+impl AuditReconciliation {
+    /// Compares what was scanned against what asset_items says is at the audit location
+    pub fn build(audit: &Audit, expected: &[AssetItem], scans: &[AuditScan], scanned_items: &[AssetItem]) -> Self {
+        let scanned_ids: HashSet<i32> = scans.iter().filter_map(|s| s.asset_item_id).collect();
+        let mut reported_ids: HashSet<i32> = HashSet::new();
+        let mut reconciliation = Self::default();
+
+        for asset_item in expected {
+            if scanned_ids.contains(&asset_item.id) {
+                reconciliation.found.push(AuditResult::from_asset_item(AuditOutcome::Found, asset_item, None));
+            } else {
+                reconciliation.missing.push(AuditResult::from_asset_item(AuditOutcome::Missing, asset_item, None));
+            }
+        }
+
+        for scan in scans {
+            let scanned_item = scan.asset_item_id.and_then(|id| scanned_items.iter().find(|i| i.id == id));
+
+            match scanned_item {
+                Some(asset_item) if audit.expects(asset_item) => {},
+                // the same item scanned again, e.g. once by asset id and once by serial number
+                Some(asset_item) if !reported_ids.insert(asset_item.id) => {},
+                Some(asset_item) => {
+                    reconciliation.unexpected.push(AuditResult::from_asset_item(AuditOutcome::Unexpected, asset_item, Some(scan.code.clone())));
+                },
+                None => {
+                    reconciliation.unknown.push(AuditResult {
+                        outcome: AuditOutcome::Unknown.as_str().to_string(),
+                        asset_item_id: None,
+                        code: Some(scan.code.clone()),
+                        asset_id: None,
+                        name: None,
+                        serial_number: None,
+                        recorded_school: None,
+                        recorded_room: None,
+                    });
+                },
+            }
+        }
+
+        reconciliation
+    }
+
+    /// Regroups the stored lines of a completed audit
+    pub fn from_results(results: Vec<AuditResult>) -> Self {
+        let mut reconciliation = Self::default();
+
+        for result in results {
+            match result.outcome.as_str() {
+                "found" => reconciliation.found.push(result),
+                "missing" => reconciliation.missing.push(result),
+                "unexpected" => reconciliation.unexpected.push(result),
+                _ => reconciliation.unknown.push(result),
+            }
+        }
+
+        reconciliation
+    }
+
+    pub fn results(&self) -> Vec<AuditResult> {
+        self.found.iter()
+            .chain(self.missing.iter())
+            .chain(self.unexpected.iter())
+            .chain(self.unknown.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audit(school: &str, room: Option<&str>) -> Audit {
+        Audit {
+            id: 1,
+            school: school.to_string(),
+            room: room.map(str::to_string),
+            status: AUDIT_STATUS_OPEN.to_string(),
+            created_by: None,
+            auditor: None,
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    fn asset_item(id: i32, school: &str, room: &str) -> AssetItem {
+        AssetItem {
+            id,
+            asset_id: Some(format!("A{}", id)),
+            serial_number: Some(format!("SN{}", id)),
+            name: None,
+            asset_type_id: None,
+            brand: None,
+            model: None,
+            description: None,
+            cost: None,
+            school: Some(school.to_string()),
+            room: Some(room.to_string()),
+            funding_source: None,
+            purchase_date: None,
+            status: "active".to_string(),
+            custom_fields: serde_json::Value::Null,
+            picture: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn scan(id: i32, code: &str, asset_item_id: Option<i32>) -> AuditScan {
+        AuditScan {
+            id,
+            audit_id: 1,
+            code: code.to_string(),
+            asset_item_id,
+            scanned_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn item_scanned_by_asset_id_and_serial_number_is_found_once() {
+        let audit = audit("North", Some("101"));
+        let expected = vec![asset_item(1, "North", "101")];
+        let scans = vec![scan(1, "A1", Some(1)), scan(2, "SN1", Some(1))];
+
+        let reconciliation = AuditReconciliation::build(&audit, &expected, &scans, &expected);
+
+        assert_eq!(reconciliation.found.len(), 1);
+        assert!(reconciliation.missing.is_empty());
+        assert!(reconciliation.unexpected.is_empty());
+    }
+
+    #[test]
+    fn misplaced_item_scanned_twice_is_unexpected_once() {
+        let audit = audit("North", Some("101"));
+        let scanned_items = vec![asset_item(2, "South", "204")];
+        let scans = vec![scan(1, "A2", Some(2)), scan(2, "SN2", Some(2))];
+
+        let reconciliation = AuditReconciliation::build(&audit, &[], &scans, &scanned_items);
+
+        assert_eq!(reconciliation.unexpected.len(), 1);
+        assert_eq!(reconciliation.unexpected[0].code.as_deref(), Some("A2"));
+    }
+
+    #[test]
+    fn unmatched_codes_are_unknown() {
+        let audit = audit("North", None);
+        let scans = vec![scan(1, "NOPE", None)];
+
+        let reconciliation = AuditReconciliation::build(&audit, &[], &scans, &[]);
+
+        assert_eq!(reconciliation.unknown.len(), 1);
+        assert_eq!(reconciliation.unknown[0].code.as_deref(), Some("NOPE"));
+    }
+}
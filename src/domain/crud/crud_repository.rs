@@ -1,8 +1,10 @@
 use std::future::Future;
 
+use uuid::Uuid;
+
 use thiserror::Error;
 
-use super::model::{asset_items::{AssetItem, AssetItemFilter, AssetItemID, NewAssetItem}, asset_types::{AssetType, AssetTypeFilter, NewAssetType, UpdateAssetType, UploadResult}};
+use super::model::{documents::{AssetDocument, NewAssetDocument}, images::AssetImage, bulk::{BulkAssetItemAction, BulkEditSummary}, history::AssetItemHistoryEntry, asset_items::{AssetItem, AssetItemFilter, AssetItemID, NewAssetItem, UpdateAssetItem}, categories::{Category, CategoryField, NewCategory, NewCategoryField, UpdateCategory}, custom_fields::{CustomField, NewCustomField}, depreciation::DepreciableAssetItem, asset_types::{AssetType, AssetTypeFilter, NewAssetType, UpdateAssetType, UploadResult}};
 
 
 #[derive(Error, Debug)]
@@ -27,6 +29,7 @@ pub trait CrudRepository: Send + Sync + Clone + 'static {
         id: i32,
     ) -> impl Future<Output = Result<Option<AssetType>, CrudRepositoryError>> + Send;
 
+    #[allow(dead_code)]
     fn get_asset_type(
         &self,
         brand: String,
@@ -42,23 +45,33 @@ pub trait CrudRepository: Send + Sync + Clone + 'static {
         filter: AssetTypeFilter,
     ) -> impl Future<Output = Result<Vec<AssetType>, CrudRepositoryError>> + Send;
 
+    fn get_asset_type_search_suggestion(
+        &self,
+        search: &str,
+    ) -> impl Future<Output = Result<Option<String>, CrudRepositoryError>> + Send;
+
     fn update_asset_type(
         &self,
         id: i32,
         update_asset_type: UpdateAssetType,
     ) -> impl Future<Output = Result<Option<AssetType>, CrudRepositoryError>> + Send;
 
-    fn update_asset_type_picture(
+    fn delete_asset_type(
         &self,
         id: i32,
-        picture: String,
-    ) -> impl Future<Output = Result<Option<AssetType>, CrudRepositoryError>> + Send;
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
 
-    fn delete_asset_type(
+    fn reassign_and_delete_asset_type(
         &self,
         id: i32,
+        reassign_to: i32,
     ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
 
+    fn get_asset_item_count_for_type(
+        &self,
+        asset_type_id: i32,
+    ) -> impl Future<Output = Result<i64, CrudRepositoryError>> + Send;
+
     fn bulk_add_asset_type(
         &self,
         new_asset_types: &[NewAssetType],
@@ -74,6 +87,11 @@ pub trait CrudRepository: Send + Sync + Clone + 'static {
         new_asset_item: NewAssetItem,
     ) -> impl Future<Output = Result<AssetItemID, CrudRepositoryError>> + Send;
 
+    fn bulk_add_asset_items(
+        &self,
+        new_asset_items: &[NewAssetItem],
+    ) -> impl Future<Output = Result<UploadResult, CrudRepositoryError>> + Send;
+
     fn get_asset_items(
         &self,
     ) -> impl Future<Output = Result<Vec<AssetItem>, CrudRepositoryError>> + Send;
@@ -88,9 +106,221 @@ pub trait CrudRepository: Send + Sync + Clone + 'static {
         id: i32,
     ) -> impl Future<Output = Result<Option<AssetItem>, CrudRepositoryError>> + Send;
 
+    fn get_asset_items_by_code(
+        &self,
+        code: String,
+    ) -> impl Future<Output = Result<Vec<AssetItem>, CrudRepositoryError>> + Send;
+
+    fn update_asset_item(
+        &self,
+        id: i32,
+        update_asset_item: UpdateAssetItem,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    /// Records a history entry when the location actually changes
+    fn update_asset_item_location(
+        &self,
+        id: i32,
+        school: String,
+        room: Option<String>,
+        changed_by: Uuid,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    /// Records a history entry when the status actually changes
+    fn update_asset_item_status(
+        &self,
+        id: i32,
+        status: String,
+        changed_by: Uuid,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    #[allow(dead_code)]
     fn delete_asset_item(
         &self,
         id: i32,
     ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
-}
 
+    /// Applies one action to every listed item in a single transaction, recording a history entry per changed item
+    fn bulk_update_asset_items(
+        &self,
+        ids: &[i32],
+        action: BulkAssetItemAction,
+        changed_by: Uuid,
+    ) -> impl Future<Output = Result<BulkEditSummary, CrudRepositoryError>> + Send;
+
+    fn get_asset_item_history(
+        &self,
+        asset_item_id: i32,
+    ) -> impl Future<Output = Result<Vec<AssetItemHistoryEntry>, CrudRepositoryError>> + Send;
+
+    fn get_asset_item_documents(
+        &self,
+        asset_item_id: i32,
+    ) -> impl Future<Output = Result<Vec<AssetDocument>, CrudRepositoryError>> + Send;
+
+    /// Links the document and records it in the item history in one transaction
+    fn add_asset_item_document(
+        &self,
+        asset_item_id: i32,
+        new_document: NewAssetDocument,
+    ) -> impl Future<Output = Result<AssetDocument, CrudRepositoryError>> + Send;
+
+    /// Unlinks the document and records it in the item history, the attachment itself is kept
+    fn delete_asset_item_document(
+        &self,
+        asset_item_id: i32,
+        id: i32,
+        changed_by: Uuid,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn get_asset_type_documents(
+        &self,
+        asset_type_id: i32,
+    ) -> impl Future<Output = Result<Vec<AssetDocument>, CrudRepositoryError>> + Send;
+
+    fn add_asset_type_document(
+        &self,
+        asset_type_id: i32,
+        new_document: NewAssetDocument,
+    ) -> impl Future<Output = Result<AssetDocument, CrudRepositoryError>> + Send;
+
+    fn delete_asset_type_document(
+        &self,
+        asset_type_id: i32,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn get_asset_type_images(
+        &self,
+        asset_type_id: i32,
+    ) -> impl Future<Output = Result<Vec<AssetImage>, CrudRepositoryError>> + Send;
+
+    /// Appends the image to the gallery, it becomes the primary image when asked or when the gallery has none
+    fn add_asset_type_image(
+        &self,
+        asset_type_id: i32,
+        image_id: i32,
+        make_primary: bool,
+    ) -> impl Future<Output = Result<AssetImage, CrudRepositoryError>> + Send;
+
+    /// Also copies the image url to asset_types.picture for list thumbnails
+    fn set_asset_type_primary_image(
+        &self,
+        asset_type_id: i32,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    /// Positions follow the order of the given gallery entry ids
+    fn reorder_asset_type_images(
+        &self,
+        asset_type_id: i32,
+        ids: &[i32],
+    ) -> impl Future<Output = Result<(), CrudRepositoryError>> + Send;
+
+    /// Removing the primary image promotes the next one in order
+    fn delete_asset_type_image(
+        &self,
+        asset_type_id: i32,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn get_asset_item_images(
+        &self,
+        asset_item_id: i32,
+    ) -> impl Future<Output = Result<Vec<AssetImage>, CrudRepositoryError>> + Send;
+
+    fn add_asset_item_image(
+        &self,
+        asset_item_id: i32,
+        image_id: i32,
+        make_primary: bool,
+    ) -> impl Future<Output = Result<AssetImage, CrudRepositoryError>> + Send;
+
+    fn set_asset_item_primary_image(
+        &self,
+        asset_item_id: i32,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn reorder_asset_item_images(
+        &self,
+        asset_item_id: i32,
+        ids: &[i32],
+    ) -> impl Future<Output = Result<(), CrudRepositoryError>> + Send;
+
+    fn delete_asset_item_image(
+        &self,
+        asset_item_id: i32,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn get_depreciable_asset_items(
+        &self,
+    ) -> impl Future<Output = Result<Vec<DepreciableAssetItem>, CrudRepositoryError>> + Send;
+
+    fn get_custom_fields(
+        &self,
+        asset_type_id: i32,
+    ) -> impl Future<Output = Result<Vec<CustomField>, CrudRepositoryError>> + Send;
+
+    fn get_all_custom_fields(
+        &self,
+    ) -> impl Future<Output = Result<Vec<CustomField>, CrudRepositoryError>> + Send;
+
+    fn add_custom_field(
+        &self,
+        new_custom_field: NewCustomField,
+    ) -> impl Future<Output = Result<CustomField, CrudRepositoryError>> + Send;
+
+    fn delete_custom_field(
+        &self,
+        asset_type_id: i32,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn get_categories(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Category>, CrudRepositoryError>> + Send;
+
+    fn get_category_by_id(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<Category>, CrudRepositoryError>> + Send;
+
+    fn add_category(
+        &self,
+        new_category: NewCategory,
+    ) -> impl Future<Output = Result<i32, CrudRepositoryError>> + Send;
+
+    fn update_category(
+        &self,
+        id: i32,
+        update_category: UpdateCategory,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn delete_category(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+
+    fn get_asset_type_count_for_category(
+        &self,
+        category_id: i32,
+    ) -> impl Future<Output = Result<i64, CrudRepositoryError>> + Send;
+
+    fn get_category_fields(
+        &self,
+        category_id: i32,
+    ) -> impl Future<Output = Result<Vec<CategoryField>, CrudRepositoryError>> + Send;
+
+    fn add_category_field(
+        &self,
+        new_category_field: NewCategoryField,
+    ) -> impl Future<Output = Result<CategoryField, CrudRepositoryError>> + Send;
+
+    fn delete_category_field(
+        &self,
+        category_id: i32,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<i32>, CrudRepositoryError>> + Send;
+}
@@ -1,5 +1,10 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{asset_types::AssetType, money::format_money};
 
 
 #[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow, sqlx::Type)]
@@ -7,22 +12,56 @@ pub struct AssetItemID {
     pub id: i32,
 }
 
+pub const ASSET_ITEM_STATUS_ACTIVE: &str = "active";
+pub const ASSET_ITEM_STATUS_MISSING: &str = "missing";
+pub const ASSET_ITEM_STATUS_RETIRED: &str = "retired";
+/// Values allowed by the asset_items_status_check constraint
+pub const ASSET_ITEM_STATUSES: [&str; 3] = [ASSET_ITEM_STATUS_ACTIVE, ASSET_ITEM_STATUS_MISSING, ASSET_ITEM_STATUS_RETIRED];
+
 #[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow, sqlx::Type)]
 pub struct AssetItem {
     pub id: i32,
     pub asset_id: Option<String>,
     pub serial_number: Option<String>,
     pub name: Option<String>,
+    pub asset_type_id: Option<i32>,
     pub brand: Option<String>, 
     pub model: Option<String>,
     pub description: Option<String>,
-    pub cost: Option<String>,
+    pub cost: Option<Decimal>,
     pub school: Option<String>,
     pub room: Option<String>,
     pub funding_source: Option<String>,
+    pub purchase_date: Option<NaiveDate>,
+    pub status: String,
+    pub custom_fields: Value,
+    /// the item's primary image, otherwise its asset type's picture
+    pub picture: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+impl AssetItem {
+    pub fn cost_display(&self) -> String {
+        format_money(self.cost)
+    }
+
+    pub fn purchase_date_display(&self) -> String {
+        self.purchase_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()
+    }
+
+    pub fn is_missing(&self) -> bool {
+        self.status == ASSET_ITEM_STATUS_MISSING
+    }
+
+    pub fn is_retired(&self) -> bool {
+        self.status == ASSET_ITEM_STATUS_RETIRED
+    }
+
+    pub fn has_asset_type(&self, asset_type_id: &i32) -> bool {
+        self.asset_type_id == Some(*asset_type_id)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NewAssetItem {
     #[serde(alias="asset_id", alias="Asset ID")]
@@ -31,6 +70,9 @@ pub struct NewAssetItem {
     pub serial_number: Option<String>,
     #[serde(alias="name", alias="Name")]
     pub name: Option<String>,
+    #[serde(alias="asset_type_id", alias="Asset Type ID")]
+    pub asset_type_id: Option<i32>,
+    /// import files written before asset_type_id name the type by brand and model, see resolve_asset_type
     #[serde(alias="brand", alias="Brand")]
     pub brand: Option<String>,
     #[serde(alias="model", alias="Model")]
@@ -41,6 +83,50 @@ pub struct NewAssetItem {
     pub room: Option<String>,
     #[serde(alias="funding_source", alias="Funding Source")]
     pub funding_source: Option<String>,
+    #[serde(alias="purchase_date", alias="Purchase Date")]
+    pub purchase_date: Option<NaiveDate>,
+    /// filled from the asset type's custom field columns after the row is read
+    #[serde(skip)]
+    pub custom_fields: Value,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ImportAssetTypeError {
+    #[error("asset type id {0} does not exist")]
+    UnknownId(i32),
+    #[error("no asset type with brand {0} and model {1}")]
+    UnknownBrandModel(String, String),
+    #[error("brand and model must be given together")]
+    Incomplete,
+}
+
+impl NewAssetItem {
+    /// Sets asset_type_id from the brand and model columns when the row has no id.
+    /// A row naming a type that does not exist is an error rather than an item without a type.
+    pub fn resolve_asset_type(&mut self, asset_types: &[AssetType]) -> Result<(), ImportAssetTypeError> {
+        if let Some(asset_type_id) = self.asset_type_id {
+            return match asset_types.iter().any(|t| t.id == asset_type_id) {
+                true => Ok(()),
+                false => Err(ImportAssetTypeError::UnknownId(asset_type_id)),
+            };
+        }
+
+        let brand = self.brand.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let model = self.model.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+        match (brand, model) {
+            (None, None) => Ok(()),
+            (Some(brand), Some(model)) => {
+                let asset_type = asset_types.iter()
+                    .find(|t| t.brand == brand && t.model == model)
+                    .ok_or_else(|| ImportAssetTypeError::UnknownBrandModel(brand.to_string(), model.to_string()))?;
+
+                self.asset_type_id = Some(asset_type.id);
+                Ok(())
+            },
+            _ => Err(ImportAssetTypeError::Incomplete),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -48,11 +134,12 @@ pub struct UpdateAssetItem {
     pub asset_id: Option<String>, 
     pub serial_number: Option<String>,
     pub name: Option<String>,
-    pub brand: Option<String>,
-    pub model: Option<String>,
+    pub asset_type_id: Option<i32>,
     pub school: Option<String>,
     pub room: Option<String>,
     pub funding_source: Option<String>,
+    pub purchase_date: Option<NaiveDate>,
+    pub custom_fields: Value,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -60,4 +147,72 @@ pub struct AssetItemFilter {
     pub search: Option<String>,
     pub sort: Option<String>,
     pub order: Option<String>,
-}
\ No newline at end of file
+    /// matches items whose asset type is in the category or one of its subcategories
+    pub category_id: Option<i32>,
+    pub school: Option<String>,
+    pub status: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset_type(id: i32, brand: &str, model: &str) -> AssetType {
+        AssetType {
+            id,
+            brand: brand.to_string(),
+            model: model.to_string(),
+            description: None,
+            cost: None,
+            useful_life_years: None,
+            picture: None,
+            category_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn row(asset_type_id: Option<i32>, brand: Option<&str>, model: Option<&str>) -> NewAssetItem {
+        NewAssetItem {
+            asset_id: Some("GAPP-00042".to_string()),
+            serial_number: None,
+            name: None,
+            asset_type_id,
+            brand: brand.map(str::to_string),
+            model: model.map(str::to_string),
+            school: None,
+            room: None,
+            funding_source: None,
+            purchase_date: None,
+            custom_fields: Value::Null,
+        }
+    }
+
+    #[test]
+    fn brand_and_model_resolve_to_the_asset_type() {
+        let asset_types = [asset_type(1, "Dell", "Latitude 5440"), asset_type(2, "HP", "ProBook 450")];
+        let mut new_asset_item = row(None, Some(" HP "), Some("ProBook 450"));
+
+        assert_eq!(new_asset_item.resolve_asset_type(&asset_types), Ok(()));
+        assert_eq!(new_asset_item.asset_type_id, Some(2));
+    }
+
+    #[test]
+    fn unknown_asset_types_are_rejected() {
+        let asset_types = [asset_type(1, "Dell", "Latitude 5440")];
+
+        assert_eq!(
+            row(None, Some("Dell"), Some("Latitude 7440")).resolve_asset_type(&asset_types),
+            Err(ImportAssetTypeError::UnknownBrandModel("Dell".to_string(), "Latitude 7440".to_string())),
+        );
+        assert_eq!(row(Some(9), None, None).resolve_asset_type(&asset_types), Err(ImportAssetTypeError::UnknownId(9)));
+        assert_eq!(row(None, Some("Dell"), None).resolve_asset_type(&asset_types), Err(ImportAssetTypeError::Incomplete));
+    }
+
+    #[test]
+    fn rows_without_an_asset_type_stay_untyped() {
+        let mut new_asset_item = row(None, None, Some(" "));
+
+        assert_eq!(new_asset_item.resolve_asset_type(&[]), Ok(()));
+        assert_eq!(new_asset_item.asset_type_id, None);
+    }
+}
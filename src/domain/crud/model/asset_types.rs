@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use super::money::{deserialize_money, deserialize_optional_i32, format_money};
+
 
 #[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow, sqlx::Type)]
 pub struct AssetType {
@@ -8,11 +11,19 @@ pub struct AssetType {
     pub brand: String, 
     pub model: String,
     pub description: Option<String>,
-    pub cost: Option<String>,
+    pub cost: Option<Decimal>,
+    pub useful_life_years: Option<i32>,
     pub picture: Option<String>,
+    pub category_id: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
+impl AssetType {
+    pub fn cost_display(&self) -> String {
+        format_money(self.cost)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NewAssetType {
     #[serde(alias="brand", alias="Brand")]
@@ -22,10 +33,14 @@ pub struct NewAssetType {
     pub model: String,
     #[serde(alias="description", alias="Description")]
     pub description: Option<String>,
-    #[serde(alias="cost", alias="Cost")]
-    pub cost: Option<String>,
+    #[serde(alias="cost", alias="Cost", default, deserialize_with = "deserialize_money")]
+    pub cost: Option<Decimal>,
+    #[serde(alias="useful_life_years", alias="Useful Life", alias="Useful Life (Years)", default, deserialize_with = "deserialize_optional_i32")]
+    pub useful_life_years: Option<i32>,
     #[serde(alias="picture", alias="Picture")]
     pub picture: Option<String>,
+    #[serde(alias="category_id", alias="Category ID", default, deserialize_with = "deserialize_optional_i32")]
+    pub category_id: Option<i32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -33,8 +48,10 @@ pub struct UpdateAssetType {
     pub brand: String, 
     pub model: String,
     pub description: Option<String>,
-    pub cost: Option<String>,
+    pub cost: Option<Decimal>,
+    pub useful_life_years: Option<i32>,
     pub picture: Option<String>,
+    pub category_id: Option<i32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -48,4 +65,6 @@ pub struct AssetTypeFilter {
     pub search: Option<String>,
     pub sort: Option<String>,
     pub order: Option<String>,
+    /// matches the category and all of its subcategories
+    pub category_id: Option<i32>,
 }
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{custom_fields::CustomField, history::{FieldChange, HISTORY_ACTION_DELETED, HISTORY_ACTION_UPDATED}};
+
+
+/// What to do with every selected asset item
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum BulkAssetItemAction {
+    Update(BulkAssetItemUpdate),
+    Delete,
+}
+
+impl BulkAssetItemAction {
+    pub fn history_action(&self) -> &'static str {
+        match self {
+            Self::Update(_) => HISTORY_ACTION_UPDATED,
+            Self::Delete => HISTORY_ACTION_DELETED,
+        }
+    }
+}
+
+/// Fields set on every selected item, None leaves the item's current value alone
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BulkAssetItemUpdate {
+    pub school: Option<String>,
+    pub room: Option<String>,
+    pub funding_source: Option<String>,
+    pub status: Option<String>,
+    pub asset_type_id: Option<i32>,
+}
+
+impl BulkAssetItemUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.school.is_none()
+            && self.room.is_none()
+            && self.funding_source.is_none()
+            && self.status.is_none()
+            && self.asset_type_id.is_none()
+    }
+
+    /// Differences this update makes to `item`, `asset_type` is the display name of the new asset type
+    pub fn changes(&self, item: &BulkEditItem, asset_type: Option<&str>) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+
+        if let Some(school) = self.school.as_deref() {
+            changes.extend(FieldChange::between("School", item.school.as_deref(), Some(school)));
+        }
+        if let Some(room) = self.room.as_deref() {
+            changes.extend(FieldChange::between("Room", item.room.as_deref(), Some(room)));
+        }
+        if let Some(funding_source) = self.funding_source.as_deref() {
+            changes.extend(FieldChange::between("Funding Source", item.funding_source.as_deref(), Some(funding_source)));
+        }
+        if let Some(status) = self.status.as_deref() {
+            changes.extend(FieldChange::between("Status", Some(item.status.as_str()), Some(status)));
+        }
+        if let Some(asset_type_id) = self.asset_type_id {
+            if item.asset_type_id != Some(asset_type_id) {
+                changes.push(FieldChange {
+                    field: "Asset Type".to_string(),
+                    from: item.asset_type.clone(),
+                    to: asset_type.map(str::to_string),
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+/// Current state of a selected item, read inside the bulk transaction
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BulkEditItem {
+    pub id: i32,
+    pub asset_id: Option<String>,
+    pub name: Option<String>,
+    pub serial_number: Option<String>,
+    pub school: Option<String>,
+    pub room: Option<String>,
+    pub funding_source: Option<String>,
+    pub status: String,
+    pub asset_type_id: Option<i32>,
+    pub asset_type: Option<String>,
+    pub custom_fields: Value,
+}
+
+impl BulkEditItem {
+    /// How the item is named in the summary, falls back to the row id
+    pub fn label(&self) -> String {
+        self.asset_id.clone()
+            .or_else(|| self.name.clone())
+            .or_else(|| self.serial_number.clone())
+            .unwrap_or_else(|| format!("#{}", self.id))
+    }
+
+    /// Custom field values re-parsed against the fields of the asset type the item moves to, `fields` holds the
+    /// definitions of both types. Values the new type does not define or that don't fit its definition are dropped
+    /// and returned as changes for the history. Required fields stay blank, a bulk edit has no values for them.
+    pub fn retyped_custom_fields(&self, asset_type_id: i32, fields: &[CustomField]) -> (Value, Vec<FieldChange>) {
+        let mut kept = Map::new();
+        let mut dropped = Vec::new();
+
+        let Value::Object(values) = &self.custom_fields else {
+            return (Value::Object(kept), dropped);
+        };
+
+        for (name, value) in values {
+            let raw_value = match value {
+                Value::String(s) => s.clone(),
+                value => value.to_string(),
+            };
+
+            let new_field = fields.iter().find(|f| f.asset_type_id == asset_type_id && &f.name == name);
+            match new_field.map(|f| f.parse_value(Some(&raw_value))) {
+                Some(Ok(Some(value))) => {
+                    kept.insert(name.clone(), value);
+                },
+                _ => {
+                    // labelled the way the item's old type showed it
+                    let label = fields.iter()
+                        .find(|f| Some(f.asset_type_id) == self.asset_type_id && &f.name == name)
+                        .map(|f| f.label.clone())
+                        .unwrap_or_else(|| name.clone());
+                    dropped.push(FieldChange { field: label, from: Some(raw_value), to: None });
+                },
+            }
+        }
+
+        (Value::Object(kept), dropped)
+    }
+
+    /// Everything the item held when it was deleted, so the history entry keeps a record of it
+    pub fn deleted_changes(&self) -> Vec<FieldChange> {
+        [
+            ("Asset ID", self.asset_id.as_deref()),
+            ("Name", self.name.as_deref()),
+            ("Serial #", self.serial_number.as_deref()),
+            ("Asset Type", self.asset_type.as_deref()),
+            ("School", self.school.as_deref()),
+            ("Room", self.room.as_deref()),
+            ("Funding Source", self.funding_source.as_deref()),
+            ("Status", Some(self.status.as_str())),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| FieldChange::between(field, value, None))
+        .collect()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BulkEditItemResult {
+    pub asset_item_id: i32,
+    pub label: String,
+    pub changes: Vec<FieldChange>,
+}
+
+impl BulkEditItemResult {
+    pub fn changes_display(&self) -> String {
+        self.changes.iter().map(FieldChange::display).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// What a bulk action did, shown to the user once the transaction commits
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BulkEditSummary {
+    pub action: String,
+    pub selected: usize,
+    pub items: Vec<BulkEditItemResult>,
+}
+
+impl BulkEditSummary {
+    pub fn is_delete(&self) -> bool {
+        self.action == HISTORY_ACTION_DELETED
+    }
+
+    pub fn changed_count(&self) -> usize {
+        self.items.iter().filter(|i| !i.changes.is_empty()).count()
+    }
+
+    pub fn unchanged_count(&self) -> usize {
+        self.items.len() - self.changed_count()
+    }
+
+    /// Selected ids that no longer exist, e.g. deleted by someone else since the page loaded
+    pub fn missing_count(&self) -> usize {
+        self.selected.saturating_sub(self.items.len())
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+
+pub const CATEGORY_PATH_SEPARATOR: &str = " > ";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CategoryError {
+    #[error("a category cannot be placed under itself or one of its subcategories")]
+    Cycle,
+    #[error("parent category does not exist")]
+    UnknownParent,
+}
+
+/// A category read from the category_paths view, with its ancestors resolved
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Category {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub name: String,
+    pub useful_life_years: Option<i32>,
+    /// own useful life or the closest ancestor's
+    pub effective_useful_life_years: Option<i32>,
+    /// names from the root category down to this one
+    pub path_names: Vec<String>,
+    /// ids from the root category down to this one
+    pub ancestor_ids: Vec<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Category {
+    /// e.g. "Computers > Chromebooks"
+    pub fn path(&self) -> String {
+        self.path_names.join(CATEGORY_PATH_SEPARATOR)
+    }
+
+    pub fn depth(&self) -> usize {
+        self.path_names.len().saturating_sub(1)
+    }
+
+    /// Whether `id` is this category or one of its ancestors
+    pub fn is_within(&self, id: i32) -> bool {
+        self.ancestor_ids.contains(&id)
+    }
+
+    pub fn is_useful_life_inherited(&self) -> bool {
+        self.useful_life_years.is_none() && self.effective_useful_life_years.is_some()
+    }
+}
+
+/// Checks that `parent` can hold the category `id` without creating a loop
+pub fn validate_parent(id: i32, parent: Option<&Category>) -> Result<(), CategoryError> {
+    match parent {
+        Some(parent) if parent.is_within(id) => Err(CategoryError::Cycle),
+        _ => Ok(()),
+    }
+}
+
+/// Path display for an optional category id, empty when unset or unknown
+pub fn category_path_display(categories: &[Category], id: Option<i32>) -> String {
+    id.and_then(|id| categories.iter().find(|c| c.id == id))
+        .map(Category::path)
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewCategory {
+    pub parent_id: Option<i32>,
+    pub name: String,
+    pub useful_life_years: Option<i32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateCategory {
+    pub parent_id: Option<i32>,
+    pub name: String,
+    pub useful_life_years: Option<i32>,
+}
+
+/// Custom field defined on a category, inherited by every asset type below it
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CategoryField {
+    pub id: i32,
+    pub category_id: i32,
+    pub name: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub required: bool,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CategoryField {
+    pub fn options_display(&self) -> String {
+        self.options.join(", ")
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewCategoryField {
+    pub category_id: i32,
+    pub name: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub required: bool,
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+
+/// Form inputs for custom fields are named "cf_<field name>" so they can be told apart from item columns
+pub const CUSTOM_FIELD_INPUT_PREFIX: &str = "cf_";
+
+pub const CUSTOM_FIELD_TYPES: [&str; 5] = ["text", "number", "date", "select", "boolean"];
+
+/// Field names would clash with the item columns in csv imports
+const RESERVED_FIELD_NAMES: [&str; 12] = ["id", "asset_id", "serial_number", "name", "asset_type_id", "brand", "model", "school", "room", "funding_source", "purchase_date", "status"];
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CustomFieldError {
+    #[error("{0} is required")]
+    Required(String),
+    #[error("{0} must be a number")]
+    InvalidNumber(String),
+    #[error("{0} must be a date formatted as YYYY-MM-DD")]
+    InvalidDate(String),
+    #[error("{0} must be one of the listed options")]
+    InvalidOption(String),
+    #[error("{0} must be true or false")]
+    InvalidBoolean(String),
+    #[error("{0} cannot be used as a field name")]
+    ReservedName(String),
+}
+
+/// A field as it applies to an asset type, either defined on the type or inherited from its category
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CustomField {
+    pub id: i32,
+    pub asset_type_id: i32,
+    /// set when the definition comes from the asset type's category or one of its ancestors
+    pub category_id: Option<i32>,
+    pub name: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub required: bool,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CustomField {
+    pub fn input_name(&self) -> String {
+        format!("{}{}", CUSTOM_FIELD_INPUT_PREFIX, self.name)
+    }
+
+    pub fn is_type(&self, field_type: &str) -> bool {
+        self.field_type == field_type
+    }
+
+    pub fn options_display(&self) -> String {
+        self.options.join(", ")
+    }
+
+    /// Value as it is shown in forms, csv exports and the item view
+    pub fn value_display(&self, values: &Value) -> String {
+        match values.get(&self.name) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::Bool(b)) => if *b { "true".to_string() } else { "false".to_string() },
+            _ => String::new(),
+        }
+    }
+
+    pub fn is_checked(&self, values: &Value) -> bool {
+        matches!(values.get(&self.name), Some(Value::Bool(true)))
+    }
+
+    pub fn has_option(&self, values: &Value, option: &str) -> bool {
+        matches!(values.get(&self.name), Some(Value::String(s)) if s == option)
+    }
+
+    /// Converts a raw form or csv value into the json value stored on the item
+    pub fn parse_value(&self, raw_value: Option<&str>) -> Result<Option<Value>, CustomFieldError> {
+        let raw_value = raw_value.map(str::trim).unwrap_or_default();
+
+        // unchecked checkboxes are not submitted, so a missing boolean is false rather than blank
+        if self.field_type == "boolean" {
+            return match raw_value.to_lowercase().as_str() {
+                "" | "false" | "no" | "0" | "off" => Ok(Some(Value::Bool(false))),
+                "true" | "yes" | "1" | "on" => Ok(Some(Value::Bool(true))),
+                _ => Err(CustomFieldError::InvalidBoolean(self.label.clone())),
+            };
+        }
+
+        if raw_value.is_empty() {
+            return match self.required {
+                true => Err(CustomFieldError::Required(self.label.clone())),
+                false => Ok(None),
+            };
+        }
+
+        let value = match self.field_type.as_str() {
+            "number" => {
+                let number = raw_value.parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .ok_or_else(|| CustomFieldError::InvalidNumber(self.label.clone()))?;
+                Value::Number(number)
+            },
+            "date" => {
+                NaiveDate::parse_from_str(raw_value, "%Y-%m-%d")
+                    .map_err(|_| CustomFieldError::InvalidDate(self.label.clone()))?;
+                Value::String(raw_value.to_string())
+            },
+            "select" => {
+                if !self.options.iter().any(|o| o == raw_value) {
+                    return Err(CustomFieldError::InvalidOption(self.label.clone()));
+                }
+                Value::String(raw_value.to_string())
+            },
+            _ => Value::String(raw_value.to_string()),
+        };
+
+        Ok(Some(value))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewCustomField {
+    pub asset_type_id: i32,
+    pub name: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub required: bool,
+}
+
+/// Builds the custom_fields json for an item, values for fields the asset type does not define are dropped
+pub fn parse_custom_values(fields: &[CustomField], raw_values: &HashMap<String, String>) -> Result<Value, CustomFieldError> {
+    let mut values = Map::new();
+
+    for field in fields {
+        if let Some(value) = field.parse_value(raw_values.get(&field.name).map(String::as_str))? {
+            values.insert(field.name.clone(), value);
+        }
+    }
+
+    Ok(Value::Object(values))
+}
+
+/// Pulls the "cf_" prefixed inputs out of a submitted form, keyed by field name
+pub fn custom_form_values(form: &HashMap<String, String>) -> HashMap<String, String> {
+    form.iter()
+        .filter_map(|(k, v)| k.strip_prefix(CUSTOM_FIELD_INPUT_PREFIX).map(|name| (name.to_string(), v.clone())))
+        .collect()
+}
+
+/// Turns a label such as "Lamp Hours" into the field key "lamp_hours"
+pub fn field_name_from_label(label: &str) -> Result<String, CustomFieldError> {
+    let mut name = String::new();
+
+    for c in label.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_lowercase());
+        } else if !name.ends_with('_') && !name.is_empty() {
+            name.push('_');
+        }
+    }
+
+    let name = name.trim_end_matches('_').to_string();
+    if name.is_empty() || RESERVED_FIELD_NAMES.contains(&name.as_str()) {
+        return Err(CustomFieldError::ReservedName(label.to_string()));
+    }
+
+    Ok(name)
+}
+
+/// Label and display value pairs for the item view
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomFieldValue {
+    pub label: String,
+    pub value: String,
+}
+
+pub fn display_custom_values(fields: &[CustomField], values: &Value) -> Vec<CustomFieldValue> {
+    fields.iter()
+        .map(|field| CustomFieldValue {
+            label: field.label.clone(),
+            value: field.value_display(values),
+        })
+        .collect()
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::{categories::CATEGORY_PATH_SEPARATOR, money::{format_money, round_money}};
+
+
+/// Useful life applied when an asset type does not define one
+pub const DEFAULT_USEFUL_LIFE_YEARS: i32 = 5;
+
+/// Straight-line depreciation with no salvage value, accrued per whole month in service
+pub fn straight_line_book_value(cost: Decimal, placed_in_service: NaiveDate, useful_life_years: i32, as_of: NaiveDate) -> Decimal {
+    if useful_life_years <= 0 {
+        return Decimal::ZERO;
+    }
+
+    let life_months = useful_life_years as i64 * 12;
+    let elapsed_months = months_between(placed_in_service, as_of).clamp(0, life_months);
+
+    let depreciated = cost * Decimal::from(elapsed_months) / Decimal::from(life_months);
+
+    round_money((cost - depreciated).max(Decimal::ZERO))
+}
+
+fn months_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    let mut months = (end.year() as i64 - start.year() as i64) * 12 + (end.month() as i64 - start.month() as i64);
+
+    // a partial month is not counted until the anniversary day is reached
+    if end.day() < start.day() {
+        months -= 1;
+    }
+
+    months
+}
+
+/// Asset item fields needed to value an item, cost and useful life come from its asset type
+/// with the useful life falling back to the type's category
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DepreciableAssetItem {
+    pub school: Option<String>,
+    pub funding_source: Option<String>,
+    pub cost: Option<Decimal>,
+    pub useful_life_years: Option<i32>,
+    pub placed_in_service: NaiveDate,
+    /// category names from the root down, empty when the asset type has no category
+    pub category_path: Vec<String>,
+}
+
+impl DepreciableAssetItem {
+    pub fn book_value(&self, as_of: NaiveDate) -> Decimal {
+        let cost = self.cost.unwrap_or(Decimal::ZERO);
+        let useful_life = self.useful_life_years.unwrap_or(DEFAULT_USEFUL_LIFE_YEARS);
+
+        straight_line_book_value(cost, self.placed_in_service, useful_life, as_of)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DepreciationReportRow {
+    pub school: String,
+    pub funding_source: String,
+    pub item_count: usize,
+    pub total_cost: Decimal,
+    pub book_value: Decimal,
+}
+
+impl DepreciationReportRow {
+    pub fn accumulated_depreciation(&self) -> Decimal {
+        self.total_cost - self.book_value
+    }
+
+    pub fn total_cost_display(&self) -> String {
+        format_money(Some(self.total_cost))
+    }
+
+    pub fn accumulated_depreciation_display(&self) -> String {
+        format_money(Some(self.accumulated_depreciation()))
+    }
+
+    pub fn book_value_display(&self) -> String {
+        format_money(Some(self.book_value))
+    }
+}
+
+/// Totals for a category including every item in its subcategories
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CategoryRollupRow {
+    pub path_names: Vec<String>,
+    pub item_count: usize,
+    pub total_cost: Decimal,
+    pub book_value: Decimal,
+}
+
+impl CategoryRollupRow {
+    pub fn path(&self) -> String {
+        self.path_names.join(CATEGORY_PATH_SEPARATOR)
+    }
+
+    pub fn name(&self) -> String {
+        self.path_names.last().cloned().unwrap_or_default()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.path_names.len().saturating_sub(1)
+    }
+
+    pub fn accumulated_depreciation(&self) -> Decimal {
+        self.total_cost - self.book_value
+    }
+
+    pub fn total_cost_display(&self) -> String {
+        format_money(Some(self.total_cost))
+    }
+
+    pub fn accumulated_depreciation_display(&self) -> String {
+        format_money(Some(self.accumulated_depreciation()))
+    }
+
+    pub fn book_value_display(&self) -> String {
+        format_money(Some(self.book_value))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DepreciationReport {
+    pub as_of: NaiveDate,
+    pub rows: Vec<DepreciationReportRow>,
+    /// one row per category level, ordered so subcategories follow their parent
+    pub category_rows: Vec<CategoryRollupRow>,
+    pub total_cost: Decimal,
+    pub book_value: Decimal,
+}
+
+impl DepreciationReport {
+    /// Groups items by (school, funding source) and by category level, summing their cost and current book value
+    pub fn build(items: &[DepreciableAssetItem], as_of: NaiveDate) -> Self {
+        let mut groups: BTreeMap<(String, String), DepreciationReportRow> = BTreeMap::new();
+        let mut category_groups: BTreeMap<Vec<String>, CategoryRollupRow> = BTreeMap::new();
+
+        for item in items {
+            let book_value = item.book_value(as_of);
+            let cost = item.cost.unwrap_or(Decimal::ZERO);
+
+            // an item counts toward its category and every ancestor of it
+            let category_path = match item.category_path.is_empty() {
+                true => vec!["Uncategorized".to_string()],
+                false => item.category_path.clone(),
+            };
+            for depth in 1..=category_path.len() {
+                let path_names = category_path[..depth].to_vec();
+                let category_row = category_groups.entry(path_names.clone())
+                    .or_insert_with(|| CategoryRollupRow {
+                        path_names,
+                        item_count: 0,
+                        total_cost: Decimal::ZERO,
+                        book_value: Decimal::ZERO,
+                    });
+
+                category_row.item_count += 1;
+                category_row.total_cost += cost;
+                category_row.book_value += book_value;
+            }
+
+            let school = item.school.clone().unwrap_or_else(|| "Unassigned".to_string());
+            let funding_source = item.funding_source.clone().unwrap_or_else(|| "Unassigned".to_string());
+
+            let row = groups.entry((school.clone(), funding_source.clone()))
+                .or_insert_with(|| DepreciationReportRow {
+                    school,
+                    funding_source,
+                    item_count: 0,
+                    total_cost: Decimal::ZERO,
+                    book_value: Decimal::ZERO,
+                });
+
+            row.item_count += 1;
+            row.total_cost += cost;
+            row.book_value += book_value;
+        }
+
+        let rows: Vec<DepreciationReportRow> = groups.into_values().collect();
+        let category_rows: Vec<CategoryRollupRow> = category_groups.into_values().collect();
+        let total_cost = rows.iter().map(|r| r.total_cost).sum();
+        let book_value = rows.iter().map(|r| r.book_value).sum();
+
+        Self { as_of, rows, category_rows, total_cost, book_value }
+    }
+
+    pub fn total_cost_display(&self) -> String {
+        format_money(Some(self.total_cost))
+    }
+
+    pub fn book_value_display(&self) -> String {
+        format_money(Some(self.book_value))
+    }
+
+    pub fn as_of_display(&self) -> String {
+        self.as_of.format("%Y-%m-%d").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn book_value_starts_at_cost() {
+        let value = straight_line_book_value(Decimal::from(1200), date("2024-01-15"), 5, date("2024-01-15"));
+        assert_eq!(value, Decimal::from(1200));
+    }
+
+    #[test]
+    fn book_value_accrues_per_whole_month() {
+        // 1200 over 60 months is 20 a month
+        let value = straight_line_book_value(Decimal::from(1200), date("2024-01-15"), 5, date("2025-01-15"));
+        assert_eq!(value, Decimal::from(960));
+
+        // the 13th month is not counted until the 15th
+        let value = straight_line_book_value(Decimal::from(1200), date("2024-01-15"), 5, date("2025-02-14"));
+        assert_eq!(value, Decimal::from(960));
+    }
+
+    #[test]
+    fn book_value_rounds_to_cents() {
+        // 1000 over 36 months, one month in
+        let value = straight_line_book_value(Decimal::from(1000), date("2024-01-01"), 3, date("2024-02-01"));
+        assert_eq!(value, Decimal::new(97222, 2));
+    }
+
+    #[test]
+    fn book_value_is_zero_after_the_useful_life() {
+        let value = straight_line_book_value(Decimal::from(1200), date("2015-01-01"), 5, date("2024-01-01"));
+        assert_eq!(value, Decimal::ZERO);
+    }
+
+    #[test]
+    fn book_value_ignores_future_service_dates() {
+        let value = straight_line_book_value(Decimal::from(1200), date("2025-01-01"), 5, date("2024-01-01"));
+        assert_eq!(value, Decimal::from(1200));
+    }
+
+    #[test]
+    fn book_value_without_useful_life_is_zero() {
+        let value = straight_line_book_value(Decimal::from(1200), date("2024-01-01"), 0, date("2024-06-01"));
+        assert_eq!(value, Decimal::ZERO);
+    }
+}
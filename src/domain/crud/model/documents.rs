@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::filesystem::models::{ContentType, Filename};
+
+use super::history::FieldChange;
+
+
+/// Values allowed by the asset_item_documents_kind_check and asset_type_documents_kind_check constraints
+pub const DOCUMENT_KINDS: [&str; 5] = ["invoice", "manual", "warranty", "damage", "other"];
+
+/// A document attachment linked to an asset item or asset type, id is the link not the attachment
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AssetDocument {
+    pub id: i32,
+    pub document_id: i32,
+    pub filename: Filename,
+    pub content_type: ContentType,
+    pub url: String,
+    pub kind: String,
+    pub description: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AssetDocument {
+    pub fn created_at_display(&self) -> String {
+        self.created_at.format("%Y-%m-%d").to_string()
+    }
+
+    pub fn is_image(&self) -> bool {
+        AsRef::<str>::as_ref(&self.content_type).starts_with("image/")
+    }
+
+    /// History line written when the document is attached or removed, e.g. "Document: (blank) → invoice.pdf"
+    pub fn added_change(&self) -> FieldChange {
+        FieldChange { field: document_field(&self.kind), from: None, to: Some(self.filename.to_string()) }
+    }
+
+    pub fn removed_change(&self) -> FieldChange {
+        FieldChange { field: document_field(&self.kind), from: Some(self.filename.to_string()), to: None }
+    }
+}
+
+fn document_field(kind: &str) -> String {
+    let mut chars = kind.chars();
+    match chars.next() {
+        Some(first) => format!("Document ({}{})", first.to_uppercase(), chars.as_str()),
+        None => "Document".to_string(),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewAssetDocument {
+    pub document_id: i32,
+    pub kind: String,
+    pub description: Option<String>,
+    pub created_by: Uuid,
+}
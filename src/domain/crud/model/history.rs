@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+
+/// Values allowed by the asset_item_history_action_check constraint
+pub const HISTORY_ACTION_UPDATED: &str = "updated";
+pub const HISTORY_ACTION_DELETED: &str = "deleted";
+pub const HISTORY_ACTION_DOCUMENT_ADDED: &str = "document_added";
+pub const HISTORY_ACTION_DOCUMENT_REMOVED: &str = "document_removed";
+
+/// One field of an asset item before and after a change, values are kept as they are displayed
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl FieldChange {
+    /// Records a change only when the value actually differs
+    pub fn between(field: &str, from: Option<&str>, to: Option<&str>) -> Option<Self> {
+        match from == to {
+            true => None,
+            false => Some(Self {
+                field: field.to_string(),
+                from: from.map(str::to_string),
+                to: to.map(str::to_string),
+            }),
+        }
+    }
+
+    /// e.g. "Room: 101 → 204"
+    pub fn display(&self) -> String {
+        format!(
+            "{}: {} → {}",
+            self.field,
+            self.from.as_deref().unwrap_or("(blank)"),
+            self.to.as_deref().unwrap_or("(blank)"),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AssetItemHistoryEntry {
+    pub id: i32,
+    pub asset_item_id: i32,
+    pub action: String,
+    pub changes: Json<Vec<FieldChange>>,
+    pub changed_by: Option<Uuid>,
+    pub changed_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AssetItemHistoryEntry {
+    pub fn created_at_display(&self) -> String {
+        self.created_at.format("%Y-%m-%d %H:%M").to_string()
+    }
+
+    /// e.g. "document added"
+    pub fn action_display(&self) -> String {
+        self.action.replace('_', " ")
+    }
+
+    pub fn changes_display(&self) -> Vec<String> {
+        self.changes.iter().map(FieldChange::display).collect()
+    }
+}
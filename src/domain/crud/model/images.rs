@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+
+use crate::domain::filesystem::models::{srcset, variant_url, Filename, ImageVariant};
+
+
+/// Shown for asset types once their last gallery image is removed
+pub const EMPTY_IMAGE_URL: &str = "/static/images/empty-image.svg";
+
+/// An image attachment in an asset type or asset item gallery, id is the gallery entry not the attachment
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AssetImage {
+    pub id: i32,
+    pub image_id: i32,
+    pub filename: Filename,
+    pub url: String,
+    pub variants: Json<Vec<ImageVariant>>,
+    pub position: i32,
+    pub is_primary: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AssetImage {
+    pub fn srcset(&self) -> String {
+        srcset(&self.variants)
+    }
+
+    pub fn thumbnail_url(&self) -> &str {
+        variant_url(&self.variants, "thumbnail", &self.url)
+    }
+}
+
+/// Puts the listed gallery entries first in the given order, entries left out keep their relative order after them
+pub fn reorder_positions(current: &[AssetImage], ordered_ids: &[i32]) -> Vec<i32> {
+    let mut ids: Vec<i32> = Vec::with_capacity(current.len());
+
+    let known_ids = ordered_ids.iter().copied().filter(|id| current.iter().any(|i| i.id == *id));
+    for id in known_ids.chain(current.iter().map(|i| i.id)) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    ids
+}
@@ -0,0 +1,66 @@
+use super::asset_items::AssetItem;
+
+
+/// A scanned or typed code resolved into what should be searched for
+#[derive(Clone, Debug, PartialEq)]
+pub enum LookupCode {
+    /// A label qr code deep link such as https://host/asset_items/42
+    ItemId(i32),
+    /// A normalized asset id or serial number
+    Code(String),
+}
+
+/// Scanners add stray whitespace and tags are printed with varying zero padding,
+/// so codes are compared uppercased with whitespace and leading zeros removed
+pub fn normalize_code(raw_code: &str) -> String {
+    let normalized: String = raw_code
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect();
+
+    normalized.trim_start_matches('0').to_string()
+}
+
+/// Returns None when nothing is left to match after normalizing
+pub fn parse_lookup_code(raw_code: &str) -> Option<LookupCode> {
+    let raw_code = raw_code.trim();
+
+    if let Some((_, path)) = raw_code.split_once("/asset_items/") {
+        let id = path.split(['/', '?', '#']).next().unwrap_or_default();
+        if let Ok(id) = id.parse::<i32>() {
+            return Some(LookupCode::ItemId(id));
+        }
+    }
+
+    let normalized = normalize_code(raw_code);
+    if normalized.is_empty() {
+        return None;
+    }
+
+    Some(LookupCode::Code(normalized))
+}
+
+#[derive(Clone, Debug)]
+pub enum LookupResult {
+    Found(i32),
+    /// Several items share the code, e.g. a serial number reused across vendors
+    Multiple(String),
+    /// The scanned code for the message and the normalized code to prefill a new item with,
+    /// None when the scan was a deep link to an item that no longer exists
+    NotFound(String, Option<String>),
+}
+
+/// Picks the item a normalized code refers to from the rows matching it,
+/// an asset id match wins over serial numbers that happen to look the same
+pub fn select_code_match<'a>(code: &str, asset_items: &'a [AssetItem]) -> Option<&'a AssetItem> {
+    let asset_id_matches: Vec<&AssetItem> = asset_items.iter()
+        .filter(|i| i.asset_id.as_deref().map(normalize_code).as_deref() == Some(code))
+        .collect();
+
+    match (asset_id_matches.as_slice(), asset_items) {
+        ([asset_item], _) => Some(asset_item),
+        ([], [asset_item]) => Some(asset_item),
+        _ => None,
+    }
+}
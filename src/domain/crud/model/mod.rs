@@ -1,2 +1,11 @@
 pub mod asset_types;
-pub mod asset_items;
\ No newline at end of file
+pub mod asset_items;
+pub mod money;
+pub mod depreciation;
+pub mod lookup;
+pub mod custom_fields;
+pub mod categories;
+pub mod history;
+pub mod bulk;
+pub mod documents;
+pub mod images;
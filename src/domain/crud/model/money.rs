@@ -0,0 +1,158 @@
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MoneyError {
+    #[error("invalid money value")]
+    Invalid,
+    #[error("money value cannot be negative")]
+    Negative,
+    #[error("money value is too large")]
+    TooLarge,
+}
+
+/// Largest amount NUMERIC(12, 2) holds, 9,999,999,999.99
+fn max_money() -> Decimal {
+    Decimal::new(999_999_999_999, 2)
+}
+
+/// Parses user or spreadsheet supplied amounts such as "$1,299.00", "1299" or " 45.5 "
+/// Returns None for blank values
+pub fn parse_money(raw_value: &str) -> Result<Option<Decimal>, MoneyError> {
+    let cleaned: String = raw_value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '$' | ',' | ' '))
+        .collect();
+
+    if cleaned.is_empty() {
+        return Ok(None);
+    }
+
+    let value = Decimal::from_str(&cleaned).map_err(|_| MoneyError::Invalid)?;
+    if value.is_sign_negative() {
+        return Err(MoneyError::Negative);
+    }
+
+    let value = round_money(value);
+    if value > max_money() {
+        return Err(MoneyError::TooLarge);
+    }
+
+    Ok(Some(value))
+}
+
+/// Rounds to whole cents, matching the NUMERIC(12, 2) column
+pub fn round_money(value: Decimal) -> Decimal {
+    value.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
+
+pub fn format_money(value: Option<Decimal>) -> String {
+    match value {
+        Some(value) => {
+            let value = round_money(value);
+            let sign = if value.is_sign_negative() { "-" } else { "" };
+            let formatted = format!("{:.2}", value.abs());
+            let (whole, cents) = formatted.split_once('.').unwrap_or((formatted.as_str(), "00"));
+
+            // group thousands: 1234567 -> 1,234,567
+            let mut grouped = String::new();
+            for (i, c) in whole.chars().enumerate() {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    grouped.push(',');
+                }
+                grouped.push(c);
+            }
+
+            format!("{}${}.{}", sign, grouped, cents)
+        },
+        None => String::new(),
+    }
+}
+
+/// serde helper used by the csv importers so "$1,299.00" deserializes into a Decimal
+pub fn deserialize_money<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw_value: Option<String> = Option::deserialize(deserializer)?;
+
+    match raw_value {
+        Some(raw_value) => parse_money(&raw_value).map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// serde helper for optional integer columns that may be blank in csv files
+pub fn deserialize_optional_i32<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw_value: Option<String> = Option::deserialize(deserializer)?;
+
+    match raw_value.as_deref().map(str::trim) {
+        Some("") | None => Ok(None),
+        Some(raw_value) => raw_value.parse::<i32>().map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn parse_money_accepts_formatted_amounts() {
+        assert_eq!(parse_money("$1,299.00"), Ok(Some(money("1299.00"))));
+        assert_eq!(parse_money(" 45.5 "), Ok(Some(money("45.50"))));
+        assert_eq!(parse_money("1299"), Ok(Some(money("1299"))));
+    }
+
+    #[test]
+    fn parse_money_rounds_to_cents() {
+        assert_eq!(parse_money("0.005"), Ok(Some(money("0.01"))));
+        assert_eq!(parse_money("10.994"), Ok(Some(money("10.99"))));
+    }
+
+    #[test]
+    fn parse_money_treats_blank_as_none() {
+        assert_eq!(parse_money(""), Ok(None));
+        assert_eq!(parse_money("  $ "), Ok(None));
+    }
+
+    #[test]
+    fn parse_money_rejects_invalid_and_negative() {
+        assert_eq!(parse_money("1.2.3"), Err(MoneyError::Invalid));
+        assert_eq!(parse_money("abc"), Err(MoneyError::Invalid));
+        assert_eq!(parse_money("-5"), Err(MoneyError::Negative));
+    }
+
+    #[test]
+    fn parse_money_caps_at_the_column_size() {
+        assert_eq!(parse_money("9,999,999,999.99"), Ok(Some(money("9999999999.99"))));
+        assert_eq!(parse_money("9999999999.995"), Err(MoneyError::TooLarge));
+        assert_eq!(parse_money("10000000000"), Err(MoneyError::TooLarge));
+        assert_eq!(parse_money("99999999999999999999999999999999"), Err(MoneyError::Invalid));
+    }
+
+    #[test]
+    fn format_money_groups_thousands() {
+        assert_eq!(format_money(Some(money("1234567.8"))), "$1,234,567.80");
+        assert_eq!(format_money(Some(money("999"))), "$999.00");
+        assert_eq!(format_money(Some(money("1000"))), "$1,000.00");
+        assert_eq!(format_money(Some(money("0"))), "$0.00");
+    }
+
+    #[test]
+    fn format_money_keeps_the_sign_and_blank() {
+        assert_eq!(format_money(Some(money("-1500.5"))), "-$1,500.50");
+        assert_eq!(format_money(None), "");
+    }
+}
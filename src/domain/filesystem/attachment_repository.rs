@@ -1,10 +1,10 @@
 use std::future::Future;
 
-use axum_typed_multipart::FieldData;
-use tempfile::NamedTempFile;
 use thiserror::Error;
 
-use super::models::{ImageAttachment, DocumentAttachment, NewImageAttachment, NewDocumentAttachment};
+use uuid::Uuid;
+
+use super::{models::{ImageAttachment, DocumentAttachment, NewImageAttachment, NewDocumentAttachment, UnreferencedAttachments}, storage_check::{AttachmentFiles, StorageCheck, StorageCheckResult}};
 
 
 #[derive(Error, Debug)]
@@ -20,4 +20,21 @@ pub trait AttachmentRepository {
     fn get_document_attachent_from_hash(&self, hash: String)-> impl Future<Output = Result<Option<DocumentAttachment>, AttachmentRepositoryError>> + Send;
     fn add_image_attachent(&self, new_attachment: NewImageAttachment)-> impl Future<Output = Result<ImageAttachment, AttachmentRepositoryError>> + Send;
     fn add_document_attachent(&self, new_attachment: NewDocumentAttachment)-> impl Future<Output = Result<DocumentAttachment, AttachmentRepositoryError>> + Send;
+    /// Stamps rows that lost their last reference and clears the stamp on rows that gained one, returns how many were newly stamped
+    fn mark_unreferenced_attachments(&self)-> impl Future<Output = Result<u64, AttachmentRepositoryError>> + Send;
+    fn get_collectable_attachments(&self, grace_period_hours: i32)-> impl Future<Output = Result<UnreferencedAttachments, AttachmentRepositoryError>> + Send;
+    /// Deletes the rows unreferenced for longer than the grace period and still unreferenced now
+    fn delete_collectable_attachments(&self, grace_period_hours: i32)-> impl Future<Output = Result<UnreferencedAttachments, AttachmentRepositoryError>> + Send;
+    fn get_image_attachment_files(&self)-> impl Future<Output = Result<Vec<AttachmentFiles>, AttachmentRepositoryError>> + Send;
+    fn get_document_attachment_files(&self)-> impl Future<Output = Result<Vec<AttachmentFiles>, AttachmentRepositoryError>> + Send;
+    /// Fails with Duplicate while another check is running
+    fn add_storage_check(&self, quarantine: bool, started_by: Option<Uuid>)-> impl Future<Output = Result<StorageCheck, AttachmentRepositoryError>> + Send;
+    fn finish_storage_check(&self, id: i32, result: StorageCheckResult)-> impl Future<Output = Result<(), AttachmentRepositoryError>> + Send;
+    fn fail_storage_check(&self, id: i32, error: String)-> impl Future<Output = Result<(), AttachmentRepositoryError>> + Send;
+    fn get_storage_checks(&self, limit: i64)-> impl Future<Output = Result<Vec<StorageCheck>, AttachmentRepositoryError>> + Send;
+    fn get_storage_check(&self, id: i32)-> impl Future<Output = Result<Option<StorageCheck>, AttachmentRepositoryError>> + Send;
+    /// Documents of the given types the text extraction job has not been to yet, oldest first
+    fn get_documents_without_text(&self, content_types: &[String], limit: i64)-> impl Future<Output = Result<Vec<DocumentAttachment>, AttachmentRepositoryError>> + Send;
+    /// Stores the extracted text or why there is none, either way the job moves on from the document
+    fn set_document_text(&self, id: i32, text: Option<String>, error: Option<String>)-> impl Future<Output = Result<(), AttachmentRepositoryError>> + Send;
 }
\ No newline at end of file
@@ -1,28 +1,217 @@
+use std::io::Cursor;
+
 use anyhow::{anyhow, Context};
-use image::EncodableLayout;
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, EncodableLayout, ImageDecoder, ImageFormat, ImageReader};
+#[cfg(feature = "heif")]
+use image::{RgbImage, RgbaImage};
+#[cfg(feature = "heif")]
+use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
 
 use crate::domain::filesystem::models::ExtensionType;
 
-use super::models::Extension;
+use super::{models::Extension, svg::sanitize_svg};
+
+
+/// Sized copies generated for every raster upload, the full size is served as the attachment itself
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageSize {
+    Thumbnail,
+    Medium,
+    Full,
+}
+
+pub const IMAGE_SIZES: [ImageSize; 3] = [ImageSize::Thumbnail, ImageSize::Medium, ImageSize::Full];
+
+impl ImageSize {
+    /// longest side in pixels, smaller uploads are never scaled up
+    pub fn max_dimension(&self) -> u32 {
+        match self {
+            ImageSize::Thumbnail => 320,
+            ImageSize::Medium => 800,
+            ImageSize::Full => 1600,
+        }
+    }
+}
+
+impl std::fmt::Display for ImageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = match self {
+            ImageSize::Thumbnail => "thumbnail",
+            ImageSize::Medium => "medium",
+            ImageSize::Full => "full",
+        };
+
+        write!(f, "{}", size)
+    }
+}
+
+#[derive(Debug)]
+pub struct EncodedImage {
+    pub size: ImageSize,
+    pub width: u32,
+    pub data: Vec<u8>,
+}
 
+#[derive(Debug, thiserror::Error)]
+pub enum ImageProcessingError {
+    #[error("the image could not be decoded")]
+    Decode(#[source] anyhow::Error),
+    #[error("the image could not be encoded")]
+    Encode(#[source] anyhow::Error),
+}
 
-pub fn process_image(data: Vec<u8>, ext: Extension) -> anyhow::Result<(Vec<u8>, Extension)> {
+#[derive(Debug)]
+pub enum ProcessedImage {
+    /// vector images have no pixels to resize and are stored sanitized
+    Original(Vec<u8>, Extension),
+    /// WebP encoded, one per entry of IMAGE_SIZES
+    Variants(Vec<EncodedImage>),
+}
+
+/// Decodes the upload and re-encodes it as WebP at each size. Only pixel data is carried over, which drops
+/// EXIF/GPS metadata, so the EXIF orientation is applied first to keep phone photos upright.
+/// CPU bound, call it from a blocking thread.
+pub fn process_image(data: Vec<u8>, ext: Extension) -> Result<ProcessedImage, ImageProcessingError> {
     assert_eq!(ext.ext_type(), ExtensionType::Image);
 
-    if ext == Extension::SVG || ext == Extension::WEBP {
-        return Ok((data, ext))
+    if ext == Extension::SVG {
+        let sanitized = sanitize_svg(&data)
+            .map_err(ImageProcessingError::Decode)?;
+
+        return Ok(ProcessedImage::Original(sanitized, ext))
+    }
+
+    let img = decode_image(&data, ext)
+        .map_err(ImageProcessingError::Decode)?;
+
+    // the webp encoder only accepts 8 bit rgb(a)
+    let img = match img.color().has_alpha() {
+        true => DynamicImage::ImageRgba8(img.to_rgba8()),
+        false => DynamicImage::ImageRgb8(img.to_rgb8()),
+    };
+
+    let mut variants = Vec::with_capacity(IMAGE_SIZES.len());
+    for size in IMAGE_SIZES {
+        let max = size.max_dimension();
+        let resized = match img.width() > max || img.height() > max {
+            true => img.resize(max, max, FilterType::Lanczos3),
+            false => img.clone(),
+        };
+
+        // Create the WebP encoder for the above image
+        let encoder = webp::Encoder::from_image(&resized)
+            .map_err(|_| ImageProcessingError::Encode(anyhow!("webp encoder failed to initialize from image")))?;
+
+        // Encode the image at a specified quality 0-100
+        let webp = encoder.encode(75f32);
+
+        variants.push(EncodedImage {
+            size,
+            width: resized.width(),
+            data: webp.as_bytes().into(),
+        });
     }
 
-    let img = image::load_from_memory(&data)
+    Ok(ProcessedImage::Variants(variants))
+}
+
+/// Re-encodes a raster image from its pixels alone for storing it as a document, which drops EXIF/GPS and any
+/// other metadata the same way the image pipeline does. The format is kept, except for HEIF formats which there
+/// is no encoder for, those become JPEG, or PNG when they have transparency. Returns the data and its format.
+/// CPU bound, call it from a blocking thread.
+pub fn strip_metadata(data: &[u8], ext: Extension) -> Result<(Vec<u8>, Extension), ImageProcessingError> {
+    let img = decode_image(data, ext)
+        .map_err(ImageProcessingError::Decode)?;
+
+    // the encoders only accept 8 bit rgb(a)
+    let has_alpha = img.color().has_alpha();
+    let img = match has_alpha {
+        true => DynamicImage::ImageRgba8(img.to_rgba8()),
+        false => DynamicImage::ImageRgb8(img.to_rgb8()),
+    };
+
+    let ext = match ext {
+        ext if ext.is_heif() && has_alpha => Extension::PNG,
+        ext if ext.is_heif() => Extension::JPEG,
+        ext => ext,
+    };
+
+    let mut out = Cursor::new(Vec::new());
+    let encoded = match ext {
+        Extension::JPEG => img.write_with_encoder(JpegEncoder::new_with_quality(&mut out, 90)),
+        ext => {
+            let format = ImageFormat::from_extension(ext.to_string())
+                .ok_or(ImageProcessingError::Encode(anyhow!("no encoder for {}", ext)))?;
+            img.write_to(&mut out, format)
+        },
+    };
+    encoded.map_err(|e| ImageProcessingError::Encode(e.into()))?;
+
+    Ok((out.into_inner(), ext))
+}
+
+fn decode_image(data: &[u8], ext: Extension) -> anyhow::Result<DynamicImage> {
+    if ext.is_heif() {
+        return decode_heif(data);
+    }
+
+    let format = ImageFormat::from_extension(ext.to_string())
+        .ok_or(anyhow!("no decoder for {}", ext))?;
+
+    let mut decoder = ImageReader::with_format(Cursor::new(data), format)
+        .into_decoder()
         .context("loading image failed")?;
 
-    // Create the WebP encoder for the above image
-    let encoder = webp::Encoder::from_image(&img)
-        .map_err(|_| anyhow!("webp encoder failed to initialize from image"))?;
+    let orientation = decoder.orientation()
+        .context("reading image orientation failed")?;
+
+    let mut img = DynamicImage::from_decoder(decoder)
+        .context("decoding image failed")?;
+    img.apply_orientation(orientation);
+
+    Ok(img)
+}
+
+/// Phones store photos as HEIC and browsers increasingly export AVIF, libheif reads both. The rotation and
+/// mirroring of a HEIF image live in the container rather than EXIF and libheif applies them while decoding.
+#[cfg(feature = "heif")]
+fn decode_heif(data: &[u8]) -> anyhow::Result<DynamicImage> {
+    let lib_heif = LibHeif::new();
+    let ctx = HeifContext::read_from_bytes(data)
+        .context("loading image failed")?;
+    let handle = ctx.primary_image_handle()
+        .context("loading image failed")?;
+
+    let has_alpha = handle.has_alpha_channel();
+    let chroma = match has_alpha {
+        true => RgbChroma::Rgba,
+        false => RgbChroma::Rgb,
+    };
+
+    // high bit depth images are converted down to the 8 bits per channel the webp encoder takes
+    let image = lib_heif.decode(&handle, ColorSpace::Rgb(chroma), None)
+        .context("decoding image failed")?;
+    let plane = image.planes()
+        .interleaved
+        .context("decoded image has no interleaved plane")?;
+
+    // rows are padded to the stride, copy only the pixels
+    let channels = if has_alpha { 4 } else { 3 };
+    let row_len = plane.width as usize * channels;
+    let mut pixels = Vec::with_capacity(row_len * plane.height as usize);
+    for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+        pixels.extend_from_slice(&row[..row_len]);
+    }
+
+    let img = match has_alpha {
+        true => RgbaImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgba8),
+        false => RgbImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgb8),
+    };
 
-    // Encode the image at a specified quality 0-100
-    let webp = encoder.encode(75f32);
+    img.context("decoded image has an unexpected size")
+}
 
-    // TODO: more effecient way than copying to vec
-    Ok((webp.as_bytes().into(), Extension::WEBP))
-}
\ No newline at end of file
+#[cfg(not(feature = "heif"))]
+fn decode_heif(_data: &[u8]) -> anyhow::Result<DynamicImage> {
+    Err(anyhow!("this server is built without heif support"))
+}
@@ -1,4 +1,7 @@
 pub mod attachment_repository;
 pub mod models;
 pub mod persistence_service;
-pub mod image_utils;
\ No newline at end of file
+pub mod image_utils;
+pub mod svg;
+pub mod storage_check;
+pub mod text_extraction;
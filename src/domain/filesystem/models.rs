@@ -1,9 +1,9 @@
-use std::{collections::HashMap, ffi::OsStr, path::PathBuf, sync::LazyLock};
+use std::{collections::HashMap, sync::LazyLock};
 
 use chrono::{DateTime, Utc};
-use mime_guess::Mime;
 use serde::{Deserialize, Serialize};
 use derive_more::derive::{Display, AsRef};
+use sqlx::types::Json;
 use tempfile::NamedTempFile;
 
 
@@ -16,6 +16,9 @@ pub enum ExtensionType {
     Application,
 }
 
+/// Bytes read from the start of an upload to check its signature, large enough to get past an svg's xml prolog
+pub const SNIFF_LENGTH: usize = 4096;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Extension {
     PDF,
@@ -28,6 +31,10 @@ pub enum Extension {
     SVG,
     CSV,
     TXT,
+    DOCX,
+    XLSX,
+    HEIC,
+    AVIF,
 }
 
 impl Extension {
@@ -43,8 +50,80 @@ impl Extension {
             Extension::SVG => ExtensionType::Image,
             Extension::CSV => ExtensionType::Text,
             Extension::TXT => ExtensionType::Text,
+            Extension::DOCX => ExtensionType::Application,
+            Extension::XLSX => ExtensionType::Application,
+            Extension::HEIC => ExtensionType::Image,
+            Extension::AVIF => ExtensionType::Image,
+        }
+    }
+
+    /// Checks the leading bytes against the format's signature, the client supplied content type is not trusted.
+    /// Text formats have no signature so they only need to look like utf-8 text.
+    pub fn matches_signature(&self, head: &[u8]) -> bool {
+        match self {
+            Extension::PDF => head.starts_with(b"%PDF-"),
+            Extension::JPEG => head.starts_with(&[0xFF, 0xD8, 0xFF]),
+            Extension::GIF => head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a"),
+            Extension::PNG => head.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]),
+            Extension::BMP => head.starts_with(b"BM"),
+            Extension::ICO => head.starts_with(&[0x00, 0x00, 0x01, 0x00]),
+            Extension::WEBP => head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP",
+            Extension::SVG => is_text(head) && looks_like_svg(head),
+            Extension::CSV => is_text(head),
+            Extension::TXT => is_text(head),
+            // office documents are zip archives
+            Extension::DOCX => head.starts_with(b"PK\x03\x04"),
+            Extension::XLSX => head.starts_with(b"PK\x03\x04"),
+            // mif1/msf1 are the generic heif brands, either codec may use them
+            Extension::HEIC => has_heif_brand(head, &[b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1"]),
+            Extension::AVIF => has_heif_brand(head, &[b"avif", b"avis", b"mif1", b"msf1"]),
         }
     }
+
+    /// Whether the image pipeline can read the format, svgs are sanitized rather than decoded
+    pub fn is_decodable(&self) -> bool {
+        self.ext_type() == ExtensionType::Image
+    }
+
+    /// Documents whose text the extraction job indexes for search
+    pub fn has_text(&self) -> bool {
+        matches!(self, Extension::PDF | Extension::TXT | Extension::CSV)
+    }
+
+    /// HEIC and AVIF are both HEIF containers and are decoded with libheif instead of the image crate
+    pub fn is_heif(&self) -> bool {
+        matches!(self, Extension::HEIC | Extension::AVIF)
+    }
+
+    /// False for HEIF formats when the server is built without the heif feature
+    pub fn is_supported(&self) -> bool {
+        !self.is_heif() || cfg!(feature = "heif")
+    }
+}
+
+/// HEIF files start with an ftyp box whose major brand names the flavour
+fn has_heif_brand(head: &[u8], brands: &[&[u8; 4]]) -> bool {
+    head.len() >= 12 && &head[4..8] == b"ftyp" && brands.iter().any(|brand| &head[8..12] == brand.as_slice())
+}
+
+fn is_text(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return false;
+    }
+
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // the sniffed prefix may end part way through a multi byte character
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+fn looks_like_svg(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    (text.starts_with("<?xml") || text.starts_with("<svg") || text.starts_with("<!--") || text.starts_with("<!DOCTYPE svg"))
+        && text.contains("<svg")
 }
 
 impl std::fmt::Display for Extension {
@@ -60,6 +139,10 @@ impl std::fmt::Display for Extension {
             Extension::SVG => "svg",
             Extension::CSV => "csv",
             Extension::TXT => "txt",
+            Extension::DOCX => "docx",
+            Extension::XLSX => "xlsx",
+            Extension::HEIC => "heic",
+            Extension::AVIF => "avif",
         };
 
         write!(f, "{}", ext)
@@ -81,10 +164,14 @@ pub static MIME_LOOKUP: LazyLock<HashMap<&'static str, Extension>> = LazyLock::n
        // ("audio/ogg", "ogg"),
        // ("audio/webm", "weba"),
        // ("audio/x-matroska", "mka"),
+        ("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", Extension::XLSX),
+        ("application/vnd.openxmlformats-officedocument.wordprocessingml.document", Extension::DOCX),
        // ("image/apng", "apng"),
-       // ("image/avif", "avif"),
+        ("image/avif", Extension::AVIF),
         ("image/bmp", Extension::BMP),
         ("image/gif", Extension::GIF),
+        ("image/heic", Extension::HEIC),
+        ("image/heif", Extension::HEIC),
         ("image/jpeg", Extension::JPEG),
        // ("image/ktx", "ktx"),
         ("image/png", Extension::PNG),
@@ -107,6 +194,14 @@ pub static MIME_LOOKUP: LazyLock<HashMap<&'static str, Extension>> = LazyLock::n
     map
 });
 
+/// An upload written to disk and hashed in the same pass as it was received
+#[derive(Debug)]
+pub struct HashedTempFile {
+    pub temp_file: NamedTempFile,
+    pub hash: String,
+    pub size: u64,
+}
+
 #[derive(Debug)]
 pub struct FilePayload {
     pub temp_file: NamedTempFile,
@@ -133,6 +228,48 @@ impl Filename {
     pub fn new(raw_value: impl Into<String>) -> Self {
         Self(raw_value.into())
     }
+
+    /// Inline Content-Disposition value. The original name comes from the uploader and may hold quotes
+    /// or non ascii characters a header can't carry, those are replaced.
+    pub fn content_disposition(&self) -> String {
+        let filename: String = self.0.chars()
+            .map(|c| if (c.is_ascii_graphic() || c == ' ') && c != '"' && c != '\\' { c } else { '_' })
+            .collect();
+
+        format!("inline; filename=\"{}\"", filename)
+    }
+}
+
+/// Last segment of an attachment or variant url, the name the file is stored under
+pub fn stored_name(url: &str) -> &str {
+    url.rsplit('/').next().unwrap_or(url)
+}
+
+/// A resized copy of an image attachment, see image_utils::ImageSize
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImageVariant {
+    pub size: String,
+    pub width: u32,
+    pub url: String,
+    /// blake3 of the stored webp, the name carries the hash of the upload it was made from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+/// e.g. "/content/images/ab12-thumbnail.webp 320w, /content/images/ab12-medium.webp 800w"
+pub fn srcset(variants: &[ImageVariant]) -> String {
+    variants.iter()
+        .map(|v| format!("{} {}w", v.url, v.width))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Falls back to the attachment url for images stored before variants were generated
+pub fn variant_url<'a>(variants: &'a [ImageVariant], size: &str, fallback: &'a str) -> &'a str {
+    variants.iter()
+        .find(|v| v.size == size)
+        .map(|v| v.url.as_str())
+        .unwrap_or(fallback)
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -141,6 +278,9 @@ pub struct NewImageAttachment {
     pub hash: String,
     pub content_type: ContentType,
     pub url: String,
+    pub variants: Vec<ImageVariant>,
+    /// blake3 of the file stored at url, which differs from hash once the upload was converted or sanitized
+    pub checksum: String,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -149,6 +289,7 @@ pub struct NewDocumentAttachment {
     pub hash: String,
     pub content_type: ContentType,
     pub url: String,
+    pub checksum: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow, sqlx::Type)]
@@ -158,6 +299,7 @@ pub struct ImageAttachment {
     pub hash: String,
     pub content_type: ContentType,
     pub url: String,
+    pub variants: Json<Vec<ImageVariant>>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -169,4 +311,90 @@ pub struct DocumentAttachment {
     pub content_type: ContentType,
     pub url: String,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+/// An attachment row nothing points at any more, found by the attachment gc
+#[derive(Clone, Debug, Serialize)]
+pub struct UnreferencedAttachment {
+    pub id: i32,
+    pub filename: Filename,
+    pub url: String,
+    pub variants: Json<Vec<ImageVariant>>,
+    pub created_at: DateTime<Utc>,
+    pub unreferenced_since: DateTime<Utc>,
+}
+
+impl UnreferencedAttachment {
+    /// Names of the stored files behind the attachment, the full size variant shares the attachment url
+    pub fn stored_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::with_capacity(self.variants.len() + 1);
+        let urls = std::iter::once(self.url.as_str()).chain(self.variants.iter().map(|v| v.url.as_str()));
+
+        for name in urls.map(stored_name) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        names
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct UnreferencedAttachments {
+    pub images: Vec<UnreferencedAttachment>,
+    pub documents: Vec<UnreferencedAttachment>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heif_head(brand: &[u8; 4]) -> Vec<u8> {
+        let mut head = vec![0x00, 0x00, 0x00, 0x18];
+        head.extend_from_slice(b"ftyp");
+        head.extend_from_slice(brand);
+        head.extend_from_slice(&[0x00; 4]);
+        head
+    }
+
+    #[test]
+    fn binary_formats_match_their_magic_bytes() {
+        assert!(Extension::PDF.matches_signature(b"%PDF-1.7\n"));
+        assert!(Extension::JPEG.matches_signature(&[0xFF, 0xD8, 0xFF, 0xE0]));
+        assert!(Extension::GIF.matches_signature(b"GIF89a\x01\x00"));
+        assert!(Extension::PNG.matches_signature(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00]));
+        assert!(Extension::WEBP.matches_signature(b"RIFF\x24\x00\x00\x00WEBPVP8 "));
+        assert!(Extension::DOCX.matches_signature(b"PK\x03\x04\x14\x00"));
+        assert!(Extension::HEIC.matches_signature(&heif_head(b"heic")));
+        assert!(Extension::AVIF.matches_signature(&heif_head(b"avif")));
+    }
+
+    #[test]
+    fn mismatched_content_is_rejected() {
+        // a png renamed to .jpg, or html sent as a pdf
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        assert!(!Extension::JPEG.matches_signature(&png));
+        assert!(!Extension::PDF.matches_signature(b"<html><script>"));
+        assert!(!Extension::WEBP.matches_signature(b"RIFF\x24\x00\x00\x00WAVEfmt "));
+        assert!(!Extension::AVIF.matches_signature(&heif_head(b"heic")));
+        assert!(!Extension::HEIC.matches_signature(b"ftyp"));
+    }
+
+    #[test]
+    fn text_formats_only_need_to_be_utf8() {
+        assert!(Extension::CSV.matches_signature(b"name,room\nProjector,101\n"));
+        assert!(Extension::TXT.matches_signature("caf\u{e9}".as_bytes()));
+        // the sniffed prefix may cut a multi byte character in half
+        assert!(Extension::TXT.matches_signature(&"caf\u{e9}".as_bytes()[..4]));
+        assert!(!Extension::TXT.matches_signature(&[0x48, 0x00, 0x49]));
+        assert!(!Extension::CSV.matches_signature(&[0xFF, 0xD8, 0xFF, 0xE0]));
+    }
+
+    #[test]
+    fn svgs_need_an_svg_element() {
+        assert!(Extension::SVG.matches_signature(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"));
+        assert!(Extension::SVG.matches_signature(b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<svg/>"));
+        assert!(!Extension::SVG.matches_signature(b"<html><body></body></html>"));
+        assert!(!Extension::SVG.matches_signature(b"plain text mentioning <svg>"));
+    }
+}
@@ -1,16 +1,20 @@
-use std::future::Future;
+use std::{fs, future::Future, path::{Path, PathBuf}};
 
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
 use tempfile::NamedTempFile;
 use thiserror::Error;
-use tokio::{sync::oneshot::error::RecvError, task::JoinError};
+use tokio::{io::AsyncWriteExt, sync::oneshot::error::RecvError, task::JoinError};
 
-use super::models::{ContentType, FilePayload, Filename, NewDocumentAttachment, NewImageAttachment};
+use super::{image_utils::{process_image, strip_metadata, ImageProcessingError, ImageSize, ProcessedImage}, models::{ContentType, Extension, ExtensionType, FilePayload, Filename, HashedTempFile, ImageVariant, NewDocumentAttachment, NewImageAttachment, MIME_LOOKUP}, svg::sanitize_svg};
 
 
 #[derive(Error, Debug)]
 pub enum PersistenceError {
     #[error("file type not supported")]
     ExtNotSupported,
+    #[error("file not found in storage")]
+    NotFound,
     #[error(transparent)]
     Task(#[from] JoinError),
     #[error(transparent)]
@@ -18,11 +22,258 @@ pub enum PersistenceError {
     #[error(transparent)]
     IO(#[from] std::io::Error),
     #[error(transparent)]
+    Image(#[from] ImageProcessingError),
+    #[error(transparent)]
     Unexpected(#[from] anyhow::Error),
 }
 
+/// Top level folders of the store, urls are <route_path>/<folder>/<name> whichever backend holds the file
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageFolder {
+    Images,
+    Documents,
+}
+
+pub const STORAGE_FOLDERS: [StorageFolder; 2] = [StorageFolder::Images, StorageFolder::Documents];
+
+impl std::fmt::Display for StorageFolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let folder = match self {
+            StorageFolder::Images => "images",
+            StorageFolder::Documents => "documents",
+        };
+
+        write!(f, "{}", folder)
+    }
+}
+
+/// How a stored file reaches the client
+pub enum StoredFile {
+    /// on this machine's disk, served with range and conditional request support
+    Local(PathBuf),
+    /// streamed through the app from the object store
+    Stream(BoxStream<'static, Result<Vec<u8>, PersistenceError>>),
+    /// a byte range fetched from the object store, content_range is the Content-Range header to answer with
+    Partial { data: Vec<u8>, content_range: String },
+    /// fetched by the client straight from the object store with a short lived presigned url
+    Redirect(String),
+}
+
+/// How a stored file is going to be served. Object stores need this up front, presigned urls carry the
+/// headers as response overrides and ranges are fetched from the store instead of cut out locally.
+#[derive(Clone, Debug)]
+pub struct FileRequest {
+    pub content_type: String,
+    pub content_disposition: String,
+    /// first and last byte of a Range request, the last is open ended when None
+    pub range: Option<(u64, Option<u64>)>,
+}
+
+/// A file produced by the upload pipeline, ready to be written under its storage folder
+#[derive(Debug)]
+pub struct StorageObject {
+    pub name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+    pub checksum: String,
+}
+
 pub trait PersistenceService {
-    fn hash_file(&self, file: NamedTempFile)-> impl Future<Output = Result<(NamedTempFile, String), PersistenceError>> + Send;
     fn persist_image_file(&self, payload: FilePayload) -> impl Future<Output = Result<NewImageAttachment, PersistenceError>> + Send;
     fn persist_document_file(&self, payload: FilePayload) -> impl Future<Output = Result<NewDocumentAttachment, PersistenceError>> + Send;
-}
\ No newline at end of file
+    fn retrieve_file(&self, folder: StorageFolder, name: &str, request: &FileRequest) -> impl Future<Output = Result<StoredFile, PersistenceError>> + Send;
+    /// Loads a whole stored file into memory, for background jobs that work on the contents
+    fn read_file(&self, folder: StorageFolder, name: &str) -> impl Future<Output = Result<Vec<u8>, PersistenceError>> + Send;
+    /// Removing a file that is already gone is not an error
+    fn delete_file(&self, folder: StorageFolder, name: &str) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+    fn list_files(&self, folder: StorageFolder) -> impl Future<Output = Result<Vec<String>, PersistenceError>> + Send;
+    /// blake3 of the stored bytes, used by the storage check
+    fn hash_stored_file(&self, folder: StorageFolder, name: &str) -> impl Future<Output = Result<String, PersistenceError>> + Send;
+    /// Moves a file out of the served folders to quarantine/<folder>/<name> for inspection
+    fn quarantine_file(&self, folder: StorageFolder, name: &str) -> impl Future<Output = Result<(), PersistenceError>> + Send;
+}
+
+/// Writes an upload to a temp file while hashing it, so the bytes are only touched once as they arrive
+pub struct HashingWriter {
+    temp_file: NamedTempFile,
+    file: tokio::fs::File,
+    hasher: blake3::Hasher,
+    size: u64,
+}
+
+impl HashingWriter {
+    pub fn new() -> Result<Self, PersistenceError> {
+        let temp_file = NamedTempFile::new()?;
+        let file = tokio::fs::File::from_std(temp_file.reopen()?);
+
+        Ok(Self { temp_file, file, hasher: blake3::Hasher::new(), size: 0 })
+    }
+
+    pub async fn write(&mut self, chunk: &[u8]) -> Result<(), PersistenceError> {
+        self.hasher.update(chunk);
+        self.file.write_all(chunk).await?;
+        self.size += chunk.len() as u64;
+
+        Ok(())
+    }
+
+    pub async fn finish(mut self) -> Result<HashedTempFile, PersistenceError> {
+        // tokio writes in the background, make sure everything reached the file before it is read back
+        self.file.flush().await?;
+
+        Ok(HashedTempFile {
+            temp_file: self.temp_file,
+            hash: self.hasher.finalize().to_string(),
+            size: self.size,
+        })
+    }
+}
+
+/// Large files are memory mapped and hashed on the rayon pool, call from a blocking task
+pub fn hash_path(path: &Path) -> std::io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_mmap_rayon(path)?;
+
+    Ok(hasher.finalize().to_string())
+}
+
+/// A file of the image pipeline with the size and width of the variant it holds, None for stored originals
+type ImageObject = (Option<(ImageSize, u32)>, StorageObject);
+
+/// Runs the image pipeline and names the resulting files, the storage backends only have to write them
+pub async fn prepare_image_files(payload: FilePayload, route_path: &str) -> Result<(NewImageAttachment, Vec<StorageObject>), PersistenceError> {
+    let ext = *MIME_LOOKUP.get(payload.content_type.as_str()).ok_or(PersistenceError::ExtNotSupported)?;
+    if !ext.is_decodable() {
+        return Err(PersistenceError::ExtNotSupported);
+    }
+
+    let FilePayload { temp_file, filename, hash, content_type } = payload;
+    let variant_hash = hash.clone();
+
+    // decoding and encoding is cpu bound, keep it off the async workers
+    let (content_type, objects) = tokio::task::spawn_blocking(move || -> Result<(String, Vec<ImageObject>), PersistenceError> {
+        let data = fs::read(temp_file.path())?;
+
+        match process_image(data, ext)? {
+            ProcessedImage::Original(data, ext) => {
+                let object = StorageObject {
+                    name: format!("{}.{}", variant_hash, ext),
+                    content_type: content_type.clone(),
+                    checksum: blake3::hash(&data).to_string(),
+                    data,
+                };
+
+                Ok((content_type, vec![(None, object)]))
+            },
+            ProcessedImage::Variants(images) => {
+                let objects = images.into_iter()
+                    .map(|image| {
+                        // the full size keeps the plain hash name so it doubles as the attachment itself
+                        let name = match image.size {
+                            ImageSize::Full => format!("{}.{}", variant_hash, Extension::WEBP),
+                            size => format!("{}-{}.{}", variant_hash, size, Extension::WEBP),
+                        };
+                        let object = StorageObject {
+                            name,
+                            content_type: "image/webp".to_string(),
+                            checksum: blake3::hash(&image.data).to_string(),
+                            data: image.data,
+                        };
+
+                        (Some((image.size, image.width)), object)
+                    })
+                    .collect();
+
+                Ok(("image/webp".to_string(), objects))
+            },
+        }
+    })
+    .await??;
+
+    let mut url = String::new();
+    let mut checksum = String::new();
+    let mut variants = Vec::with_capacity(objects.len());
+    for (variant, object) in objects.iter() {
+        let object_url = format!("{}/{}/{}", route_path, StorageFolder::Images, object.name);
+
+        match variant {
+            Some((size, width)) => {
+                if *size == ImageSize::Full {
+                    url = object_url.clone();
+                    checksum = object.checksum.clone();
+                }
+                variants.push(ImageVariant {
+                    size: size.to_string(),
+                    width: *width,
+                    url: object_url,
+                    checksum: Some(object.checksum.clone()),
+                });
+            },
+            None => {
+                url = object_url;
+                checksum = object.checksum.clone();
+            },
+        }
+    }
+
+    let attachment = NewImageAttachment {
+        filename: Filename::new(filename),
+        url: url,
+        content_type: ContentType::new(content_type),
+        hash: hash,
+        variants: variants,
+        checksum: checksum,
+    };
+
+    Ok((attachment, objects.into_iter().map(|(_, object)| object).collect()))
+}
+
+/// Checks a document and names it, the temp file is left for the storage backend to move into place
+pub async fn prepare_document_file(payload: FilePayload, route_path: &str) -> Result<(NewDocumentAttachment, String, NamedTempFile), PersistenceError> {
+    // images are accepted as documents too, e.g. photos of damage, and keep their size
+    let uploaded_ext = *MIME_LOOKUP.get(payload.content_type.as_str()).ok_or(PersistenceError::ExtNotSupported)?;
+
+    // svgs are rewritten without scripts since documents are served from our origin too, and rasters are
+    // re-encoded so phone photos don't hand out their EXIF/GPS metadata, which also proves they decode
+    let (mut payload, checksum, ext) = match uploaded_ext.ext_type() {
+        ExtensionType::Image if uploaded_ext.is_decodable() => tokio::task::spawn_blocking(move || -> Result<(FilePayload, String, Extension), PersistenceError> {
+            let data = fs::read(payload.temp_file.path())?;
+
+            let (data, ext) = match uploaded_ext {
+                Extension::SVG => (sanitize_svg(&data).map_err(ImageProcessingError::Decode)?, Extension::SVG),
+                ext => strip_metadata(&data, ext)?,
+            };
+            fs::write(payload.temp_file.path(), &data)?;
+
+            let checksum = blake3::hash(&data).to_string();
+            Ok((payload, checksum, ext))
+        })
+        .await??,
+        _ => {
+            let checksum = payload.hash.clone();
+            (payload, checksum, uploaded_ext)
+        },
+    };
+
+    // heif photos come back as another format
+    if ext != uploaded_ext {
+        payload.content_type = match ext {
+            Extension::PNG => "image/png",
+            _ => "image/jpeg",
+        }.to_string();
+    }
+
+    let name = format!("{}.{}", payload.hash, ext);
+    let url = format!("{}/{}/{}", route_path, StorageFolder::Documents, &name);
+
+    let attachment = NewDocumentAttachment {
+        filename: Filename::new(payload.filename),
+        url: url,
+        content_type: ContentType::new(payload.content_type),
+        hash: payload.hash,
+        checksum: checksum,
+    };
+
+    Ok((attachment, name, payload.temp_file))
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use super::{models::{stored_name, ContentType, ImageVariant}, persistence_service::StorageFolder};
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageIssueKind {
+    /// an attachment row points at a file the store does not have
+    Missing,
+    /// the stored bytes no longer hash to the recorded checksum
+    Mismatch,
+    /// a stored file no attachment row claims
+    Orphaned,
+}
+
+impl std::fmt::Display for StorageIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            StorageIssueKind::Missing => "missing",
+            StorageIssueKind::Mismatch => "hash mismatch",
+            StorageIssueKind::Orphaned => "orphaned",
+        };
+
+        write!(f, "{}", kind)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StorageIssue {
+    pub kind: StorageIssueKind,
+    pub folder: StorageFolder,
+    pub name: String,
+    pub attachment_id: Option<i32>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    /// moved out of the served folders by the check
+    pub quarantined: bool,
+}
+
+/// The stored files of one attachment row
+#[derive(Clone, Debug)]
+pub struct AttachmentFiles {
+    pub id: i32,
+    pub hash: String,
+    pub content_type: ContentType,
+    pub url: String,
+    pub checksum: Option<String>,
+    pub variants: Json<Vec<ImageVariant>>,
+}
+
+impl AttachmentFiles {
+    /// Every stored name with the checksum its bytes should have, None when only presence can be checked
+    pub fn expected_files(&self) -> Vec<(&str, Option<&str>)> {
+        // rows from before checksums were recorded kept the upload as is, unless it was converted or sanitized
+        let content_type: &str = self.content_type.as_ref();
+        let legacy_checksum = match self.variants.is_empty() && content_type != "image/svg+xml" {
+            true => Some(self.hash.as_str()),
+            false => None,
+        };
+
+        let mut files = vec![(stored_name(&self.url), self.checksum.as_deref().or(legacy_checksum))];
+        for variant in self.variants.iter() {
+            let name = stored_name(&variant.url);
+            if files.iter().any(|(n, _)| *n == name) {
+                continue;
+            }
+            files.push((name, variant.checksum.as_deref()));
+        }
+
+        files
+    }
+}
+
+#[derive(Clone, Debug, Serialize, sqlx::FromRow)]
+pub struct StorageCheck {
+    pub id: i32,
+    pub quarantine: bool,
+    pub checked_files: i32,
+    pub issues: Json<Vec<StorageIssue>>,
+    pub error: Option<String>,
+    pub started_by: Option<Uuid>,
+    pub started_by_name: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl StorageCheck {
+    pub fn status(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("failed: {}", error);
+        }
+
+        match (self.finished_at, self.issues.len()) {
+            (None, _) => "running".to_string(),
+            (Some(_), 0) => "ok".to_string(),
+            (Some(_), 1) => "1 issue".to_string(),
+            (Some(_), n) => format!("{} issues", n),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.finished_at.is_none() && self.error.is_none()
+    }
+
+    pub fn started_at_display(&self) -> String {
+        self.started_at.format("%Y-%m-%d %H:%M").to_string()
+    }
+
+    pub fn finished_at_display(&self) -> String {
+        self.finished_at.map(|d| d.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+    }
+}
+
+/// Outcome of walking the store, recorded on the storage check row
+#[derive(Clone, Debug, Default)]
+pub struct StorageCheckResult {
+    pub checked_files: i32,
+    pub issues: Vec<StorageIssue>,
+}
@@ -0,0 +1,195 @@
+use anyhow::{anyhow, Context};
+use quick_xml::{events::{BytesStart, Event}, Reader, Writer};
+
+
+/// Elements kept in a sanitized svg, anything else is dropped along with its children.
+/// Filter primitives (feBlend, feGaussianBlur, ...) are matched by their "fe" prefix.
+/// <animate> and <set> are left out since they can rewrite an href after sanitizing.
+const ALLOWED_ELEMENTS: [&str; 29] = [
+    "svg", "g", "defs", "symbol", "use", "switch", "view", "title", "desc",
+    "path", "rect", "circle", "ellipse", "line", "polyline", "polygon", "image",
+    "text", "tspan", "textPath",
+    "linearGradient", "radialGradient", "stop", "pattern", "clipPath", "mask", "marker", "filter",
+    "animateTransform",
+];
+
+/// Only same document references and inline raster images may be linked, nothing is fetched from elsewhere
+const ALLOWED_DATA_URIS: [&str; 4] = ["data:image/png;", "data:image/jpeg;", "data:image/gif;", "data:image/webp;"];
+
+/// Rebuilds an uploaded svg keeping only drawing elements and harmless attributes.
+/// Scripts, foreignObject, style sheets, event handler attributes, external references, doctypes
+/// (entity expansion) and processing instructions are removed. Fails when the document is not an svg.
+pub fn sanitize_svg(data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let text = std::str::from_utf8(data).context("svg is not valid utf-8")?;
+    let mut reader = Reader::from_str(text);
+    let mut writer = Writer::new(Vec::with_capacity(data.len()));
+
+    // depth inside a dropped element, its whole subtree is skipped
+    let mut skip_depth = 0usize;
+    let mut seen_root = false;
+
+    loop {
+        let event = reader.read_event().context("svg could not be parsed")?;
+
+        match event {
+            Event::Start(element) => {
+                if skip_depth > 0 || !is_allowed_element(&element, &mut seen_root)? {
+                    skip_depth += 1;
+                    continue;
+                }
+                writer.write_event(Event::Start(sanitize_element(&element)?))?;
+            },
+            Event::Empty(element) => {
+                if skip_depth > 0 || !is_allowed_element(&element, &mut seen_root)? {
+                    continue;
+                }
+                writer.write_event(Event::Empty(sanitize_element(&element)?))?;
+            },
+            Event::End(element) => {
+                if skip_depth > 0 {
+                    skip_depth -= 1;
+                    continue;
+                }
+                writer.write_event(Event::End(element))?;
+            },
+            Event::Text(text) => {
+                if skip_depth == 0 {
+                    writer.write_event(Event::Text(text))?;
+                }
+            },
+            Event::CData(cdata) => {
+                if skip_depth == 0 {
+                    writer.write_event(Event::CData(cdata))?;
+                }
+            },
+            Event::Decl(decl) => {
+                writer.write_event(Event::Decl(decl))?;
+            },
+            Event::DocType(_) | Event::PI(_) | Event::Comment(_) => { },
+            Event::Eof => break,
+        }
+    }
+
+    if !seen_root {
+        return Err(anyhow!("document has no svg root element"));
+    }
+
+    Ok(writer.into_inner())
+}
+
+fn is_allowed_element(element: &BytesStart, seen_root: &mut bool) -> anyhow::Result<bool> {
+    let name = element.name();
+    let name = std::str::from_utf8(name.as_ref()).context("invalid element name")?;
+
+    if !*seen_root {
+        if name != "svg" {
+            return Err(anyhow!("root element is {} not svg", name));
+        }
+        *seen_root = true;
+    }
+
+    Ok(ALLOWED_ELEMENTS.contains(&name) || (name.starts_with("fe") && name.len() > 2))
+}
+
+fn sanitize_element<'a>(element: &BytesStart<'a>) -> anyhow::Result<BytesStart<'static>> {
+    let name = std::str::from_utf8(element.name().as_ref())
+        .context("invalid element name")?
+        .to_string();
+
+    let mut sanitized = BytesStart::new(name);
+    for attribute in element.attributes() {
+        let attribute = attribute.context("invalid svg attribute")?;
+        let key = std::str::from_utf8(attribute.key.as_ref()).context("invalid attribute name")?.to_string();
+        let value = attribute.unescape_value().context("invalid attribute value")?.to_string();
+
+        if is_allowed_attribute(&key, &value) {
+            sanitized.push_attribute((key.as_str(), value.as_str()));
+        }
+    }
+
+    Ok(sanitized)
+}
+
+fn is_allowed_attribute(key: &str, value: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    let local_name = key.rsplit(':').next().unwrap_or(&key);
+    // whitespace and control characters are ignored by browsers inside "java\tscript:" urls
+    let value: String = value.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect::<String>().to_ascii_lowercase();
+
+    if local_name.starts_with("on") {
+        return false;
+    }
+
+    if value.contains("javascript:") || value.contains("vbscript:") || value.contains("expression(") || value.contains("@import") {
+        return false;
+    }
+
+    if local_name == "href" || local_name == "src" {
+        return value.starts_with('#') || ALLOWED_DATA_URIS.iter().any(|uri| value.starts_with(uri));
+    }
+
+    // fill="url(#gradient)" is fine, url(https://...) would load something from elsewhere
+    let mut rest = value.as_str();
+    while let Some(index) = rest.find("url(") {
+        rest = &rest[index + 4..];
+        if !rest.trim_start_matches(['\'', '"']).starts_with('#') {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sanitize(svg: &str) -> String {
+        String::from_utf8(sanitize_svg(svg.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn keeps_drawing_elements() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><g fill="url(#fade)"><rect width="10" height="10"/></g></svg>"##;
+
+        assert_eq!(sanitize(svg), svg);
+    }
+
+    #[test]
+    fn drops_scripts_and_foreign_objects_with_their_children() {
+        let svg = r#"<svg><script>alert(1)</script><foreignObject><div><p>hi</p></div></foreignObject><circle r="1"/></svg>"#;
+
+        assert_eq!(sanitize(svg), r#"<svg><circle r="1"/></svg>"#);
+    }
+
+    #[test]
+    fn drops_event_handlers_and_script_urls() {
+        let svg = r#"<svg onload="alert(1)"><a href="javascript:alert(1)"/><rect style="fill: url(java&#9;script:alert(1))" onclick="x()"/></svg>"#;
+
+        assert_eq!(sanitize(svg), r#"<svg><rect/></svg>"#);
+    }
+
+    #[test]
+    fn only_allows_local_and_inline_image_references() {
+        let svg = r##"<svg><use href="#icon"/><image xlink:href="https://example.com/x.png"/><image href="data:image/png;base64,AAAA"/><rect fill="url(https://example.com/x)"/></svg>"##;
+
+        assert_eq!(
+            sanitize(svg),
+            r##"<svg><use href="#icon"/><image/><image href="data:image/png;base64,AAAA"/><rect/></svg>"##,
+        );
+    }
+
+    #[test]
+    fn removes_doctypes_and_processing_instructions() {
+        let svg = r#"<?xml version="1.0"?><!DOCTYPE svg [<!ENTITY x "boom">]><?xml-stylesheet href="evil.css"?><!-- note --><svg/>"#;
+
+        assert_eq!(sanitize(svg), r#"<?xml version="1.0"?><svg/>"#);
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_svg() {
+        assert!(sanitize_svg(b"<html><svg/></html>").is_err());
+        assert!(sanitize_svg(b"just text").is_err());
+        assert!(sanitize_svg(&[0xFF, 0xFE, 0x00]).is_err());
+    }
+}
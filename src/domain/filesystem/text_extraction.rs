@@ -0,0 +1,68 @@
+use anyhow::{bail, Context};
+
+use super::models::Extension;
+
+
+/// Longest text kept per document. Postgres caps tsvectors at 1MB and stops counting word positions at 16383,
+/// the pages past this add little to a search.
+pub const MAX_TEXT_LENGTH: usize = 256 * 1024;
+
+/// Pulls the searchable text out of a stored document, see Extension::has_text for the supported formats.
+/// Parsing a pdf is CPU bound, call it from a blocking thread.
+pub fn extract_text(data: &[u8], ext: Extension) -> anyhow::Result<String> {
+    let text = match ext {
+        Extension::PDF => pdf_extract::extract_text_from_mem(data).context("could not read the pdf text")?,
+        Extension::TXT | Extension::CSV => String::from_utf8_lossy(data).into_owned(),
+        _ => bail!("no text extraction for {} files", ext),
+    };
+
+    Ok(normalize_text(&text))
+}
+
+/// Collapses the layout whitespace pdfs are full of and drops NUL characters, which postgres text can not hold
+fn normalize_text(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len().min(MAX_TEXT_LENGTH));
+
+    for word in text.split(|c: char| c.is_whitespace() || c == '\0').filter(|word| !word.is_empty()) {
+        if normalized.len() + word.len() + 1 > MAX_TEXT_LENGTH {
+            break;
+        }
+
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.push_str(word);
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapses_layout_whitespace() {
+        assert_eq!(normalize_text("  Invoice\n\n  No.\t 42\r\n\u{a0}Total  "), "Invoice No. 42 Total");
+    }
+
+    #[test]
+    fn drops_nul_characters() {
+        assert_eq!(normalize_text("Serial\0Number\0\0 X1"), "Serial Number X1");
+    }
+
+    #[test]
+    fn blank_text_becomes_empty() {
+        assert_eq!(normalize_text(" \n\t\0 "), "");
+    }
+
+    #[test]
+    fn stops_at_the_last_whole_word_within_the_limit() {
+        let text = "word ".repeat(MAX_TEXT_LENGTH);
+        let normalized = normalize_text(&text);
+
+        assert!(normalized.len() <= MAX_TEXT_LENGTH);
+        assert!(normalized.len() > MAX_TEXT_LENGTH - 5);
+        assert!(normalized.ends_with("word"));
+    }
+}
@@ -13,6 +13,7 @@ pub struct OauthCredentials {
 pub struct PasswordCredentials {
     pub email: String,
     pub password: String,
+    #[allow(dead_code)]
     pub next: Option<String>,
 }
 
@@ -43,6 +43,7 @@ pub trait UserRepository: Send + Sync + Clone + 'static {
         user_id: Uuid,
     ) -> impl Future<Output = Result<Option<Uuid>, UserRepositoryError>> + Send;
 
+    #[allow(dead_code)]
     fn get_user(
         &self,
         id: &uuid::Uuid,
@@ -1,7 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use compact_str::CompactString;
 use derive_more::derive::{Display, AsRef};
 
 
@@ -25,7 +24,7 @@ impl PasswordHash {
     }
 }
 
-#[derive(Clone, Debug, Display, Serialize, Deserialize, AsRef, sqlx::Type)]
+#[derive(Clone, Debug, Default, Display, Serialize, Deserialize, AsRef, sqlx::Type)]
 #[as_ref(str, [u8], String)]
 pub struct Picture(String);
 
@@ -35,16 +34,12 @@ impl Picture {
     }
 }
 
-impl Default for Picture {
-    fn default() -> Self {
-        Self(Default::default())
-    }
-}
 
 
 #[derive(Clone, Copy, Debug, Display, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "provider", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
 pub enum Provider {
     EmailPassword,
     Google,
@@ -111,6 +106,12 @@ pub struct SessionUser {
     pub user: UserDescriptor,
 }
 
+impl SessionUser {
+    pub fn is_admin(&self) -> bool {
+        self.user.role == "admin"
+    }
+}
+
 impl From<UserDescriptor> for SessionUser {
     fn from(value: UserDescriptor) -> Self {
         Self{user: value}
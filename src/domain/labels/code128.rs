@@ -0,0 +1,115 @@
+use thiserror::Error;
+
+
+#[derive(Error, Debug, PartialEq)]
+pub enum Code128Error {
+    #[error("barcode value is empty")]
+    Empty,
+    #[error("barcode value contains a character code set B cannot encode")]
+    InvalidCharacter,
+}
+
+/// Bar/space widths for symbol values 0-105, the last entry is the stop pattern
+const PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const START_B: usize = 104;
+const STOP: usize = 106;
+
+/// Whether code set B can hold every character, labels print the value as plain text otherwise
+pub fn can_encode(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| (' '..='~').contains(&c))
+}
+
+/// Encodes printable ascii with code set B, returns one entry per module where true is a bar
+pub fn encode(value: &str) -> Result<Vec<bool>, Code128Error> {
+    if value.is_empty() {
+        return Err(Code128Error::Empty);
+    }
+
+    let mut symbols = vec![START_B];
+    for c in value.chars() {
+        if !(' '..='~').contains(&c) {
+            return Err(Code128Error::InvalidCharacter);
+        }
+        symbols.push(c as usize - 32);
+    }
+
+    let checksum = symbols.iter()
+        .enumerate()
+        .map(|(i, s)| if i == 0 { *s } else { i * s })
+        .sum::<usize>() % 103;
+
+    symbols.push(checksum);
+    symbols.push(STOP);
+
+    let mut modules = Vec::new();
+    for symbol in symbols {
+        // patterns alternate bar, space, bar... starting with a bar
+        for (i, width) in PATTERNS[symbol].bytes().enumerate() {
+            let is_bar = i % 2 == 0;
+            modules.extend(std::iter::repeat_n(is_bar, (width - b'0') as usize));
+        }
+    }
+
+    Ok(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(modules: &[bool]) -> String {
+        modules.iter().map(|m| if *m { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn encodes_start_data_checksum_and_stop() {
+        // start B, "A", checksum (104 + 33) % 103 = 34, stop
+        let expected = ["11010010000", "10100011000", "10001011000", "1100011101011"].concat();
+
+        assert_eq!(bits(&encode("A").unwrap()), expected);
+    }
+
+    #[test]
+    fn weights_the_checksum_by_position() {
+        // (104 + 1 * 33 + 2 * 34) % 103 = 102
+        let modules = encode("AB").unwrap();
+        let checksum = &modules[33..44];
+
+        assert_eq!(bits(checksum), "11110101110");
+    }
+
+    #[test]
+    fn every_symbol_is_eleven_modules_wide() {
+        let modules = encode("GAPP-00042").unwrap();
+
+        // start, 10 characters and the checksum plus the 13 module stop
+        assert_eq!(modules.len(), 12 * 11 + 13);
+    }
+
+    #[test]
+    fn rejects_values_code_set_b_cannot_encode() {
+        assert_eq!(encode(""), Err(Code128Error::Empty));
+        assert_eq!(encode("café"), Err(Code128Error::InvalidCharacter));
+        assert_eq!(encode("tab\there"), Err(Code128Error::InvalidCharacter));
+    }
+
+    #[test]
+    fn can_encode_matches_encode() {
+        for value in ["GAPP-00042", "A", "", "café", "tab\there"] {
+            assert_eq!(can_encode(value), encode(value).is_ok(), "{:?}", value);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod code128;
+pub mod symbols;
+pub mod sheets;
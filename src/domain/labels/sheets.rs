@@ -0,0 +1,194 @@
+use anyhow::{anyhow, Context};
+use printpdf::{BuiltinFont, Color, Greyscale, IndirectFontRef, Mm, PdfDocument, PdfLayerReference, Rect};
+use qrcode::QrCode;
+
+use super::{code128, symbols::bar_runs};
+
+
+const MM_PER_INCH: f32 = 25.4;
+const LABEL_PADDING_MM: f32 = 1.5;
+
+/// Physical layout of a label stock, dimensions are in inches as printed on the box
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LabelSheet {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub page_width: f32,
+    pub page_height: f32,
+    pub top_margin: f32,
+    pub left_margin: f32,
+    pub label_width: f32,
+    pub label_height: f32,
+    pub horizontal_pitch: f32,
+    pub vertical_pitch: f32,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl LabelSheet {
+    pub fn labels_per_page(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    /// Small labels only have room for the barcode
+    pub fn fits_qr(&self) -> bool {
+        self.label_height >= 0.75
+    }
+}
+
+pub const AVERY_SHEETS: [LabelSheet; 3] = [
+    LabelSheet {
+        id: "avery_5160",
+        name: "Avery 5160 - 1\" x 2-5/8\" (30 per sheet)",
+        page_width: 8.5,
+        page_height: 11.0,
+        top_margin: 0.5,
+        left_margin: 0.1875,
+        label_width: 2.625,
+        label_height: 1.0,
+        horizontal_pitch: 2.75,
+        vertical_pitch: 1.0,
+        columns: 3,
+        rows: 10,
+    },
+    LabelSheet {
+        id: "avery_5163",
+        name: "Avery 5163 - 2\" x 4\" (10 per sheet)",
+        page_width: 8.5,
+        page_height: 11.0,
+        top_margin: 0.5,
+        left_margin: 0.15625,
+        label_width: 4.0,
+        label_height: 2.0,
+        horizontal_pitch: 4.1875,
+        vertical_pitch: 2.0,
+        columns: 2,
+        rows: 5,
+    },
+    LabelSheet {
+        id: "avery_5167",
+        name: "Avery 5167 - 1/2\" x 1-3/4\" (80 per sheet)",
+        page_width: 8.5,
+        page_height: 11.0,
+        top_margin: 0.5,
+        left_margin: 0.28125,
+        label_width: 1.75,
+        label_height: 0.5,
+        horizontal_pitch: 2.0625,
+        vertical_pitch: 0.5,
+        columns: 4,
+        rows: 20,
+    },
+];
+
+pub fn find_sheet(id: &str) -> Option<LabelSheet> {
+    AVERY_SHEETS.iter().find(|s| s.id == id).copied()
+}
+
+/// Content printed on a single label
+#[derive(Clone, Debug)]
+pub struct LabelData {
+    pub title: String,
+    pub barcode_value: String,
+    pub link: String,
+}
+
+/// Lays labels out left to right, top to bottom and returns the pdf bytes
+pub fn render_label_sheet(sheet: &LabelSheet, labels: &[LabelData]) -> anyhow::Result<Vec<u8>> {
+    let page_width = Mm(sheet.page_width * MM_PER_INCH);
+    let page_height = Mm(sheet.page_height * MM_PER_INCH);
+
+    let (doc, page, layer) = PdfDocument::new("Asset Labels", page_width, page_height, "labels");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| anyhow!("could not load font: {:?}", e))?;
+
+    let mut layer = doc.get_page(page).get_layer(layer);
+
+    for (i, label) in labels.iter().enumerate() {
+        let slot = i % sheet.labels_per_page();
+        if i > 0 && slot == 0 {
+            let (page, new_layer) = doc.add_page(page_width, page_height, "labels");
+            layer = doc.get_page(page).get_layer(new_layer);
+        }
+
+        let column = slot % sheet.columns;
+        let row = slot / sheet.columns;
+
+        // pdf origin is the bottom left corner of the page
+        let x = (sheet.left_margin + column as f32 * sheet.horizontal_pitch) * MM_PER_INCH;
+        let top = (sheet.page_height - sheet.top_margin - row as f32 * sheet.vertical_pitch) * MM_PER_INCH;
+        let y = top - sheet.label_height * MM_PER_INCH;
+
+        draw_label(&layer, &font, sheet, label, x, y)?;
+    }
+
+    doc.save_to_bytes().map_err(|e| anyhow!("could not write pdf: {:?}", e))
+}
+
+fn draw_label(layer: &PdfLayerReference, font: &IndirectFontRef, sheet: &LabelSheet, label: &LabelData, x: f32, y: f32) -> anyhow::Result<()> {
+    let right = x + sheet.label_width * MM_PER_INCH - LABEL_PADDING_MM;
+    let height = sheet.label_height * MM_PER_INCH - LABEL_PADDING_MM * 2.0;
+    let mut x = x + LABEL_PADDING_MM;
+    let y = y + LABEL_PADDING_MM;
+
+    layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+
+    if sheet.fits_qr() {
+        let qr = QrCode::new(label.link.as_bytes()).context("could not encode qr code")?;
+        let modules = qr.width();
+        let module_mm = height / modules as f32;
+
+        for (i, color) in qr.to_colors().iter().enumerate() {
+            if *color == qrcode::Color::Dark {
+                let mx = x + (i % modules) as f32 * module_mm;
+                let my = y + height - ((i / modules) + 1) as f32 * module_mm;
+                layer.add_rect(Rect::new(Mm(mx), Mm(my), Mm(mx + module_mm), Mm(my + module_mm)));
+            }
+        }
+
+        x += height + LABEL_PADDING_MM;
+    }
+
+    let barcode_width = right - x;
+    let text_size = if sheet.label_height < 0.75 { 5.0 } else { 7.0 };
+    let text_height = text_size * 0.3528 + 0.5; // points to mm plus a little leading
+    let barcode_height = height - text_height * 2.0;
+
+    // one asset id outside code set B should not fail the whole sheet, that label keeps its qr code and text
+    if code128::can_encode(&label.barcode_value) {
+        let modules = code128::encode(&label.barcode_value).context("could not encode barcode")?;
+        let module_mm = barcode_width / modules.len() as f32;
+        let barcode_y = y + text_height * 2.0;
+
+        for (start, run) in bar_runs(&modules) {
+            let bx = x + start as f32 * module_mm;
+            layer.add_rect(Rect::new(Mm(bx), Mm(barcode_y), Mm(bx + run as f32 * module_mm), Mm(barcode_y + barcode_height)));
+        }
+    }
+
+    layer.use_text(label.barcode_value.clone(), text_size, Mm(x), Mm(y + text_height), font);
+    layer.use_text(label.title.clone(), text_size, Mm(x), Mm(y), font);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(barcode_value: &str) -> LabelData {
+        LabelData {
+            title: "Dell Latitude 5440".to_string(),
+            barcode_value: barcode_value.to_string(),
+            link: "https://example.com/asset_items/1".to_string(),
+        }
+    }
+
+    #[test]
+    fn a_value_without_a_barcode_does_not_fail_the_sheet() {
+        for sheet in AVERY_SHEETS {
+            let pdf = render_label_sheet(&sheet, &[label("GAPP-00042"), label("Café-7"), label("")]).unwrap();
+            assert!(pdf.starts_with(b"%PDF"));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::Cursor;
+
+use anyhow::Context;
+use image::{DynamicImage, GrayImage, ImageFormat, Luma};
+use qrcode::{render::svg, QrCode};
+
+use super::code128;
+
+
+const QUIET_ZONE_MODULES: usize = 10;
+const BARCODE_MODULE_PX: usize = 2;
+const BARCODE_HEIGHT_PX: usize = 80;
+const QR_MIN_PX: u32 = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LabelSymbology {
+    Barcode,
+    Qr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LabelFormat {
+    Svg,
+    Png,
+}
+
+impl LabelFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            LabelFormat::Svg => "image/svg+xml",
+            LabelFormat::Png => "image/png",
+        }
+    }
+}
+
+/// Parses route segments such as "barcode.svg" or "qr.png"
+pub fn parse_symbol_route(segment: &str) -> Option<(LabelSymbology, LabelFormat)> {
+    let (symbology, format) = segment.split_once('.')?;
+
+    let symbology = match symbology {
+        "barcode" => LabelSymbology::Barcode,
+        "qr" => LabelSymbology::Qr,
+        _ => return None,
+    };
+
+    let format = match format {
+        "svg" => LabelFormat::Svg,
+        "png" => LabelFormat::Png,
+        _ => return None,
+    };
+
+    Some((symbology, format))
+}
+
+pub fn barcode_svg(value: &str) -> anyhow::Result<String> {
+    let modules = code128::encode(value).context("could not encode barcode")?;
+    let width = (modules.len() + QUIET_ZONE_MODULES * 2) * BARCODE_MODULE_PX;
+    let text_height = 20;
+
+    let mut bars = String::new();
+    for (x, width_px) in bar_runs(&modules) {
+        bars.push_str(&format!(
+            r#"<rect x="{}" y="0" width="{}" height="{}"/>"#,
+            (x + QUIET_ZONE_MODULES) * BARCODE_MODULE_PX,
+            width_px * BARCODE_MODULE_PX,
+            BARCODE_HEIGHT_PX,
+        ));
+    }
+
+    Ok(format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="100%" height="100%" fill="#fff"/><g fill="#000">{bars}</g><text x="{cx}" y="{ty}" font-family="monospace" font-size="14" text-anchor="middle">{text}</text></svg>"##,
+        w = width,
+        h = BARCODE_HEIGHT_PX + text_height,
+        bars = bars,
+        cx = width / 2,
+        ty = BARCODE_HEIGHT_PX + 16,
+        text = escape_xml(value),
+    ))
+}
+
+pub fn barcode_png(value: &str) -> anyhow::Result<Vec<u8>> {
+    let modules = code128::encode(value).context("could not encode barcode")?;
+    let width = (modules.len() + QUIET_ZONE_MODULES * 2) * BARCODE_MODULE_PX;
+
+    let mut img = GrayImage::from_pixel(width as u32, BARCODE_HEIGHT_PX as u32, Luma([255u8]));
+    for (x, width_px) in bar_runs(&modules) {
+        let start = (x + QUIET_ZONE_MODULES) * BARCODE_MODULE_PX;
+        for px in start..start + width_px * BARCODE_MODULE_PX {
+            for py in 0..BARCODE_HEIGHT_PX {
+                img.put_pixel(px as u32, py as u32, Luma([0u8]));
+            }
+        }
+    }
+
+    encode_png(DynamicImage::ImageLuma8(img))
+}
+
+pub fn qr_svg(data: &str) -> anyhow::Result<String> {
+    let code = QrCode::new(data.as_bytes()).context("could not encode qr code")?;
+
+    Ok(code.render::<svg::Color>()
+        .min_dimensions(QR_MIN_PX, QR_MIN_PX)
+        .build())
+}
+
+pub fn qr_png(data: &str) -> anyhow::Result<Vec<u8>> {
+    let code = QrCode::new(data.as_bytes()).context("could not encode qr code")?;
+
+    let img = code.render::<Luma<u8>>()
+        .min_dimensions(QR_MIN_PX, QR_MIN_PX)
+        .build();
+
+    encode_png(DynamicImage::ImageLuma8(img))
+}
+
+/// Collapses consecutive bar modules into (start module, width in modules)
+pub fn bar_runs(modules: &[bool]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for (i, is_bar) in modules.iter().enumerate() {
+        match (is_bar, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                runs.push((s, i - s));
+                start = None;
+            },
+            _ => {},
+        }
+    }
+
+    if let Some(s) = start {
+        runs.push((s, modules.len() - s));
+    }
+
+    runs
+}
+
+fn encode_png(img: DynamicImage) -> anyhow::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    img.write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .context("could not encode png")?;
+
+    Ok(data)
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
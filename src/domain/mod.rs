@@ -1,4 +1,8 @@
-
 pub mod identityaccess;
 pub mod filesystem;
-pub mod crud;
\ No newline at end of file
+pub mod crud;
+pub mod labels;
+pub mod audits;
+pub mod transfers;
+pub mod search;
+pub mod saved_views;
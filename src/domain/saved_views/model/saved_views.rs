@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::crud::model::asset_items::AssetItemFilter;
+
+
+/// Asset item table columns a view can hide, in table order
+pub const ASSET_ITEM_COLUMNS: [(&str, &str); 6] = [
+    ("asset_id", "Asset ID"),
+    ("name", "Name"),
+    ("description", "Description"),
+    ("brand", "Brand"),
+    ("model", "Model"),
+    ("serial_number", "Serial #"),
+];
+
+/// The asset item columns to show, carried in the url as a comma separated list.
+/// Unknown names are dropped and an empty selection shows every column.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ColumnSelection(Vec<String>);
+
+impl ColumnSelection {
+    pub fn parse(value: Option<&str>) -> Self {
+        let selected: Vec<&str> = value
+            .map(|v| v.split(',').map(str::trim).collect())
+            .unwrap_or_default();
+
+        let columns: Vec<String> = ASSET_ITEM_COLUMNS
+            .iter()
+            .filter(|(name, _)| selected.contains(name))
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        match columns.len() == ASSET_ITEM_COLUMNS.len() {
+            true => Self::default(),
+            false => Self(columns),
+        }
+    }
+
+    pub fn shows(&self, column: &str) -> bool {
+        self.0.is_empty() || self.0.iter().any(|c| c == column)
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn to_param(&self) -> String {
+        self.0.join(",")
+    }
+
+    pub fn into_inner(self) -> Vec<String> {
+        self.0
+    }
+}
+
+impl From<Vec<String>> for ColumnSelection {
+    fn from(value: Vec<String>) -> Self {
+        Self::parse(Some(&value.join(",")))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedView {
+    pub id: i32,
+    pub user_id: Uuid,
+    pub owner_name: String,
+    pub name: String,
+    pub filter: AssetItemFilter,
+    pub columns: ColumnSelection,
+    /// role the view is shared with, None keeps it to its owner
+    pub shared_role: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SavedView {
+    /// The asset items page with the filter and columns in the query string,
+    /// so the link shows the same items when pasted into an email
+    pub fn url(&self) -> String {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if let Some(search) = &self.filter.search {
+            query.append_pair("search", search);
+        }
+        if let Some(category_id) = self.filter.category_id {
+            query.append_pair("category_id", &category_id.to_string());
+        }
+        if let Some(school) = &self.filter.school {
+            query.append_pair("school", school);
+        }
+        if let Some(status) = &self.filter.status {
+            query.append_pair("status", status);
+        }
+        if let (Some(sort), Some(order)) = (&self.filter.sort, &self.filter.order) {
+            query.append_pair("sort", sort);
+            query.append_pair("order", order);
+        }
+        if !self.columns.is_all() {
+            query.append_pair("columns", &self.columns.to_param());
+        }
+
+        match query.finish() {
+            query if query.is_empty() => "/asset_items".to_string(),
+            query => format!("/asset_items?{}", query),
+        }
+    }
+
+    pub fn is_owned_by(&self, user_id: &Uuid) -> bool {
+        self.user_id == *user_id
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewSavedView {
+    pub user_id: Uuid,
+    pub name: String,
+    pub filter: AssetItemFilter,
+    pub columns: ColumnSelection,
+    pub shared_role_id: Option<i32>,
+}
@@ -0,0 +1,44 @@
+use std::future::Future;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::model::saved_views::{NewSavedView, SavedView};
+
+
+#[derive(Error, Debug)]
+pub enum SavedViewRepositoryError {
+    #[error("a view with this name already exists")]
+    Conflict,
+    #[error("related item in foreign table does not exist")]
+    Reference,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+pub trait SavedViewRepository: Send + Sync + Clone + 'static {
+
+    /// The user's own views followed by the ones shared with their role
+    fn get_saved_views(
+        &self,
+        user_id: Uuid,
+        role: String,
+    ) -> impl Future<Output = Result<Vec<SavedView>, SavedViewRepositoryError>> + Send;
+
+    fn get_saved_view_by_id(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<SavedView>, SavedViewRepositoryError>> + Send;
+
+    fn add_saved_view(
+        &self,
+        new_saved_view: NewSavedView,
+    ) -> impl Future<Output = Result<SavedView, SavedViewRepositoryError>> + Send;
+
+    /// Only the owner can delete a view, None when it does not exist or belongs to someone else
+    fn delete_saved_view(
+        &self,
+        id: i32,
+        user_id: Uuid,
+    ) -> impl Future<Output = Result<Option<i32>, SavedViewRepositoryError>> + Send;
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+
+pub const OWNER_KIND_ASSET_TYPE: &str = "asset_type";
+
+/// Turns what was typed into a prefix tsquery, "dell lat" becomes "dell:* & lat:*" so results show up while typing.
+/// Only letters and digits are kept, none of the tsquery syntax can get through.
+pub fn prefix_tsquery(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect();
+
+    match terms.is_empty() {
+        true => None,
+        false => Some(terms.join(" & ")),
+    }
+}
+
+/// Makes what was typed match literally inside an ILIKE pattern, used with `ESCAPE '\'`
+pub fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// One row of the global search, rendered the same way whichever kind of record it points at
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SearchHit {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub url: String,
+    /// tsquery rank plus trigram similarity, only comparable within one kind of record
+    pub rank: f32,
+}
+
+/// A school, or a room in it, that asset items are recorded at
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LocationMatch {
+    pub school: String,
+    pub room: Option<String>,
+    pub item_count: i64,
+    pub rank: f32,
+}
+
+impl From<LocationMatch> for SearchHit {
+    fn from(location: LocationMatch) -> Self {
+        let (title, search) = match &location.room {
+            Some(room) => (format!("{} / {}", location.school, room), room.as_str()),
+            None => (location.school.clone(), location.school.as_str()),
+        };
+        let url = format!("/asset_items?search={}", url::form_urlencoded::byte_serialize(search.as_bytes()).collect::<String>());
+
+        Self {
+            title,
+            subtitle: Some(format!("{} items", location.item_count)),
+            url,
+            rank: location.rank,
+        }
+    }
+}
+
+/// Matches of the global search grouped by what they point at, each group best first
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SearchResults {
+    pub asset_types: Vec<SearchHit>,
+    pub asset_items: Vec<SearchHit>,
+    pub users: Vec<SearchHit>,
+    pub locations: Vec<SearchHit>,
+    pub documents: Vec<DocumentMatch>,
+}
+
+impl SearchResults {
+    pub fn is_empty(&self) -> bool {
+        self.asset_types.is_empty() && self.asset_items.is_empty() && self.users.is_empty()
+            && self.locations.is_empty() && self.documents.is_empty()
+    }
+
+    /// The groups that found something, in the order they are shown
+    pub fn groups(&self) -> Vec<(&'static str, &[SearchHit])> {
+        [
+            ("Asset Items", self.asset_items.as_slice()),
+            ("Asset Types", self.asset_types.as_slice()),
+            ("Locations", self.locations.as_slice()),
+            ("Users", self.users.as_slice()),
+        ]
+        .into_iter()
+        .filter(|(_, hits)| !hits.is_empty())
+        .collect()
+    }
+}
+
+/// A document whose text or filename matched the search, once per asset it is attached to
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DocumentMatch {
+    pub document_id: i32,
+    pub filename: String,
+    pub url: String,
+    pub owner_kind: String,
+    pub owner_id: i32,
+    pub owner_name: String,
+    /// excerpt around the matched words, which are wrapped in <mark> by ts_headline
+    pub snippet: String,
+    pub rank: f32,
+}
+
+impl DocumentMatch {
+    pub fn owner_url(&self) -> String {
+        match self.owner_kind.as_str() {
+            OWNER_KIND_ASSET_TYPE => format!("/asset_types/{}", self.owner_id),
+            _ => format!("/asset_items/{}", self.owner_id),
+        }
+    }
+
+    pub fn owner_kind_display(&self) -> &str {
+        match self.owner_kind.as_str() {
+            OWNER_KIND_ASSET_TYPE => "Asset Type",
+            _ => "Asset Item",
+        }
+    }
+
+    /// The snippet is document text, so everything but the highlight markers is escaped before it is rendered
+    pub fn snippet_html(&self) -> String {
+        escape_html(&self.snippet)
+            .replace("&lt;mark&gt;", "<mark>")
+            .replace("&lt;/mark&gt;", "</mark>")
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#x27;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_term_becomes_a_lowercase_prefix() {
+        assert_eq!(prefix_tsquery("Dell Lat").as_deref(), Some("dell:* & lat:*"));
+        assert_eq!(prefix_tsquery("epson").as_deref(), Some("epson:*"));
+    }
+
+    #[test]
+    fn tsquery_syntax_is_stripped() {
+        assert_eq!(prefix_tsquery("a & !b | (c:*)").as_deref(), Some("a:* & b:* & c:*"));
+        assert_eq!(prefix_tsquery("GAPP-00042").as_deref(), Some("gapp:* & 00042:*"));
+        assert_eq!(prefix_tsquery("o'neil").as_deref(), Some("o:* & neil:*"));
+    }
+
+    #[test]
+    fn keeps_non_ascii_letters() {
+        assert_eq!(prefix_tsquery("Café").as_deref(), Some("café:*"));
+    }
+
+    #[test]
+    fn like_wildcards_are_escaped() {
+        assert_eq!(escape_like("100%"), "100\\%");
+        assert_eq!(escape_like("hp_4"), "hp\\_4");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+        assert_eq!(escape_like("Dell Latitude"), "Dell Latitude");
+    }
+
+    #[test]
+    fn nothing_searchable_is_none() {
+        assert_eq!(prefix_tsquery(""), None);
+        assert_eq!(prefix_tsquery(" &|!:*() "), None);
+    }
+}
@@ -0,0 +1,54 @@
+use std::future::Future;
+
+use thiserror::Error;
+
+use super::model::search::{DocumentMatch, LocationMatch, SearchHit};
+
+
+#[derive(Error, Debug)]
+pub enum SearchRepositoryError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+/// The global search takes the query twice, as a prefix tsquery (see prefix_tsquery) for ranked matches
+/// and as typed for trigram matching, which finds names with a typo in them
+pub trait SearchRepository: Send + Sync + Clone + 'static {
+
+    fn search_asset_types(
+        &self,
+        tsquery: String,
+        text: String,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<SearchHit>, SearchRepositoryError>> + Send;
+
+    fn search_asset_items(
+        &self,
+        tsquery: String,
+        text: String,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<SearchHit>, SearchRepositoryError>> + Send;
+
+    fn search_users(
+        &self,
+        tsquery: String,
+        text: String,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<SearchHit>, SearchRepositoryError>> + Send;
+
+    /// Schools and rooms as recorded on asset items
+    fn search_locations(
+        &self,
+        tsquery: String,
+        text: String,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<LocationMatch>, SearchRepositoryError>> + Send;
+
+    /// Full-text search over document filenames and extracted text, best matches first.
+    /// The limit counts documents, each is returned once per asset it is attached to.
+    fn search_documents(
+        &self,
+        query: String,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<DocumentMatch>, SearchRepositoryError>> + Send;
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::crud::model::{asset_items::AssetItem, history::FieldChange};
+
+
+pub const TRANSFER_STATUS_PENDING: &str = "pending";
+pub const TRANSFER_STATUS_RECEIVED: &str = "received";
+
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Transfer {
+    pub id: i32,
+    pub source_school: String,
+    pub destination_school: String,
+    pub destination_room: Option<String>,
+    pub notes: Option<String>,
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub created_by_name: Option<String>,
+    pub received_by: Option<Uuid>,
+    pub received_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub item_count: i64,
+    pub scanned_count: i64,
+}
+
+impl Transfer {
+    pub fn is_pending(&self) -> bool {
+        self.status == TRANSFER_STATUS_PENDING
+    }
+
+    pub fn is_received(&self) -> bool {
+        self.status == TRANSFER_STATUS_RECEIVED
+    }
+
+    pub fn destination_display(&self) -> String {
+        match &self.destination_room {
+            Some(room) => format!("{} / {}", self.destination_school, room),
+            None => self.destination_school.clone(),
+        }
+    }
+
+    pub fn created_at_display(&self) -> String {
+        self.created_at.format("%Y-%m-%d %H:%M").to_string()
+    }
+
+    pub fn received_at_display(&self) -> String {
+        self.received_at.map(|d| d.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+    }
+
+    /// Every item has been scanned in by the receiving school
+    pub fn is_fully_scanned(&self) -> bool {
+        self.item_count > 0 && self.scanned_count == self.item_count
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewTransfer {
+    pub source_school: String,
+    pub destination_school: String,
+    pub destination_room: Option<String>,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+    pub asset_items: Vec<AssetItem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TransferItem {
+    pub transfer_id: i32,
+    pub asset_item_id: i32,
+    pub asset_id: Option<String>,
+    pub name: Option<String>,
+    pub serial_number: Option<String>,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    pub school: Option<String>,
+    pub room: Option<String>,
+    pub from_room: Option<String>,
+    pub scanned_at: Option<DateTime<Utc>>,
+}
+
+impl TransferItem {
+    pub fn is_scanned(&self) -> bool {
+        self.scanned_at.is_some()
+    }
+
+    pub fn asset_type_display(&self) -> String {
+        match (&self.brand, &self.model) {
+            (Some(brand), Some(model)) => format!("{} {}", brand, model),
+            _ => String::new(),
+        }
+    }
+
+    /// School and room changes written to the item history when the transfer is received
+    pub fn received_changes(&self, transfer: &Transfer) -> Vec<FieldChange> {
+        [
+            FieldChange::between("School", self.school.as_deref(), Some(transfer.destination_school.as_str())),
+            FieldChange::between("Room", self.room.as_deref(), transfer.destination_room.as_deref()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Why a selected item can not be sent
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransferItemProblem {
+    NotAtSource,
+    AlreadyPending,
+    Retired,
+}
+
+impl TransferItemProblem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferItemProblem::NotAtSource => "is not recorded at the sending school",
+            TransferItemProblem::AlreadyPending => "is already part of a pending transfer",
+            TransferItemProblem::Retired => "is retired",
+        }
+    }
+}
+
+/// Checks every selected item can leave the source school, returns the item label and problem for each that can not
+pub fn validate_transfer_items(source_school: &str, asset_items: &[AssetItem], pending_ids: &[i32]) -> Vec<(String, TransferItemProblem)> {
+    asset_items.iter()
+        .filter_map(|asset_item| {
+            let problem = if asset_item.school.as_deref() != Some(source_school) {
+                TransferItemProblem::NotAtSource
+            } else if pending_ids.contains(&asset_item.id) {
+                TransferItemProblem::AlreadyPending
+            } else if asset_item.is_retired() {
+                TransferItemProblem::Retired
+            } else {
+                return None;
+            };
+
+            let label = asset_item.asset_id.clone()
+                .or(asset_item.serial_number.clone())
+                .unwrap_or(format!("#{}", asset_item.id));
+
+            Some((label, problem))
+        })
+        .collect()
+}
+
+/// Pending transfers a school is sending or waiting to receive, shown on its dashboard
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SchoolTransfers {
+    pub school: String,
+    pub incoming: Vec<Transfer>,
+    pub outgoing: Vec<Transfer>,
+}
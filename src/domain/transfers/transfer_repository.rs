@@ -0,0 +1,86 @@
+use std::future::Future;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::crud::model::asset_items::AssetItem;
+
+use super::model::transfers::{NewTransfer, Transfer, TransferItem};
+
+
+#[derive(Error, Debug)]
+pub enum TransferRepositoryError {
+    #[error("related item in foreign table does not exist")]
+    Reference,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+pub trait TransferRepository: Send + Sync + Clone + 'static {
+
+    /// Stores the transfer and its items in one transaction
+    fn add_transfer(
+        &self,
+        new_transfer: NewTransfer,
+    ) -> impl Future<Output = Result<Transfer, TransferRepositoryError>> + Send;
+
+    fn get_transfers(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Transfer>, TransferRepositoryError>> + Send;
+
+    fn get_transfer_by_id(
+        &self,
+        id: i32,
+    ) -> impl Future<Output = Result<Option<Transfer>, TransferRepositoryError>> + Send;
+
+    fn get_pending_transfers_for_school(
+        &self,
+        school: String,
+    ) -> impl Future<Output = Result<Vec<Transfer>, TransferRepositoryError>> + Send;
+
+    fn get_transfer_items(
+        &self,
+        transfer_id: i32,
+    ) -> impl Future<Output = Result<Vec<TransferItem>, TransferRepositoryError>> + Send;
+
+    fn get_transfer_candidates(
+        &self,
+        asset_item_ids: &[i32],
+    ) -> impl Future<Output = Result<Vec<AssetItem>, TransferRepositoryError>> + Send;
+
+    fn get_school_asset_items(
+        &self,
+        school: String,
+    ) -> impl Future<Output = Result<Vec<AssetItem>, TransferRepositoryError>> + Send;
+
+    /// Ids out of the given items that already belong to a pending transfer
+    fn get_pending_asset_item_ids(
+        &self,
+        asset_item_ids: &[i32],
+    ) -> impl Future<Output = Result<Vec<i32>, TransferRepositoryError>> + Send;
+
+    fn get_schools(
+        &self,
+    ) -> impl Future<Output = Result<Vec<String>, TransferRepositoryError>> + Send;
+
+    /// Returns false when the item is not part of the transfer
+    fn scan_transfer_item(
+        &self,
+        transfer_id: i32,
+        asset_item_id: i32,
+    ) -> impl Future<Output = Result<bool, TransferRepositoryError>> + Send;
+
+    /// Moves every item to the destination, records their history and closes the transfer in one transaction,
+    /// returns None when the transfer is no longer pending
+    fn receive_transfer(
+        &self,
+        transfer_id: i32,
+        received_by: Uuid,
+    ) -> impl Future<Output = Result<Option<Transfer>, TransferRepositoryError>> + Send;
+
+    /// Returns None when the transfer is no longer pending
+    fn cancel_transfer(
+        &self,
+        transfer_id: i32,
+    ) -> impl Future<Output = Result<Option<Transfer>, TransferRepositoryError>> + Send;
+}
@@ -1,14 +1,14 @@
 use anyhow::Context;
-use tempfile::NamedTempFile;
 use std::{fs, path::{Path, PathBuf}};
 
-use crate::domain::filesystem::{models::{ContentType, ExtensionType, FilePayload, Filename, NewDocumentAttachment, NewImageAttachment, MIME_LOOKUP}, persistence_service::{PersistenceError, PersistenceService}};
+use crate::domain::filesystem::{models::{FilePayload, NewDocumentAttachment, NewImageAttachment}, persistence_service::{hash_path, prepare_document_file, prepare_image_files, FileRequest, PersistenceError, PersistenceService, StorageFolder, StoredFile}};
 
 
 #[derive(Debug, Clone)]
 pub struct LocalPersistenceService {
     pub images_path: PathBuf,
     pub docs_path: PathBuf,
+    quarantine_path: PathBuf,
     route_path: String,
 }
 
@@ -17,6 +17,7 @@ impl LocalPersistenceService {
         let base = PathBuf::from(".");
         let images_path = base.join(&serve_path).join("images");
         let docs_path = base.join(&serve_path).join("documents");
+        let quarantine_path = base.join(&serve_path).join("quarantine");
 
         fs::create_dir_all(images_path.clone()).context("could not create image directory")?;
         fs::create_dir_all(docs_path.clone()).context("could not create documents directory")?;
@@ -24,62 +25,102 @@ impl LocalPersistenceService {
         Ok(Self {
             images_path,
             docs_path,
+            quarantine_path,
             route_path: route_path.to_string(),
         })
     }
+
+    pub fn folder_path(&self, folder: StorageFolder) -> &Path {
+        match folder {
+            StorageFolder::Images => &self.images_path,
+            StorageFolder::Documents => &self.docs_path,
+        }
+    }
 }
 
 impl PersistenceService for LocalPersistenceService {
     async fn persist_image_file(&self, payload: FilePayload) -> Result<NewImageAttachment, PersistenceError> {
-        let ext = MIME_LOOKUP.get(payload.content_type.as_str()).ok_or(PersistenceError::ExtNotSupported)?;
-        if ext.ext_type() != ExtensionType::Image {
-            return Err(PersistenceError::ExtNotSupported);
+        let (attachment, objects) = prepare_image_files(payload, &self.route_path).await?;
+
+        for object in objects {
+            let filepath = self.images_path.join(&object.name);
+            tokio::fs::write(&filepath, &object.data).await.context(format!("could not write image to {:?}", filepath))?;
         }
 
+        Ok(attachment)
+    }
 
-        let persisted_filename = format!("{}.{}", payload.hash, ext);
-        let filepath = self.images_path.join(&persisted_filename);
-        let url = format!("{}/images/{}", self.route_path, &persisted_filename);
+    async fn persist_document_file(&self, payload: FilePayload) -> Result<NewDocumentAttachment, PersistenceError> {
+        let (attachment, name, temp_file) = prepare_document_file(payload, &self.route_path).await?;
 
-        payload.temp_file.persist(&filepath).context(format!("could not perist tempfile to {:?}", filepath))?;
+        let filepath = self.docs_path.join(&name);
+        temp_file.persist(&filepath).context(format!("could not perist tempfile to {:?}", filepath))?;
 
-        Ok(NewImageAttachment {
-            filename: Filename::new(payload.filename),
-            url: url,
-            content_type: ContentType::new(payload.content_type),
-            hash: payload.hash,
-        })
+        Ok(attachment)
     }
 
-    async fn persist_document_file(&self, payload: FilePayload) -> Result<NewDocumentAttachment, PersistenceError> {
-        let ext = MIME_LOOKUP.get(payload.content_type.as_str()).ok_or(PersistenceError::ExtNotSupported)?;
-        if ext.ext_type() == ExtensionType::Image {
-            return Err(PersistenceError::ExtNotSupported);
+    async fn retrieve_file(&self, folder: StorageFolder, name: &str, _request: &FileRequest) -> Result<StoredFile, PersistenceError> {
+        let filepath = self.folder_path(folder).join(name);
+        if !tokio::fs::try_exists(&filepath).await? {
+            return Err(PersistenceError::NotFound);
         }
 
-        let persisted_filename = format!("{}.{}", payload.hash, ext);
-        let filepath = self.docs_path.join(&persisted_filename);
-        let url = format!("{}/documents/{}", self.route_path, &persisted_filename);
+        Ok(StoredFile::Local(filepath))
+    }
 
-        payload.temp_file.persist(&filepath).context(format!("could not perist tempfile to {:?}", filepath))?;
+    async fn read_file(&self, folder: StorageFolder, name: &str) -> Result<Vec<u8>, PersistenceError> {
+        let filepath = self.folder_path(folder).join(name);
 
-        Ok(NewDocumentAttachment {
-            filename: Filename::new(payload.filename),
-            url: url,
-            content_type: ContentType::new(payload.content_type),
-            hash: payload.hash,
-        })
+        match tokio::fs::read(&filepath).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(PersistenceError::NotFound),
+            Err(e) => Err(e.into()),
+        }
     }
 
-    async fn hash_file(&self, file: NamedTempFile)-> Result<(NamedTempFile, String), PersistenceError> {
-        let (f, h) = tokio::task::spawn_blocking(move|| {
-            let mut hasher = blake3::Hasher::new();
-            hasher.update_mmap_rayon(file.path()).unwrap();
+    async fn delete_file(&self, folder: StorageFolder, name: &str) -> Result<(), PersistenceError> {
+        let filepath = self.folder_path(folder).join(name);
 
-            (file, hasher.finalize().to_string())
-        })
-        .await?;
+        match tokio::fs::remove_file(&filepath).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
 
-        Ok((f, h))
+    async fn list_files(&self, folder: StorageFolder) -> Result<Vec<String>, PersistenceError> {
+        let mut entries = tokio::fs::read_dir(self.folder_path(folder)).await?;
+
+        let mut names = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_file() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+
+        Ok(names)
     }
-}
\ No newline at end of file
+
+    async fn hash_stored_file(&self, folder: StorageFolder, name: &str) -> Result<String, PersistenceError> {
+        let filepath = self.folder_path(folder).join(name);
+        if !tokio::fs::try_exists(&filepath).await? {
+            return Err(PersistenceError::NotFound);
+        }
+
+        let hash = tokio::task::spawn_blocking(move || hash_path(&filepath)).await??;
+
+        Ok(hash)
+    }
+
+    async fn quarantine_file(&self, folder: StorageFolder, name: &str) -> Result<(), PersistenceError> {
+        let quarantine_folder = self.quarantine_path.join(folder.to_string());
+        tokio::fs::create_dir_all(&quarantine_folder).await.context("could not create quarantine directory")?;
+
+        let filepath = self.folder_path(folder).join(name);
+        tokio::fs::rename(&filepath, quarantine_folder.join(name))
+            .await
+            .context(format!("could not quarantine {:?}", filepath))?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,12 @@
+pub mod postgres;
 pub mod postgres_user_repository;
 pub mod google_oauth_service;
 pub mod postgres_attachment_repository;
 pub mod local_persistence_service;
-pub mod postgres_crud_repository;
\ No newline at end of file
+pub mod postgres_crud_repository;
+pub mod postgres_audit_repository;
+pub mod postgres_transfer_repository;
+pub mod postgres_search_repository;
+pub mod postgres_saved_view_repository;
+pub mod s3_persistence_service;
+pub mod storage_persistence_service;
@@ -0,0 +1,47 @@
+use sqlx::{postgres::{PgConnectOptions, PgPoolOptions, PgSslMode}, PgPool};
+
+use crate::settings::DatabaseConfig;
+
+
+const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "23505";
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+/// Pool for a repository, connections are opened on first use
+pub fn connect_lazy(config: &DatabaseConfig) -> PgPool {
+    let ssl_mode = if config.require_ssl == true { PgSslMode::Require } else { PgSslMode::Prefer };
+    let pg_connect_options = PgConnectOptions::new()
+        .host(&config.host)
+        .username(&config.username)
+        .password(&config.password)
+        .port(config.port)
+        .ssl_mode(ssl_mode)
+        .database(&config.database_name);
+
+    PgPoolOptions::new().connect_lazy_with(pg_connect_options)
+}
+
+pub fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(db_err) = err {
+        if let Some(code) = db_err.code() {
+            if code == UNIQUE_CONSTRAINT_VIOLATION_CODE {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+// an insert or update operation has attempted to create a foreign key value in a child table
+// that does not have a matching primary key value in the parent table
+pub fn is_foreign_key_violation(err: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(db_err) = err {
+        if let Some(code) = db_err.code() {
+            if code == FOREIGN_KEY_VIOLATION {
+                return true;
+            }
+        }
+    }
+
+    false
+}
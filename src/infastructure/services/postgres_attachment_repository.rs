@@ -1,27 +1,11 @@
-use std::io::Read;
 
 use anyhow::Context;
-use axum_typed_multipart::FieldData;
-use sqlx::{postgres::{PgConnectOptions, PgPoolOptions, PgSslMode}, PgPool};
-use tempfile::NamedTempFile;
+use sqlx::{types::Json, PgPool};
+use uuid::Uuid;
 
-use crate::{domain::filesystem::{attachment_repository::{AttachmentRepository, AttachmentRepositoryError}, models::{ImageAttachment, NewImageAttachment, DocumentAttachment, NewDocumentAttachment, Filename, ContentType}}, settings::DatabaseConfig};
+use crate::{domain::filesystem::{attachment_repository::{AttachmentRepository, AttachmentRepositoryError}, models::{ImageAttachment, ImageVariant, NewImageAttachment, DocumentAttachment, NewDocumentAttachment, Filename, ContentType, UnreferencedAttachment, UnreferencedAttachments}, storage_check::{AttachmentFiles, StorageCheck, StorageCheckResult, StorageIssue}}, settings::DatabaseConfig, infastructure::services::postgres::{self, is_unique_constraint_violation}};
 
 
-const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "23505";
-
-fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
-    if let sqlx::Error::Database(db_err) = err {
-        if let Some(code) = db_err.code() {
-            if code == UNIQUE_CONSTRAINT_VIOLATION_CODE {
-                return true;
-            }
-        }
-    }
-
-    false
-}
-
 #[derive(Debug, Clone)]
 pub struct PostgresAttachmentRepository {
     pool: PgPool,
@@ -29,18 +13,7 @@ pub struct PostgresAttachmentRepository {
 
 impl PostgresAttachmentRepository {
     pub fn new(config: &DatabaseConfig) -> anyhow::Result<Self> {
-        let ssl_mode = if config.require_ssl == true { PgSslMode::Require } else { PgSslMode::Prefer };
-        let pg_connect_options = PgConnectOptions::new()
-            .host(&config.host)
-            .username(&config.username)
-            .password(&config.password)
-            .port(config.port)
-            .ssl_mode(ssl_mode)
-            .database(&config.database_name);
-
-        let pg_pool = PgPoolOptions::new().connect_lazy_with(pg_connect_options);
-
-        Ok(Self { pool: pg_pool })
+        Ok(Self { pool: postgres::connect_lazy(config) })
     }
 }
 
@@ -49,7 +22,8 @@ impl AttachmentRepository for PostgresAttachmentRepository {
         let attachment = sqlx::query_as!(
             ImageAttachment,
             r#"
-            SELECT id, filename as "filename: Filename", hash, content_type as "content_type: ContentType", url, created_at
+            SELECT id, filename as "filename: Filename", hash, content_type as "content_type: ContentType", url,
+                variants as "variants: Json<Vec<ImageVariant>>", created_at
             FROM image_attachments
             WHERE $1 = hash
             "#,
@@ -83,14 +57,17 @@ impl AttachmentRepository for PostgresAttachmentRepository {
         let attachment = sqlx::query_as!(
             ImageAttachment,
             r#"
-            INSERT INTO image_attachments (hash, filename, content_type, url)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id, filename as "filename: Filename", hash, content_type as "content_type: ContentType", url, created_at
+            INSERT INTO image_attachments (hash, filename, content_type, url, variants, checksum)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, filename as "filename: Filename", hash, content_type as "content_type: ContentType", url,
+                variants as "variants: Json<Vec<ImageVariant>>", created_at
             "#,
             new_attachment.hash,
             new_attachment.filename.to_string(),
             new_attachment.content_type.to_string(),
             new_attachment.url,
+            Json(&new_attachment.variants) as _,
+            new_attachment.checksum,
         )
         .fetch_one(&self.pool)
         .await
@@ -106,14 +83,15 @@ impl AttachmentRepository for PostgresAttachmentRepository {
         let attachment = sqlx::query_as!(
             DocumentAttachment,
             r#"
-            INSERT INTO document_attachments (hash, filename, content_type, url)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO document_attachments (hash, filename, content_type, url, checksum)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING id, filename as "filename: Filename", hash, content_type as "content_type: ContentType", url, created_at
             "#,
             new_attachment.hash,
             new_attachment.filename.to_string(),
             new_attachment.content_type.to_string(),
             new_attachment.url,
+            new_attachment.checksum,
         )
         .fetch_one(&self.pool)
         .await
@@ -124,7 +102,342 @@ impl AttachmentRepository for PostgresAttachmentRepository {
 
         Ok(attachment)
     }
-}
 
+    async fn mark_unreferenced_attachments(&self)-> Result<u64, AttachmentRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        sqlx::query!(
+            r#"
+            UPDATE image_attachments
+            SET unreferenced_since = NULL
+            WHERE unreferenced_since IS NOT NULL
+                AND id IN (SELECT id FROM referenced_image_attachments)
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not clear image attachment marks")?;
+
+        sqlx::query!(
+            r#"
+            UPDATE document_attachments
+            SET unreferenced_since = NULL
+            WHERE unreferenced_since IS NOT NULL
+                AND id IN (SELECT id FROM referenced_document_attachments)
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not clear document attachment marks")?;
+
+        let images = sqlx::query!(
+            r#"
+            UPDATE image_attachments
+            SET unreferenced_since = NOW()
+            WHERE unreferenced_since IS NULL
+                AND id NOT IN (SELECT id FROM referenced_image_attachments)
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not mark image attachments")?;
+
+        let documents = sqlx::query!(
+            r#"
+            UPDATE document_attachments
+            SET unreferenced_since = NOW()
+            WHERE unreferenced_since IS NULL
+                AND id NOT IN (SELECT id FROM referenced_document_attachments)
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not mark document attachments")?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(images.rows_affected() + documents.rows_affected())
+    }
+
+    async fn get_collectable_attachments(&self, grace_period_hours: i32)-> Result<UnreferencedAttachments, AttachmentRepositoryError> {
+        let images = sqlx::query_as!(
+            UnreferencedAttachment,
+            r#"
+            SELECT id, filename as "filename: Filename", url, variants as "variants: Json<Vec<ImageVariant>>",
+                created_at, unreferenced_since as "unreferenced_since!"
+            FROM image_attachments
+            WHERE unreferenced_since < NOW() - make_interval(hours => $1)
+                AND id NOT IN (SELECT id FROM referenced_image_attachments)
+            ORDER BY unreferenced_since
+            "#,
+            grace_period_hours
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve collectable image attachments")?;
+
+        let documents = sqlx::query_as!(
+            UnreferencedAttachment,
+            r#"
+            SELECT id, filename as "filename: Filename", url, '[]'::jsonb as "variants!: Json<Vec<ImageVariant>>",
+                created_at, unreferenced_since as "unreferenced_since!"
+            FROM document_attachments
+            WHERE unreferenced_since < NOW() - make_interval(hours => $1)
+                AND id NOT IN (SELECT id FROM referenced_document_attachments)
+            ORDER BY unreferenced_since
+            "#,
+            grace_period_hours
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve collectable document attachments")?;
+
+        Ok(UnreferencedAttachments { images, documents })
+    }
+
+    async fn delete_collectable_attachments(&self, grace_period_hours: i32)-> Result<UnreferencedAttachments, AttachmentRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        // references are checked again here, an upload of the same file reuses the row and may have linked it since the mark
+        let images = sqlx::query_as!(
+            UnreferencedAttachment,
+            r#"
+            DELETE FROM image_attachments
+            WHERE unreferenced_since < NOW() - make_interval(hours => $1)
+                AND id NOT IN (SELECT id FROM referenced_image_attachments)
+            RETURNING id, filename as "filename: Filename", url, variants as "variants: Json<Vec<ImageVariant>>",
+                created_at, unreferenced_since as "unreferenced_since!"
+            "#,
+            grace_period_hours
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("could not delete collectable image attachments")?;
+
+        let documents = sqlx::query_as!(
+            UnreferencedAttachment,
+            r#"
+            DELETE FROM document_attachments
+            WHERE unreferenced_since < NOW() - make_interval(hours => $1)
+                AND id NOT IN (SELECT id FROM referenced_document_attachments)
+            RETURNING id, filename as "filename: Filename", url, '[]'::jsonb as "variants!: Json<Vec<ImageVariant>>",
+                created_at, unreferenced_since as "unreferenced_since!"
+            "#,
+            grace_period_hours
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("could not delete collectable document attachments")?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(UnreferencedAttachments { images, documents })
+    }
+
+    async fn get_image_attachment_files(&self)-> Result<Vec<AttachmentFiles>, AttachmentRepositoryError> {
+        let files = sqlx::query_as!(
+            AttachmentFiles,
+            r#"
+            SELECT id, hash, content_type as "content_type: ContentType", url, checksum,
+                variants as "variants: Json<Vec<ImageVariant>>"
+            FROM image_attachments
+            ORDER BY id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve image attachment files from database")?;
+
+        Ok(files)
+    }
+
+    async fn get_document_attachment_files(&self)-> Result<Vec<AttachmentFiles>, AttachmentRepositoryError> {
+        let files = sqlx::query_as!(
+            AttachmentFiles,
+            r#"
+            SELECT id, hash, content_type as "content_type: ContentType", url, checksum,
+                '[]'::jsonb as "variants!: Json<Vec<ImageVariant>>"
+            FROM document_attachments
+            ORDER BY id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve document attachment files from database")?;
+
+        Ok(files)
+    }
+
+    async fn add_storage_check(&self, quarantine: bool, started_by: Option<Uuid>)-> Result<StorageCheck, AttachmentRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        // a check that never finished was cut off by a restart, it would block new checks for good
+        sqlx::query!(
+            r#"
+            UPDATE storage_checks
+            SET error = 'interrupted'
+            WHERE finished_at IS NULL AND error IS NULL
+                AND started_at < NOW() - INTERVAL '1 day'
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not update interrupted storage checks")?;
+
+        let check = sqlx::query_as!(
+            StorageCheck,
+            r#"
+            WITH c AS (
+                INSERT INTO storage_checks (quarantine, started_by)
+                VALUES ($1, $2)
+                RETURNING *
+            )
+            SELECT c.id, c.quarantine, c.checked_files, c.issues as "issues: Json<Vec<StorageIssue>>", c.error,
+                c.started_by, u.given_name || ' ' || u.family_name as started_by_name, c.started_at, c.finished_at
+            FROM c
+            LEFT JOIN users AS u
+                ON c.started_by = u.id
+            "#,
+            quarantine,
+            started_by,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { AttachmentRepositoryError::Duplicate }
+            else { AttachmentRepositoryError::Unknown(e.into()) }
+        })?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(check)
+    }
+
+    async fn finish_storage_check(&self, id: i32, result: StorageCheckResult)-> Result<(), AttachmentRepositoryError> {
+        sqlx::query!(
+            r#"
+            UPDATE storage_checks
+            SET checked_files = $2, issues = $3, finished_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            result.checked_files,
+            Json(&result.issues) as _,
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not update storage check")?;
+
+        Ok(())
+    }
+
+    async fn fail_storage_check(&self, id: i32, error: String)-> Result<(), AttachmentRepositoryError> {
+        sqlx::query!(
+            r#"
+            UPDATE storage_checks
+            SET error = $2
+            WHERE id = $1
+            "#,
+            id,
+            error,
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not update storage check")?;
+
+        Ok(())
+    }
+
+    async fn get_storage_checks(&self, limit: i64)-> Result<Vec<StorageCheck>, AttachmentRepositoryError> {
+        let checks = sqlx::query_as!(
+            StorageCheck,
+            r#"
+            SELECT c.id, c.quarantine, c.checked_files, c.issues as "issues: Json<Vec<StorageIssue>>", c.error,
+                c.started_by, u.given_name || ' ' || u.family_name as started_by_name, c.started_at, c.finished_at
+            FROM storage_checks AS c
+            LEFT JOIN users AS u
+                ON c.started_by = u.id
+            ORDER BY c.started_at DESC
+            LIMIT $1
+            "#,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve storage checks from database")?;
+
+        Ok(checks)
+    }
+
+    async fn get_storage_check(&self, id: i32)-> Result<Option<StorageCheck>, AttachmentRepositoryError> {
+        let check = sqlx::query_as!(
+            StorageCheck,
+            r#"
+            SELECT c.id, c.quarantine, c.checked_files, c.issues as "issues: Json<Vec<StorageIssue>>", c.error,
+                c.started_by, u.given_name || ' ' || u.family_name as started_by_name, c.started_at, c.finished_at
+            FROM storage_checks AS c
+            LEFT JOIN users AS u
+                ON c.started_by = u.id
+            WHERE c.id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not retrieve storage check from database")?;
+
+        Ok(check)
+    }
+
+    async fn get_documents_without_text(&self, content_types: &[String], limit: i64)-> Result<Vec<DocumentAttachment>, AttachmentRepositoryError> {
+        let documents = sqlx::query_as!(
+            DocumentAttachment,
+            r#"
+            SELECT id, filename as "filename: Filename", hash, content_type as "content_type: ContentType", url, created_at
+            FROM document_attachments
+            WHERE text_extracted_at IS NULL AND content_type = ANY($1)
+            ORDER BY id
+            LIMIT $2
+            "#,
+            content_types,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve documents without text from database")?;
+
+        Ok(documents)
+    }
+
+    async fn set_document_text(&self, id: i32, text: Option<String>, error: Option<String>)-> Result<(), AttachmentRepositoryError> {
+        sqlx::query!(
+            r#"
+            UPDATE document_attachments
+            SET text_content = $2, text_extraction_error = $3, text_extracted_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            text,
+            error,
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not store document text in database")?;
+
+        Ok(())
+    }
+}
 
 
@@ -0,0 +1,238 @@
+use anyhow::Context;
+use sqlx::PgPool;
+
+use crate::{domain::{audits::{audit_repository::{AuditRepository, AuditRepositoryError}, model::audits::{Audit, AuditResult, AuditScan, NewAudit}}, crud::model::asset_items::AssetItem}, settings::DatabaseConfig, infastructure::services::postgres::{self, is_foreign_key_violation}};
+
+
+#[derive(Debug, Clone)]
+pub struct PostgresAuditRepository {
+    pool: PgPool,
+}
+
+impl PostgresAuditRepository {
+    pub fn new(config: &DatabaseConfig) -> anyhow::Result<Self> {
+        Ok(Self { pool: postgres::connect_lazy(config) })
+    }
+}
+
+impl AuditRepository for PostgresAuditRepository {
+    async fn add_audit(&self, new_audit: NewAudit) -> Result<Audit, AuditRepositoryError> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO inventory_audits (school, room, created_by)
+            VALUES($1, $2, $3)
+            RETURNING id
+            "#,
+            new_audit.school,
+            new_audit.room,
+            new_audit.created_by,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { AuditRepositoryError::Reference }
+            else { AuditRepositoryError::Unknown(e.into()) }
+        })?;
+
+        let audit = self.get_audit_by_id(inserted.id)
+            .await?
+            .context("inserted audit not found")?;
+
+        Ok(audit)
+    }
+
+    async fn get_audits(&self) -> Result<Vec<Audit>, AuditRepositoryError> {
+        let audits = sqlx::query_as!(
+            Audit,
+            r#"
+            SELECT a.id, a.school, a.room, a.status, a.created_by, u.given_name || ' ' || u.family_name as auditor, a.created_at, a.completed_at
+            FROM inventory_audits AS a
+            LEFT JOIN users AS u
+                ON a.created_by = u.id
+            ORDER BY a.created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve audits from database")?;
+
+        Ok(audits)
+    }
+
+    async fn get_audit_by_id(&self, id: i32) -> Result<Option<Audit>, AuditRepositoryError> {
+        let audit = sqlx::query_as!(
+            Audit,
+            r#"
+            SELECT a.id, a.school, a.room, a.status, a.created_by, u.given_name || ' ' || u.family_name as auditor, a.created_at, a.completed_at
+            FROM inventory_audits AS a
+            LEFT JOIN users AS u
+                ON a.created_by = u.id
+            WHERE a.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not retrieve audit from database")?;
+
+        Ok(audit)
+    }
+
+    async fn add_audit_scan(&self, audit_id: i32, code: String, asset_item_id: Option<i32>) -> Result<Option<AuditScan>, AuditRepositoryError> {
+        let scan = sqlx::query_as!(
+            AuditScan,
+            r#"
+            INSERT INTO inventory_audit_scans (audit_id, code, asset_item_id)
+            VALUES($1, $2, $3)
+            ON CONFLICT (audit_id, code) DO NOTHING
+            RETURNING id, audit_id, code, asset_item_id, scanned_at
+            "#,
+            audit_id,
+            code,
+            asset_item_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { AuditRepositoryError::Reference }
+            else { AuditRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(scan)
+    }
+
+    async fn get_audit_scans(&self, audit_id: i32) -> Result<Vec<AuditScan>, AuditRepositoryError> {
+        let scans = sqlx::query_as!(
+            AuditScan,
+            r#"
+            SELECT id, audit_id, code, asset_item_id, scanned_at
+            FROM inventory_audit_scans
+            WHERE audit_id = $1
+            ORDER BY scanned_at
+            "#,
+            audit_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve audit scans from database")?;
+
+        Ok(scans)
+    }
+
+    async fn get_audit_scanned_items(&self, audit_id: i32) -> Result<Vec<AssetItem>, AuditRepositoryError> {
+        // an item scanned under both its asset id and its serial number has two scans
+        let asset_items = sqlx::query_as!(
+            AssetItem,
+            r#"
+            SELECT DISTINCT ON (ai.id) ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+            FROM inventory_audit_scans AS s
+            INNER JOIN asset_item_details AS ai
+                ON s.asset_item_id = ai.id
+            WHERE s.audit_id = $1
+            ORDER BY ai.id
+            "#,
+            audit_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve scanned asset items from database")?;
+
+        Ok(asset_items)
+    }
+
+    async fn get_audit_expected_items(&self, school: String, room: Option<String>) -> Result<Vec<AssetItem>, AuditRepositoryError> {
+        // retired items are no longer expected to be on site
+        let asset_items = sqlx::query_as!(
+            AssetItem,
+            r#"
+            SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+            FROM asset_item_details AS ai
+            WHERE ai.school = $1
+                AND ($2::TEXT IS NULL OR ai.room = $2)
+                AND ai.status <> 'retired'
+            ORDER BY ai.room, ai.asset_id, ai.id
+            "#,
+            school,
+            room,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve expected asset items from database")?;
+
+        Ok(asset_items)
+    }
+
+    async fn complete_audit(&self, audit_id: i32, results: &[AuditResult]) -> Result<Option<Audit>, AuditRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        let completed = sqlx::query!(
+            r#"
+            UPDATE inventory_audits SET status = 'completed', completed_at = NOW()
+            WHERE id = $1 AND status = 'open'
+            RETURNING id
+            "#,
+            audit_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not complete audit")?;
+
+        if completed.is_none() {
+            return Ok(None);
+        }
+
+        let outcomes: Vec<String> = results.iter().map(|r| r.outcome.clone()).collect();
+        let asset_item_ids: Vec<Option<i32>> = results.iter().map(|r| r.asset_item_id).collect();
+        let codes: Vec<Option<String>> = results.iter().map(|r| r.code.clone()).collect();
+        let asset_ids: Vec<Option<String>> = results.iter().map(|r| r.asset_id.clone()).collect();
+        let names: Vec<Option<String>> = results.iter().map(|r| r.name.clone()).collect();
+        let serial_numbers: Vec<Option<String>> = results.iter().map(|r| r.serial_number.clone()).collect();
+        let schools: Vec<Option<String>> = results.iter().map(|r| r.recorded_school.clone()).collect();
+        let rooms: Vec<Option<String>> = results.iter().map(|r| r.recorded_room.clone()).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO inventory_audit_results (audit_id, outcome, asset_item_id, code, asset_id, name, serial_number, recorded_school, recorded_room)
+            SELECT $1, * FROM UNNEST($2::TEXT[], $3::INTEGER[], $4::TEXT[], $5::TEXT[], $6::TEXT[], $7::TEXT[], $8::TEXT[], $9::TEXT[])
+            "#,
+            audit_id,
+            &outcomes,
+            &asset_item_ids as _,
+            &codes as _,
+            &asset_ids as _,
+            &names as _,
+            &serial_numbers as _,
+            &schools as _,
+            &rooms as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not store audit results")?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        self.get_audit_by_id(audit_id).await
+    }
+
+    async fn get_audit_results(&self, audit_id: i32) -> Result<Vec<AuditResult>, AuditRepositoryError> {
+        let results = sqlx::query_as!(
+            AuditResult,
+            r#"
+            SELECT outcome, asset_item_id, code, asset_id, name, serial_number, recorded_school, recorded_room
+            FROM inventory_audit_results
+            WHERE audit_id = $1
+            ORDER BY id
+            "#,
+            audit_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve audit results from database")?;
+
+        Ok(results)
+    }
+}
@@ -1,10 +1,10 @@
 use anyhow::Context;
-use compact_str::{CompactString, ToCompactString};
-use futures::TryFutureExt;
-use sqlx::{postgres::{PgConnectOptions, PgPoolOptions, PgSslMode}, PgPool};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{types::Json, PgPool};
 use uuid::Uuid;
 
-use crate::{domain::crud::{crud_repository::{CrudRepository, CrudRepositoryError}, model::{asset_items::{AssetItem, AssetItemFilter, AssetItemID, NewAssetItem}, asset_types::{AssetType, AssetTypeFilter, NewAssetType, UpdateAssetType, UploadResult}}}, settings::DatabaseConfig};
+use crate::{domain::{crud::{crud_repository::{CrudRepository, CrudRepositoryError}, model::{documents::{AssetDocument, NewAssetDocument}, images::{AssetImage, EMPTY_IMAGE_URL}, bulk::{BulkAssetItemAction, BulkEditItem, BulkEditItemResult, BulkEditSummary}, history::{AssetItemHistoryEntry, FieldChange, HISTORY_ACTION_DOCUMENT_ADDED, HISTORY_ACTION_DOCUMENT_REMOVED, HISTORY_ACTION_UPDATED}, categories::{Category, CategoryField, NewCategory, NewCategoryField, UpdateCategory}, asset_items::{AssetItem, AssetItemFilter, AssetItemID, NewAssetItem, UpdateAssetItem}, custom_fields::{CustomField, NewCustomField}, depreciation::DepreciableAssetItem, asset_types::{AssetType, AssetTypeFilter, NewAssetType, UpdateAssetType, UploadResult}}}, filesystem::models::{ContentType, Filename, ImageVariant}, search::model::search::{escape_like, prefix_tsquery}}, settings::DatabaseConfig, infastructure::services::postgres::{self, is_unique_constraint_violation, is_foreign_key_violation}};
 
 
 #[derive(Debug, Clone)]
@@ -14,49 +14,62 @@ pub struct PostgresCrudRepository {
 
 impl PostgresCrudRepository {
     pub fn new(config: &DatabaseConfig) -> anyhow::Result<Self> {
-        let ssl_mode = if config.require_ssl == true { PgSslMode::Require } else { PgSslMode::Prefer };
-        let pg_connect_options = PgConnectOptions::new()
-            .host(&config.host)
-            .username(&config.username)
-            .password(&config.password)
-            .port(config.port)
-            .ssl_mode(ssl_mode)
-            .database(&config.database_name);
-
-        let pg_pool = PgPoolOptions::new().connect_lazy_with(pg_connect_options);
-
-        Ok(Self { pool: pg_pool })
+        Ok(Self { pool: postgres::connect_lazy(config) })
     }
 }
 
 
-const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "23505";
-const FOREIGN_KEY_VIOLATION: &str = "23503";
-
-fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
-    if let sqlx::Error::Database(db_err) = err {
-        if let Some(code) = db_err.code() {
-            if code == UNIQUE_CONSTRAINT_VIOLATION_CODE {
-                return true;
-            }
-        }
-    }
+/// asset_types.picture mirrors the primary gallery image's thumbnail so lists can show it without joining the gallery
+async fn sync_asset_type_picture(conn: &mut sqlx::PgConnection, asset_type_id: i32) -> Result<(), CrudRepositoryError> {
+    sqlx::query!(
+        r#"
+        UPDATE asset_types
+        SET picture = COALESCE((
+            SELECT COALESCE(
+                (SELECT v->>'url' FROM jsonb_array_elements(a.variants) AS v WHERE v->>'size' = 'thumbnail'),
+                a.url
+            )
+            FROM asset_type_images AS g
+            INNER JOIN image_attachments AS a
+                ON g.image_id = a.id
+            WHERE g.asset_type_id = $1 AND g.is_primary
+        ), $2)
+        WHERE id = $1
+        "#,
+        asset_type_id,
+        EMPTY_IMAGE_URL,
+    )
+    .execute(conn)
+    .await
+    .context("could not update asset type picture")?;
 
-    false
+    Ok(())
 }
 
-// an insert or update operation has attempted to create a foreign key value in a child table
-// that does not have a matching primary key value in the parent table
-fn is_foreign_key_violation(err: &sqlx::Error) -> bool {
-    if let sqlx::Error::Database(db_err) = err {
-        if let Some(code) = db_err.code() {
-            if code == FOREIGN_KEY_VIOLATION {
-                return true;
-            }
-        }
-    }
+/// asset_items.picture is left null without a primary image so the asset type's picture is shown instead
+async fn sync_asset_item_picture(conn: &mut sqlx::PgConnection, asset_item_id: i32) -> Result<(), CrudRepositoryError> {
+    sqlx::query!(
+        r#"
+        UPDATE asset_items
+        SET picture = (
+            SELECT COALESCE(
+                (SELECT v->>'url' FROM jsonb_array_elements(a.variants) AS v WHERE v->>'size' = 'thumbnail'),
+                a.url
+            )
+            FROM asset_item_images AS g
+            INNER JOIN image_attachments AS a
+                ON g.image_id = a.id
+            WHERE g.asset_item_id = $1 AND g.is_primary
+        )
+        WHERE id = $1
+        "#,
+        asset_item_id,
+    )
+    .execute(conn)
+    .await
+    .context("could not update asset item picture")?;
 
-    false
+    Ok(())
 }
 
 impl CrudRepository for PostgresCrudRepository {
@@ -64,20 +77,23 @@ impl CrudRepository for PostgresCrudRepository {
         let asset_type = sqlx::query_as!(
             AssetType,
             r#"
-            INSERT INTO asset_types (brand, model, description, cost, picture)
-            VALUES($1, $2, $3, $4, $5)
-            RETURNING id, brand, model, description, cost, picture, created_at
+            INSERT INTO asset_types (brand, model, description, cost, useful_life_years, picture, category_id)
+            VALUES($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
             "#,
             new_asset_type.brand,
             new_asset_type.model,
             new_asset_type.description,
             new_asset_type.cost,
+            new_asset_type.useful_life_years,
             new_asset_type.picture,
+            new_asset_type.category_id,
         )
         .fetch_one(&self.pool)
         .await
         .map_err(|e| {
             if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
             else { CrudRepositoryError::Unknown(e.into()) }
         })?;
 
@@ -88,7 +104,7 @@ impl CrudRepository for PostgresCrudRepository {
         let asset_type = sqlx::query_as!(
             AssetType,
             r#"
-            SELECT id, brand, model, description, cost, picture, created_at
+            SELECT id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
             FROM asset_types
             WHERE asset_types.id = $1
             "#,
@@ -105,7 +121,7 @@ impl CrudRepository for PostgresCrudRepository {
         let asset_type = sqlx::query_as!(
             AssetType,
             r#"
-            SELECT id, brand, model, description, cost, picture, created_at
+            SELECT id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
             FROM asset_types
             WHERE asset_types.brand = $1 AND asset_types.model = $2
             "#,
@@ -124,7 +140,7 @@ impl CrudRepository for PostgresCrudRepository {
         let asset_types = sqlx::query_as!(
             AssetType,
             r#"
-            SELECT id, brand, model, description, cost, picture, created_at
+            SELECT id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
             FROM asset_types
             "#,
         )
@@ -136,110 +152,79 @@ impl CrudRepository for PostgresCrudRepository {
     }
 
     async fn get_asset_types_search(&self, filter: AssetTypeFilter) -> Result<Vec<AssetType>, CrudRepositoryError> {
+        let prefix = filter.search.as_deref().and_then(prefix_tsquery);
+        let pattern = filter.search.as_deref().map(escape_like);
 
-        let asset_types = match (filter.search.as_deref(), filter.sort.as_deref(), filter.order.as_deref()) {
-            (Some(search), Some(sort), Some("ASC")) => {
-                sqlx::query_as!(
-                    AssetType,
-                    r#"
-                    SELECT id, brand, model, description, cost, picture, created_at
-                    FROM asset_types
-                    WHERE full_search @@ websearch_to_tsquery($1)
-                    ORDER BY
-                    CASE 
-                        WHEN $2 = 'brand' THEN brand
-                        WHEN $2 = 'model' THEN model
-                    END ASC
-                    "#,
-                    search,
-                    sort,
-                )
-                .fetch_all(&self.pool)
-                .await
-            },
-            (Some(search), Some(sort), Some("DESC")) => {
-                sqlx::query_as!(
-                    AssetType,
-                    r#"
-                    SELECT id, brand, model, description, cost, picture, created_at
-                    FROM asset_types
-                    WHERE full_search @@ websearch_to_tsquery($1)
-                    ORDER BY
-                    CASE 
-                        WHEN $2 = 'brand' THEN brand
-                        WHEN $2 = 'model' THEN model
-                    END DESC
-                    "#,
-                    search,
-                    sort,
-                )
-                .fetch_all(&self.pool)
-                .await
-            },
-            (Some(search), None, None) => {
-                sqlx::query_as!(
-                    AssetType,
-                    r#"
-                    SELECT id, brand, model, description, cost, picture, created_at
-                    FROM asset_types
-                    WHERE full_search @@ websearch_to_tsquery($1)
-                    "#,
-                    search,
-                )
-                .fetch_all(&self.pool)
-                .await
-            },
-            (None, Some(sort), Some("ASC")) => {
-                sqlx::query_as!(
-                    AssetType,
-                    r#"
-                    SELECT id, brand, model, description, cost, picture, created_at
-                    FROM asset_types
-                    ORDER BY
-                    CASE 
-                        WHEN $1 = 'brand' THEN brand
-                        WHEN $1 = 'model' THEN model
-                    END ASC
-                    "#,
-                    sort,
-                )
-                .fetch_all(&self.pool)
-                .await
-            },
-            (None, Some(sort), Some("DESC")) => {
-                sqlx::query_as!(
-                    AssetType,
-                    r#"
-                    SELECT id, brand, model, description, cost, picture, created_at
-                    FROM asset_types
-                    ORDER BY
-                    CASE 
-                        WHEN $1 = 'brand' THEN brand
-                        WHEN $1 = 'model' THEN model
-                    END DESC
-                    "#,
-                    sort,
-                )
-                .fetch_all(&self.pool)
-                .await
-            },          
-            _ => {
-                sqlx::query_as!(
-                    AssetType,
-                    r#"
-                    SELECT id, brand, model, description, cost, picture, created_at
-                    FROM asset_types
-                    "#,
-                )
-                .fetch_all(&self.pool)
-                .await
-            },
-        }.context("could not retrieve asset types from database")?;
-        
+        // an unsorted search is ordered by relevance, a sorted one by relevance within equal sort values
+        let asset_types = sqlx::query_as!(
+            AssetType,
+            r#"
+            SELECT id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
+            FROM asset_types
+            WHERE ($1::TEXT IS NULL
+                    OR full_search @@ websearch_to_tsquery($1)
+                    OR full_search @@ to_tsquery('simple', coalesce($5, ''))
+                    OR $1 <% (brand || ' ' || model)
+                    OR (brand || ' ' || model) ILIKE '%' || $6 || '%' ESCAPE '\')
+                AND ($4::INTEGER IS NULL OR category_id IN (SELECT id FROM category_paths WHERE $4 = ANY(ancestor_ids)))
+            ORDER BY
+            CASE WHEN $3 = 'ASC' THEN
+                CASE
+                    WHEN $2 = 'brand' THEN brand
+                    WHEN $2 = 'model' THEN model
+                END
+            END ASC,
+            CASE WHEN $3 = 'DESC' THEN
+                CASE
+                    WHEN $2 = 'brand' THEN brand
+                    WHEN $2 = 'model' THEN model
+                END
+            END DESC,
+            CASE WHEN $3 = 'ASC' AND $2 = 'cost' THEN cost END ASC NULLS LAST,
+            CASE WHEN $3 = 'DESC' AND $2 = 'cost' THEN cost END DESC NULLS LAST,
+            ts_rank(full_search, websearch_to_tsquery($1))
+                + ts_rank(full_search, to_tsquery('simple', coalesce($5, '')))
+                + word_similarity($1, brand || ' ' || model) DESC NULLS LAST,
+            brand, model
+            "#,
+            filter.search,
+            filter.sort,
+            filter.order,
+            filter.category_id,
+            prefix,
+            pattern,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset types from database")?;
 
         Ok(asset_types)
     }
 
+    async fn get_asset_type_search_suggestion(&self, search: &str) -> Result<Option<String>, CrudRepositoryError> {
+        let suggestion = sqlx::query_scalar!(
+            r#"
+            SELECT term AS "term!"
+            FROM (
+                SELECT brand AS term FROM asset_types
+                UNION
+                SELECT model FROM asset_types
+                UNION
+                SELECT brand || ' ' || model FROM asset_types
+            ) AS terms
+            WHERE term % $1
+            ORDER BY similarity(term, $1) DESC, term
+            LIMIT 1
+            "#,
+            search,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not retrieve asset type search suggestion from database")?;
+
+        Ok(suggestion)
+    }
+
     async fn delete_asset_type(&self, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
         let returned_id = sqlx::query!(
             r#"
@@ -250,11 +235,69 @@ impl CrudRepository for PostgresCrudRepository {
         )
         .fetch_optional(&self.pool)
         .await
-        .context("could not delete asset from database")?;
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(returned_id.map(|r| r.id))
+    }
+
+    async fn reassign_and_delete_asset_type(&self, id: i32, reassign_to: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        sqlx::query!(
+            r#"
+            UPDATE asset_items SET asset_type_id = $1
+            WHERE asset_type_id = $2
+            "#,
+            reassign_to,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        let returned_id = sqlx::query!(
+            r#"
+            DELETE FROM asset_types WHERE id = $1
+            RETURNING id
+            "#,
+            id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
 
         Ok(returned_id.map(|r| r.id))
     }
 
+    async fn get_asset_item_count_for_type(&self, asset_type_id: i32) -> Result<i64, CrudRepositoryError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!" FROM asset_items WHERE asset_type_id = $1
+            "#,
+            asset_type_id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("could not count asset items from database")?;
+
+        Ok(count)
+    }
+
     async fn update_asset_type(&self, id: i32, update_asset_type: UpdateAssetType) -> Result<Option<AssetType>, CrudRepositoryError> {
         let asset_type = match update_asset_type.picture {
             Some(picture) => {
@@ -262,21 +305,24 @@ impl CrudRepository for PostgresCrudRepository {
                     AssetType,
                     r#"
                     UPDATE asset_types
-                        SET brand = $1, model = $2, description = $3, cost = $4, picture = $5
-                        WHERE id = $6
-                        RETURNING id, brand, model, description, cost, picture, created_at
+                        SET brand = $1, model = $2, description = $3, cost = $4, useful_life_years = $5, picture = $6, category_id = $7
+                        WHERE id = $8
+                        RETURNING id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
                     "#,
                     update_asset_type.brand.clone(),
                     update_asset_type.model.clone(),
                     update_asset_type.description.clone(),
-                    update_asset_type.cost.clone(),
+                    update_asset_type.cost,
+                    update_asset_type.useful_life_years,
                     picture.clone(),
+                    update_asset_type.category_id,
                     id,
                 )
                 .fetch_optional(&self.pool)
                 .await
                 .map_err(|e| {
                     if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+                    else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
                     else { CrudRepositoryError::Unknown(e.into()) }
                 })?
             },
@@ -285,20 +331,23 @@ impl CrudRepository for PostgresCrudRepository {
                     AssetType,
                     r#"
                     UPDATE asset_types
-                        SET brand = $1, model = $2, description = $3, cost = $4
-                        WHERE id = $5
-                        RETURNING id, brand, model, description, cost, picture, created_at
+                        SET brand = $1, model = $2, description = $3, cost = $4, useful_life_years = $5, category_id = $6
+                        WHERE id = $7
+                        RETURNING id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
                     "#,
                     update_asset_type.brand.clone(),
                     update_asset_type.model.clone(),
                     update_asset_type.description.clone(),
-                    update_asset_type.cost.clone(),
+                    update_asset_type.cost,
+                    update_asset_type.useful_life_years,
+                    update_asset_type.category_id,
                     id,
                 )
                 .fetch_optional(&self.pool)
                 .await
                 .map_err(|e| {
                     if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+                    else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
                     else { CrudRepositoryError::Unknown(e.into()) }
                 })?
             },
@@ -307,67 +356,55 @@ impl CrudRepository for PostgresCrudRepository {
         Ok(asset_type)
     }
 
-    async fn update_asset_type_picture(&self, id: i32, picture: String) -> Result<Option<AssetType>, CrudRepositoryError> {
-        let asset_type = sqlx::query_as!(
-            AssetType,
-            r#"
-            UPDATE asset_types
-                SET picture = $1
-                WHERE id = $2
-                RETURNING id, brand, model, description, cost, picture, created_at
-            "#,
-            picture,
-            id,
-        )
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| {
-            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
-            else { CrudRepositoryError::Unknown(e.into()) }
-        })?;
-
-        Ok(asset_type)
-    }
-
     async fn bulk_add_or_update_asset_type(&self, add_asset_types: &[NewAssetType]) -> Result<UploadResult, CrudRepositoryError> {
         let brands: Vec<String> = add_asset_types.iter().map(|a| a.brand.clone()).collect();
         let models: Vec<String> = add_asset_types.iter().map(|a| a.model.clone()).collect();
-        let descriptions: Vec<Option<String>> = add_asset_types.iter().map(|a| a.description.as_ref().map(|d| d.clone())).collect();
-        let costs: Vec<Option<String>> = add_asset_types.iter().map(|a| a.cost.as_ref().map(|c| c.clone())).collect();
-        let pictures: Vec<Option<String>> = add_asset_types.iter().map(|a| a.picture.as_ref().map(|p| p.clone())).collect();
+        let descriptions: Vec<Option<String>> = add_asset_types.iter().map(|a| a.description.clone()).collect();
+        let costs: Vec<Option<Decimal>> = add_asset_types.iter().map(|a| a.cost).collect();
+        let useful_lives: Vec<Option<i32>> = add_asset_types.iter().map(|a| a.useful_life_years).collect();
+        let pictures: Vec<Option<String>> = add_asset_types.iter().map(|a| a.picture.clone()).collect();
+        let category_ids: Vec<Option<i32>> = add_asset_types.iter().map(|a| a.category_id).collect();
 
         let rows = sqlx::query_as!(
             AssetType,
             r#"
-            INSERT INTO asset_types (brand, model, description, cost, picture)
-            SELECT DISTINCT ON(brand, model) brand, model, description, cost, picture FROM(
+            INSERT INTO asset_types (brand, model, description, cost, useful_life_years, picture, category_id)
+            SELECT DISTINCT ON(brand, model) brand, model, description, cost, useful_life_years, picture, category_id FROM(
                 SELECT * FROM UNNEST (
                 $1::TEXT[],
                 $2::TEXT[],
                 $3::TEXT[],
-                $4::TEXT[],
-                $5::TEXT[]
-            ) AS t(brand, model, description, cost, picture)
+                $4::NUMERIC[],
+                $5::INTEGER[],
+                $6::TEXT[],
+                $7::INTEGER[]
+            ) AS t(brand, model, description, cost, useful_life_years, picture, category_id)
             WHERE t.brand IS NOT NULL AND t.model IS NOT NULL
             ) AS bulk_query
             ON CONFLICT ON CONSTRAINT asset_types_brand_model_key DO
             UPDATE SET 
                 description = excluded.description,
                 cost = excluded.cost,
-                picture = excluded.picture
-            RETURNING id, brand, model, description, cost, picture, created_at
+                useful_life_years = excluded.useful_life_years,
+                picture = excluded.picture,
+                -- files without a category column keep the category set in the app
+                category_id = COALESCE(excluded.category_id, asset_types.category_id)
+            RETURNING id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
             "#,
             &brands,
             &models,
             &descriptions as _,
             &costs as _,
+            &useful_lives as _,
             &pictures as _,
+            &category_ids as _,
         )
         .fetch_all(&self.pool)
         .await
         .map_err(|e| {
             tracing::error!("{}", e);
-            CrudRepositoryError::Unknown(e.into())
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
         })?;
 
         Ok(UploadResult { total: brands.len(), processed: rows.len() })
@@ -376,38 +413,45 @@ impl CrudRepository for PostgresCrudRepository {
     async fn bulk_add_asset_type(&self, add_asset_types: &[NewAssetType]) -> Result<UploadResult, CrudRepositoryError> {
         let brands: Vec<String> = add_asset_types.iter().map(|a| a.brand.clone()).collect();
         let models: Vec<String> = add_asset_types.iter().map(|a| a.model.clone()).collect();
-        let descriptions: Vec<Option<String>> = add_asset_types.iter().map(|a| a.description.as_ref().map(|d| d.clone())).collect();
-        let costs: Vec<Option<String>> = add_asset_types.iter().map(|a| a.cost.as_ref().map(|c| c.clone())).collect();
-        let pictures: Vec<Option<String>> = add_asset_types.iter().map(|a| a.picture.as_ref().map(|p| p.clone())).collect();
+        let descriptions: Vec<Option<String>> = add_asset_types.iter().map(|a| a.description.clone()).collect();
+        let costs: Vec<Option<Decimal>> = add_asset_types.iter().map(|a| a.cost).collect();
+        let useful_lives: Vec<Option<i32>> = add_asset_types.iter().map(|a| a.useful_life_years).collect();
+        let pictures: Vec<Option<String>> = add_asset_types.iter().map(|a| a.picture.clone()).collect();
+        let category_ids: Vec<Option<i32>> = add_asset_types.iter().map(|a| a.category_id).collect();
 
         let rows = sqlx::query_as!(
             AssetType,
             r#"
-            INSERT INTO asset_types (brand, model, description, cost, picture)
-            SELECT DISTINCT ON(brand, model) brand, model, description, cost, picture FROM(
+            INSERT INTO asset_types (brand, model, description, cost, useful_life_years, picture, category_id)
+            SELECT DISTINCT ON(brand, model) brand, model, description, cost, useful_life_years, picture, category_id FROM(
                 SELECT * FROM UNNEST (
                 $1::TEXT[],
                 $2::TEXT[],
                 $3::TEXT[],
-                $4::TEXT[],
-                $5::TEXT[]
-            ) AS t(brand, model, description, cost, picture)
+                $4::NUMERIC[],
+                $5::INTEGER[],
+                $6::TEXT[],
+                $7::INTEGER[]
+            ) AS t(brand, model, description, cost, useful_life_years, picture, category_id)
             WHERE t.brand IS NOT NULL AND t.model IS NOT NULL
             ) AS bulk_query
             ON CONFLICT ON CONSTRAINT asset_types_brand_model_key DO NOTHING
-            RETURNING id, brand, model, description, cost, picture, created_at
+            RETURNING id, brand, model, description, cost, useful_life_years, picture, category_id, created_at
             "#,
             &brands,
             &models,
             &descriptions as _,
             &costs as _,
+            &useful_lives as _,
             &pictures as _,
+            &category_ids as _,
         )
         .fetch_all(&self.pool)
         .await
         .map_err(|e| {
             tracing::error!("{}", e);
-            CrudRepositoryError::Unknown(e.into())
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
         })?;
 
         Ok(UploadResult { total: brands.len(), processed: rows.len() })
@@ -417,18 +461,19 @@ impl CrudRepository for PostgresCrudRepository {
         let asset_item = sqlx::query_as!(
             AssetItemID,
             r#"
-            INSERT INTO asset_items (asset_id, name, serial_number, brand, model, school, room, funding_source)
-            VALUES($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO asset_items (asset_id, name, serial_number, asset_type_id, school, room, funding_source, purchase_date, custom_fields)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING id
             "#,
             new_asset_item.asset_id,
             new_asset_item.name,
             new_asset_item.serial_number,
-            new_asset_item.brand,
-            new_asset_item.model,
+            new_asset_item.asset_type_id,
             new_asset_item.school,
             new_asset_item.room,
-            new_asset_item.funding_source
+            new_asset_item.funding_source,
+            new_asset_item.purchase_date,
+            new_asset_item.custom_fields,
         )
         .fetch_one(&self.pool)
         .await
@@ -441,14 +486,61 @@ impl CrudRepository for PostgresCrudRepository {
         Ok(asset_item)
     }
 
+    async fn bulk_add_asset_items(&self, new_asset_items: &[NewAssetItem]) -> Result<UploadResult, CrudRepositoryError> {
+        let asset_ids: Vec<Option<String>> = new_asset_items.iter().map(|i| i.asset_id.clone()).collect();
+        let names: Vec<Option<String>> = new_asset_items.iter().map(|i| i.name.clone()).collect();
+        let serial_numbers: Vec<Option<String>> = new_asset_items.iter().map(|i| i.serial_number.clone()).collect();
+        let asset_type_ids: Vec<Option<i32>> = new_asset_items.iter().map(|i| i.asset_type_id).collect();
+        let schools: Vec<Option<String>> = new_asset_items.iter().map(|i| i.school.clone()).collect();
+        let rooms: Vec<Option<String>> = new_asset_items.iter().map(|i| i.room.clone()).collect();
+        let funding_sources: Vec<Option<String>> = new_asset_items.iter().map(|i| i.funding_source.clone()).collect();
+        let purchase_dates: Vec<Option<NaiveDate>> = new_asset_items.iter().map(|i| i.purchase_date).collect();
+        let custom_fields: Vec<serde_json::Value> = new_asset_items.iter().map(|i| i.custom_fields.clone()).collect();
+
+        // rows that collide with an existing asset id or serial number are skipped
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO asset_items (asset_id, name, serial_number, asset_type_id, school, room, funding_source, purchase_date, custom_fields)
+            SELECT * FROM UNNEST (
+                $1::TEXT[],
+                $2::TEXT[],
+                $3::TEXT[],
+                $4::INTEGER[],
+                $5::TEXT[],
+                $6::TEXT[],
+                $7::TEXT[],
+                $8::DATE[],
+                $9::JSONB[]
+            )
+            ON CONFLICT DO NOTHING
+            RETURNING id
+            "#,
+            &asset_ids as _,
+            &names as _,
+            &serial_numbers as _,
+            &asset_type_ids as _,
+            &schools as _,
+            &rooms as _,
+            &funding_sources as _,
+            &purchase_dates as _,
+            &custom_fields,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(UploadResult { total: new_asset_items.len(), processed: inserted.len() })
+    }
+
     async fn get_asset_items(&self) -> Result<Vec<AssetItem>, CrudRepositoryError> {
         let asset_items = sqlx::query_as!(
             AssetItem,
             r#"
-            SELECT ai.id, ai.asset_id, ai.name, ai.serial_number, ai.brand, ai.model, at.description, at.cost, ai.school, ai.room, ai.funding_source, ai.created_at
-            FROM asset_items AS ai
-            LEFT JOIN asset_types AS at
-                ON ai.brand = at.brand AND ai.model = at.model
+            SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+            FROM asset_item_details AS ai
             "#,
         )
         .fetch_all(&self.pool)
@@ -459,18 +551,100 @@ impl CrudRepository for PostgresCrudRepository {
     }
 
     async fn get_asset_items_search(&self, filter: AssetItemFilter) -> Result<Vec<AssetItem>, CrudRepositoryError> {
-        
-        Ok(Vec::new())        
+        let asset_items = match filter.order.as_deref() {
+            Some("DESC") => {
+                sqlx::query_as!(
+                    AssetItem,
+                    r#"
+                    SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+                    FROM asset_item_details AS ai
+                    WHERE ($1::TEXT IS NULL
+                        OR ai.asset_id ILIKE '%' || $1 || '%'
+                        OR ai.serial_number ILIKE '%' || $1 || '%'
+                        OR ai.name ILIKE '%' || $1 || '%'
+                        OR ai.brand ILIKE '%' || $1 || '%'
+                        OR ai.model ILIKE '%' || $1 || '%'
+                        OR ai.school ILIKE '%' || $1 || '%'
+                        OR ai.room ILIKE '%' || $1 || '%'
+                        OR EXISTS (
+                            SELECT 1 FROM jsonb_each_text(ai.custom_fields) AS cf
+                            WHERE cf.value ILIKE '%' || $1 || '%'
+                        ))
+                        AND ($3::INTEGER IS NULL OR ai.category_id IN (SELECT id FROM category_paths WHERE $3 = ANY(ancestor_ids)))
+                        AND ($4::TEXT IS NULL OR lower(ai.school) = lower($4))
+                        AND ($5::TEXT IS NULL OR ai.status = $5)
+                    ORDER BY
+                    CASE 
+                        WHEN $2 = 'asset_id' THEN ai.asset_id
+                        WHEN $2 = 'name' THEN ai.name
+                        WHEN $2 = 'brand' THEN ai.brand
+                        WHEN $2 = 'model' THEN ai.model
+                        WHEN $2 = 'serial_number' THEN ai.serial_number
+                        WHEN $2 = 'school' THEN ai.school
+                        WHEN $2 = 'room' THEN ai.room
+                    END DESC, ai.id
+                    "#,
+                    filter.search,
+                    filter.sort,
+                    filter.category_id,
+                    filter.school,
+                    filter.status,
+                )
+                .fetch_all(&self.pool)
+                .await
+            },
+            _ => {
+                sqlx::query_as!(
+                    AssetItem,
+                    r#"
+                    SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+                    FROM asset_item_details AS ai
+                    WHERE ($1::TEXT IS NULL
+                        OR ai.asset_id ILIKE '%' || $1 || '%'
+                        OR ai.serial_number ILIKE '%' || $1 || '%'
+                        OR ai.name ILIKE '%' || $1 || '%'
+                        OR ai.brand ILIKE '%' || $1 || '%'
+                        OR ai.model ILIKE '%' || $1 || '%'
+                        OR ai.school ILIKE '%' || $1 || '%'
+                        OR ai.room ILIKE '%' || $1 || '%'
+                        OR EXISTS (
+                            SELECT 1 FROM jsonb_each_text(ai.custom_fields) AS cf
+                            WHERE cf.value ILIKE '%' || $1 || '%'
+                        ))
+                        AND ($3::INTEGER IS NULL OR ai.category_id IN (SELECT id FROM category_paths WHERE $3 = ANY(ancestor_ids)))
+                        AND ($4::TEXT IS NULL OR lower(ai.school) = lower($4))
+                        AND ($5::TEXT IS NULL OR ai.status = $5)
+                    ORDER BY
+                    CASE 
+                        WHEN $2 = 'asset_id' THEN ai.asset_id
+                        WHEN $2 = 'name' THEN ai.name
+                        WHEN $2 = 'brand' THEN ai.brand
+                        WHEN $2 = 'model' THEN ai.model
+                        WHEN $2 = 'serial_number' THEN ai.serial_number
+                        WHEN $2 = 'school' THEN ai.school
+                        WHEN $2 = 'room' THEN ai.room
+                    END ASC, ai.id
+                    "#,
+                    filter.search,
+                    filter.sort,
+                    filter.category_id,
+                    filter.school,
+                    filter.status,
+                )
+                .fetch_all(&self.pool)
+                .await
+            },
+        }.context("could not retrieve asset items from database")?;
+
+        Ok(asset_items)
     }
 
     async fn get_asset_item_by_id(&self, id: i32) -> Result<Option<AssetItem>, CrudRepositoryError> {
         let asset_item = sqlx::query_as!(
             AssetItem,
             r#"
-            SELECT ai.id, ai.asset_id, ai.name, ai.serial_number, ai.brand, ai.model, at.description, at.cost, ai.school, ai.room, ai.funding_source, ai.created_at
-            FROM asset_items AS ai
-            LEFT JOIN asset_types AS at
-                ON ai.brand = at.brand AND ai.model = at.model
+            SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+            FROM asset_item_details AS ai
             WHERE ai.id = $1
             "#,
             id
@@ -482,6 +656,176 @@ impl CrudRepository for PostgresCrudRepository {
         Ok(asset_item)
     }
 
+    async fn get_asset_items_by_code(&self, code: String) -> Result<Vec<AssetItem>, CrudRepositoryError> {
+        // code is expected to be normalized the same way as the lookup indexes
+        let asset_items = sqlx::query_as!(
+            AssetItem,
+            r#"
+            SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+            FROM asset_item_details AS ai
+            WHERE LTRIM(UPPER(REGEXP_REPLACE(ai.asset_id, '\s', '', 'g')), '0') = $1
+                OR LTRIM(UPPER(REGEXP_REPLACE(ai.serial_number, '\s', '', 'g')), '0') = $1
+            ORDER BY ai.id
+            "#,
+            code
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset items from database")?;
+
+        Ok(asset_items)
+    }
+
+    async fn update_asset_item(&self, id: i32, update_asset_item: UpdateAssetItem) -> Result<Option<i32>, CrudRepositoryError> {
+        let returned_id = sqlx::query!(
+            r#"
+            UPDATE asset_items
+            SET asset_id = $2, name = $3, serial_number = $4, asset_type_id = $5, school = $6, room = $7, funding_source = $8, purchase_date = $9, custom_fields = $10
+            WHERE id = $1
+            RETURNING id
+            "#,
+            id,
+            update_asset_item.asset_id,
+            update_asset_item.name,
+            update_asset_item.serial_number,
+            update_asset_item.asset_type_id,
+            update_asset_item.school,
+            update_asset_item.room,
+            update_asset_item.funding_source,
+            update_asset_item.purchase_date,
+            update_asset_item.custom_fields,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(returned_id.map(|r| r.id))
+    }
+
+    async fn update_asset_item_location(&self, id: i32, school: String, room: Option<String>, changed_by: Uuid) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        // lock the row so the recorded "from" values match what gets overwritten
+        let Some(current) = sqlx::query!(
+            r#"
+            SELECT school, room FROM asset_items WHERE id = $1
+            FOR UPDATE
+            "#,
+            id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not retrieve asset item from database")? else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE asset_items SET school = $2, room = $3
+            WHERE id = $1
+            "#,
+            id,
+            school,
+            room,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not update asset item location")?;
+
+        let changes: Vec<FieldChange> = [
+            FieldChange::between("School", current.school.as_deref(), Some(school.as_str())),
+            FieldChange::between("Room", current.room.as_deref(), room.as_deref()),
+        ].into_iter().flatten().collect();
+
+        if !changes.is_empty() {
+            let changes = serde_json::to_value(changes)
+                .context("could not serialize asset item changes")?;
+
+            sqlx::query!(
+                r#"
+                INSERT INTO asset_item_history (asset_item_id, action, changes, changed_by)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                id,
+                HISTORY_ACTION_UPDATED,
+                changes,
+                changed_by,
+            )
+            .execute(&mut *tx)
+            .await
+            .context("could not record asset item history")?;
+        }
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(Some(id))
+    }
+
+    async fn update_asset_item_status(&self, id: i32, status: String, changed_by: Uuid) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        // lock the row so the recorded "from" value matches what gets overwritten
+        let Some(current) = sqlx::query_scalar!(
+            r#"
+            SELECT status FROM asset_items WHERE id = $1
+            FOR UPDATE
+            "#,
+            id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not retrieve asset item from database")? else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE asset_items SET status = $2
+            WHERE id = $1
+            "#,
+            id,
+            status,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not update asset item status")?;
+
+        if let Some(change) = FieldChange::between("Status", Some(current.as_str()), Some(status.as_str())) {
+            let changes = serde_json::to_value(vec![change])
+                .context("could not serialize asset item changes")?;
+
+            sqlx::query!(
+                r#"
+                INSERT INTO asset_item_history (asset_item_id, action, changes, changed_by)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                id,
+                HISTORY_ACTION_UPDATED,
+                changes,
+                changed_by,
+            )
+            .execute(&mut *tx)
+            .await
+            .context("could not record asset item history")?;
+        }
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(Some(id))
+    }
+
     async fn delete_asset_item(&self, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
         let returned_id = sqlx::query!(
             r#"
@@ -497,8 +841,1050 @@ impl CrudRepository for PostgresCrudRepository {
         Ok(returned_id.map(|r| r.id))
     }
 
-    
-}
-
-
+    async fn bulk_update_asset_items(&self, ids: &[i32], action: BulkAssetItemAction, changed_by: Uuid) -> Result<BulkEditSummary, CrudRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
 
+        // lock the rows so the recorded "from" values match what gets overwritten
+        let asset_items = sqlx::query_as!(
+            BulkEditItem,
+            r#"
+            SELECT ai.id, ai.asset_id, ai.name, ai.serial_number, ai.school, ai.room, ai.funding_source, ai.status, ai.asset_type_id,
+                at.brand || ' ' || at.model as asset_type, ai.custom_fields
+            FROM asset_items AS ai
+            LEFT JOIN asset_types AS at
+                ON ai.asset_type_id = at.id
+            WHERE ai.id = ANY($1)
+            ORDER BY ai.id
+            FOR UPDATE OF ai
+            "#,
+            ids,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("could not retrieve asset items from database")?;
+
+        let history_action = action.history_action();
+        let item_ids: Vec<i32> = asset_items.iter().map(|i| i.id).collect();
+
+        let results: Vec<BulkEditItemResult> = match action {
+            BulkAssetItemAction::Update(update) => {
+                let asset_type = match update.asset_type_id {
+                    Some(asset_type_id) => {
+                        let asset_type = sqlx::query_scalar!(
+                            r#"
+                            SELECT brand || ' ' || model as "asset_type!" FROM asset_types WHERE id = $1
+                            "#,
+                            asset_type_id,
+                        )
+                        .fetch_optional(&mut *tx)
+                        .await
+                        .context("could not retrieve asset type from database")?;
+
+                        Some(asset_type.ok_or(CrudRepositoryError::Reference)?)
+                    },
+                    None => None,
+                };
+
+                // custom field definitions of the new type and of the types the items are leaving
+                let fields = match update.asset_type_id {
+                    Some(asset_type_id) => {
+                        let mut asset_type_ids: Vec<i32> = asset_items.iter().filter_map(|i| i.asset_type_id).collect();
+                        asset_type_ids.push(asset_type_id);
+
+                        sqlx::query_as!(
+                            CustomField,
+                            r#"
+                            SELECT id as "id!", asset_type_id as "asset_type_id!", category_id, name as "name!", label as "label!", field_type as "field_type!",
+                                options as "options!", required as "required!", position as "position!", created_at as "created_at!"
+                            FROM asset_type_effective_fields
+                            WHERE asset_type_id = ANY($1)
+                            "#,
+                            &asset_type_ids,
+                        )
+                        .fetch_all(&mut *tx)
+                        .await
+                        .context("could not retrieve custom fields from database")?
+                    },
+                    None => Vec::new(),
+                };
+
+                let mut results = Vec::with_capacity(asset_items.len());
+                let mut custom_fields = Vec::with_capacity(asset_items.len());
+                for i in asset_items.iter() {
+                    let mut changes = update.changes(i, asset_type.as_deref());
+                    let values = match update.asset_type_id {
+                        Some(asset_type_id) if i.asset_type_id != Some(asset_type_id) => {
+                            let (values, dropped) = i.retyped_custom_fields(asset_type_id, &fields);
+                            changes.extend(dropped);
+                            values
+                        },
+                        _ => i.custom_fields.clone(),
+                    };
+
+                    results.push(BulkEditItemResult {
+                        asset_item_id: i.id,
+                        label: i.label(),
+                        changes,
+                    });
+                    custom_fields.push(values);
+                }
+
+                sqlx::query!(
+                    r#"
+                    UPDATE asset_items AS ai
+                    SET school = COALESCE($2, ai.school),
+                        room = COALESCE($3, ai.room),
+                        funding_source = COALESCE($4, ai.funding_source),
+                        status = COALESCE($5, ai.status),
+                        asset_type_id = COALESCE($6, ai.asset_type_id),
+                        custom_fields = t.custom_fields
+                    FROM UNNEST($1::INTEGER[], $7::JSONB[]) AS t(id, custom_fields)
+                    WHERE ai.id = t.id
+                    "#,
+                    &item_ids,
+                    update.school,
+                    update.room,
+                    update.funding_source,
+                    update.status,
+                    update.asset_type_id,
+                    &custom_fields,
+                )
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+                    else { CrudRepositoryError::Unknown(e.into()) }
+                })?;
+
+                results
+            },
+            BulkAssetItemAction::Delete => {
+                let results = asset_items.iter()
+                    .map(|i| BulkEditItemResult {
+                        asset_item_id: i.id,
+                        label: i.label(),
+                        changes: i.deleted_changes(),
+                    })
+                    .collect();
+
+                sqlx::query!(
+                    r#"
+                    DELETE FROM asset_items WHERE id = ANY($1)
+                    "#,
+                    &item_ids,
+                )
+                .execute(&mut *tx)
+                .await
+                .context("could not delete asset items from database")?;
+
+                results
+            },
+        };
+
+        // items the action left as they were get no history entry
+        let changed: Vec<&BulkEditItemResult> = results.iter().filter(|r| !r.changes.is_empty()).collect();
+        let history_ids: Vec<i32> = changed.iter().map(|r| r.asset_item_id).collect();
+        let history_changes: Vec<serde_json::Value> = changed.iter()
+            .map(|r| serde_json::to_value(&r.changes))
+            .collect::<Result<Vec<_>, _>>()
+            .context("could not serialize asset item changes")?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO asset_item_history (asset_item_id, action, changes, changed_by)
+            SELECT t.asset_item_id, $3, t.changes, $4
+            FROM UNNEST($1::INTEGER[], $2::JSONB[]) AS t(asset_item_id, changes)
+            "#,
+            &history_ids,
+            &history_changes,
+            history_action,
+            changed_by,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not record asset item history")?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(BulkEditSummary { action: history_action.to_string(), selected: ids.len(), items: results })
+    }
+
+    async fn get_asset_item_history(&self, asset_item_id: i32) -> Result<Vec<AssetItemHistoryEntry>, CrudRepositoryError> {
+        let history = sqlx::query_as!(
+            AssetItemHistoryEntry,
+            r#"
+            SELECT h.id, h.asset_item_id, h.action, h.changes as "changes: Json<Vec<FieldChange>>", h.changed_by,
+                u.given_name || ' ' || u.family_name as changed_by_name, h.created_at
+            FROM asset_item_history AS h
+            LEFT JOIN users AS u
+                ON h.changed_by = u.id
+            WHERE h.asset_item_id = $1
+            ORDER BY h.created_at DESC, h.id DESC
+            "#,
+            asset_item_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset item history from database")?;
+
+        Ok(history)
+    }
+
+    async fn get_asset_item_documents(&self, asset_item_id: i32) -> Result<Vec<AssetDocument>, CrudRepositoryError> {
+        let documents = sqlx::query_as!(
+            AssetDocument,
+            r#"
+            SELECT d.id, d.document_id, a.filename as "filename: Filename", a.content_type as "content_type: ContentType", a.url, d.kind, d.description,
+                d.created_by, u.given_name || ' ' || u.family_name as created_by_name, d.created_at
+            FROM asset_item_documents AS d
+            INNER JOIN document_attachments AS a
+                ON d.document_id = a.id
+            LEFT JOIN users AS u
+                ON d.created_by = u.id
+            WHERE d.asset_item_id = $1
+            ORDER BY d.created_at DESC
+            "#,
+            asset_item_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset item documents from database")?;
+
+        Ok(documents)
+    }
+
+    async fn add_asset_item_document(&self, asset_item_id: i32, new_document: NewAssetDocument) -> Result<AssetDocument, CrudRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        let document = sqlx::query_as!(
+            AssetDocument,
+            r#"
+            WITH inserted AS (
+                INSERT INTO asset_item_documents (asset_item_id, document_id, kind, description, created_by)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id, document_id, kind, description, created_by, created_at
+            )
+            SELECT d.id, d.document_id, a.filename as "filename: Filename", a.content_type as "content_type: ContentType", a.url, d.kind, d.description,
+                d.created_by, u.given_name || ' ' || u.family_name as created_by_name, d.created_at
+            FROM inserted AS d
+            INNER JOIN document_attachments AS a
+                ON d.document_id = a.id
+            LEFT JOIN users AS u
+                ON d.created_by = u.id
+            "#,
+            asset_item_id,
+            new_document.document_id,
+            new_document.kind,
+            new_document.description,
+            new_document.created_by,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        let changes = serde_json::to_value(vec![document.added_change()])
+            .context("could not serialize asset item changes")?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO asset_item_history (asset_item_id, action, changes, changed_by)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            asset_item_id,
+            HISTORY_ACTION_DOCUMENT_ADDED,
+            changes,
+            new_document.created_by,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not record asset item history")?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(document)
+    }
+
+    async fn delete_asset_item_document(&self, asset_item_id: i32, id: i32, changed_by: Uuid) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        let document = sqlx::query_as!(
+            AssetDocument,
+            r#"
+            WITH deleted AS (
+                DELETE FROM asset_item_documents
+                WHERE id = $1 AND asset_item_id = $2
+                RETURNING id, document_id, kind, description, created_by, created_at
+            )
+            SELECT d.id, d.document_id, a.filename as "filename: Filename", a.content_type as "content_type: ContentType", a.url, d.kind, d.description,
+                d.created_by, NULL::TEXT as created_by_name, d.created_at
+            FROM deleted AS d
+            INNER JOIN document_attachments AS a
+                ON d.document_id = a.id
+            "#,
+            id,
+            asset_item_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not delete asset item document from database")?;
+
+        let document = match document {
+            Some(document) => document,
+            None => return Ok(None),
+        };
+
+        let changes = serde_json::to_value(vec![document.removed_change()])
+            .context("could not serialize asset item changes")?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO asset_item_history (asset_item_id, action, changes, changed_by)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            asset_item_id,
+            HISTORY_ACTION_DOCUMENT_REMOVED,
+            changes,
+            changed_by,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not record asset item history")?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        Ok(Some(document.id))
+    }
+
+    async fn get_asset_type_documents(&self, asset_type_id: i32) -> Result<Vec<AssetDocument>, CrudRepositoryError> {
+        let documents = sqlx::query_as!(
+            AssetDocument,
+            r#"
+            SELECT d.id, d.document_id, a.filename as "filename: Filename", a.content_type as "content_type: ContentType", a.url, d.kind, d.description,
+                d.created_by, u.given_name || ' ' || u.family_name as created_by_name, d.created_at
+            FROM asset_type_documents AS d
+            INNER JOIN document_attachments AS a
+                ON d.document_id = a.id
+            LEFT JOIN users AS u
+                ON d.created_by = u.id
+            WHERE d.asset_type_id = $1
+            ORDER BY d.created_at DESC
+            "#,
+            asset_type_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset type documents from database")?;
+
+        Ok(documents)
+    }
+
+    async fn add_asset_type_document(&self, asset_type_id: i32, new_document: NewAssetDocument) -> Result<AssetDocument, CrudRepositoryError> {
+        let document = sqlx::query_as!(
+            AssetDocument,
+            r#"
+            WITH inserted AS (
+                INSERT INTO asset_type_documents (asset_type_id, document_id, kind, description, created_by)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id, document_id, kind, description, created_by, created_at
+            )
+            SELECT d.id, d.document_id, a.filename as "filename: Filename", a.content_type as "content_type: ContentType", a.url, d.kind, d.description,
+                d.created_by, u.given_name || ' ' || u.family_name as created_by_name, d.created_at
+            FROM inserted AS d
+            INNER JOIN document_attachments AS a
+                ON d.document_id = a.id
+            LEFT JOIN users AS u
+                ON d.created_by = u.id
+            "#,
+            asset_type_id,
+            new_document.document_id,
+            new_document.kind,
+            new_document.description,
+            new_document.created_by,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(document)
+    }
+
+    async fn delete_asset_type_document(&self, asset_type_id: i32, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM asset_type_documents
+            WHERE id = $1 AND asset_type_id = $2
+            RETURNING id
+            "#,
+            id,
+            asset_type_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not delete asset type document from database")?;
+
+        Ok(deleted.map(|r| r.id))
+    }
+
+    async fn get_asset_type_images(&self, asset_type_id: i32) -> Result<Vec<AssetImage>, CrudRepositoryError> {
+        let images = sqlx::query_as!(
+            AssetImage,
+            r#"
+            SELECT g.id, g.image_id, a.filename as "filename: Filename", a.url, a.variants as "variants: Json<Vec<ImageVariant>>",
+                g.position, g.is_primary, g.created_at
+            FROM asset_type_images AS g
+            INNER JOIN image_attachments AS a
+                ON g.image_id = a.id
+            WHERE g.asset_type_id = $1
+            ORDER BY g.position, g.id
+            "#,
+            asset_type_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset type images from database")?;
+
+        Ok(images)
+    }
+
+    async fn add_asset_type_image(&self, asset_type_id: i32, image_id: i32, make_primary: bool) -> Result<AssetImage, CrudRepositoryError> {
+        let mut tx = self.pool.begin().await.context("could not start transaction")?;
+
+        if make_primary == true {
+            sqlx::query!(
+                r#"
+                UPDATE asset_type_images
+                SET is_primary = FALSE
+                WHERE asset_type_id = $1 AND is_primary AND image_id <> $2
+                "#,
+                asset_type_id,
+                image_id,
+            )
+            .execute(&mut *tx)
+            .await
+            .context("could not unset primary asset type image")?;
+        }
+
+        let image = sqlx::query_as!(
+            AssetImage,
+            r#"
+            WITH inserted AS (
+                INSERT INTO asset_type_images (asset_type_id, image_id, position, is_primary)
+                SELECT $1, $2,
+                    COALESCE((SELECT MAX(position) + 1 FROM asset_type_images WHERE asset_type_id = $1), 0),
+                    $3 OR NOT EXISTS (SELECT 1 FROM asset_type_images WHERE asset_type_id = $1 AND is_primary)
+                ON CONFLICT (asset_type_id, image_id)
+                DO UPDATE SET is_primary = excluded.is_primary OR asset_type_images.is_primary
+                RETURNING id, image_id, position, is_primary, created_at
+            )
+            SELECT g.id, g.image_id, a.filename as "filename: Filename", a.url, a.variants as "variants: Json<Vec<ImageVariant>>",
+                g.position, g.is_primary, g.created_at
+            FROM inserted AS g
+            INNER JOIN image_attachments AS a
+                ON g.image_id = a.id
+            "#,
+            asset_type_id,
+            image_id,
+            make_primary,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        sync_asset_type_picture(&mut tx, asset_type_id).await?;
+
+        tx.commit().await.context("could not commit asset type image")?;
+
+        Ok(image)
+    }
+
+    async fn set_asset_type_primary_image(&self, asset_type_id: i32, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin().await.context("could not start transaction")?;
+
+        // cleared first so the one primary per gallery index never sees two at once
+        sqlx::query!(
+            r#"
+            UPDATE asset_type_images
+            SET is_primary = FALSE
+            WHERE asset_type_id = $1 AND is_primary AND id <> $2
+            "#,
+            asset_type_id,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not unset primary asset type image")?;
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE asset_type_images
+            SET is_primary = TRUE
+            WHERE id = $1 AND asset_type_id = $2
+            RETURNING id
+            "#,
+            id,
+            asset_type_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not set primary asset type image")?;
+
+        // nothing changed when the image is not part of this gallery
+        let Some(updated) = updated else {
+            tx.rollback().await.context("could not rollback transaction")?;
+            return Ok(None);
+        };
+
+        sync_asset_type_picture(&mut tx, asset_type_id).await?;
+
+        tx.commit().await.context("could not commit primary asset type image")?;
+
+        Ok(Some(updated.id))
+    }
+
+    async fn reorder_asset_type_images(&self, asset_type_id: i32, ids: &[i32]) -> Result<(), CrudRepositoryError> {
+        sqlx::query!(
+            r#"
+            UPDATE asset_type_images AS g
+            SET position = (o.position - 1)::INTEGER
+            FROM UNNEST($2::INTEGER[]) WITH ORDINALITY AS o(id, position)
+            WHERE g.id = o.id AND g.asset_type_id = $1
+            "#,
+            asset_type_id,
+            ids,
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not reorder asset type images")?;
+
+        Ok(())
+    }
+
+    async fn delete_asset_type_image(&self, asset_type_id: i32, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin().await.context("could not start transaction")?;
+
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM asset_type_images
+            WHERE id = $1 AND asset_type_id = $2
+            RETURNING id, is_primary
+            "#,
+            id,
+            asset_type_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not delete asset type image from database")?;
+
+        let Some(deleted) = deleted else {
+            tx.rollback().await.context("could not rollback transaction")?;
+            return Ok(None);
+        };
+
+        if deleted.is_primary == true {
+            sqlx::query!(
+                r#"
+                UPDATE asset_type_images
+                SET is_primary = TRUE
+                WHERE id = (
+                    SELECT id FROM asset_type_images
+                    WHERE asset_type_id = $1
+                    ORDER BY position, id
+                    LIMIT 1
+                )
+                "#,
+                asset_type_id,
+            )
+            .execute(&mut *tx)
+            .await
+            .context("could not promote next asset type image")?;
+
+            sync_asset_type_picture(&mut tx, asset_type_id).await?;
+        }
+
+        tx.commit().await.context("could not commit asset type image removal")?;
+
+        Ok(Some(deleted.id))
+    }
+
+    async fn get_asset_item_images(&self, asset_item_id: i32) -> Result<Vec<AssetImage>, CrudRepositoryError> {
+        let images = sqlx::query_as!(
+            AssetImage,
+            r#"
+            SELECT g.id, g.image_id, a.filename as "filename: Filename", a.url, a.variants as "variants: Json<Vec<ImageVariant>>",
+                g.position, g.is_primary, g.created_at
+            FROM asset_item_images AS g
+            INNER JOIN image_attachments AS a
+                ON g.image_id = a.id
+            WHERE g.asset_item_id = $1
+            ORDER BY g.position, g.id
+            "#,
+            asset_item_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset item images from database")?;
+
+        Ok(images)
+    }
+
+    async fn add_asset_item_image(&self, asset_item_id: i32, image_id: i32, make_primary: bool) -> Result<AssetImage, CrudRepositoryError> {
+        let mut tx = self.pool.begin().await.context("could not start transaction")?;
+
+        if make_primary == true {
+            sqlx::query!(
+                r#"
+                UPDATE asset_item_images
+                SET is_primary = FALSE
+                WHERE asset_item_id = $1 AND is_primary AND image_id <> $2
+                "#,
+                asset_item_id,
+                image_id,
+            )
+            .execute(&mut *tx)
+            .await
+            .context("could not unset primary asset item image")?;
+        }
+
+        let image = sqlx::query_as!(
+            AssetImage,
+            r#"
+            WITH inserted AS (
+                INSERT INTO asset_item_images (asset_item_id, image_id, position, is_primary)
+                SELECT $1, $2,
+                    COALESCE((SELECT MAX(position) + 1 FROM asset_item_images WHERE asset_item_id = $1), 0),
+                    $3 OR NOT EXISTS (SELECT 1 FROM asset_item_images WHERE asset_item_id = $1 AND is_primary)
+                ON CONFLICT (asset_item_id, image_id)
+                DO UPDATE SET is_primary = excluded.is_primary OR asset_item_images.is_primary
+                RETURNING id, image_id, position, is_primary, created_at
+            )
+            SELECT g.id, g.image_id, a.filename as "filename: Filename", a.url, a.variants as "variants: Json<Vec<ImageVariant>>",
+                g.position, g.is_primary, g.created_at
+            FROM inserted AS g
+            INNER JOIN image_attachments AS a
+                ON g.image_id = a.id
+            "#,
+            asset_item_id,
+            image_id,
+            make_primary,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        sync_asset_item_picture(&mut tx, asset_item_id).await?;
+
+        tx.commit().await.context("could not commit asset item image")?;
+
+        Ok(image)
+    }
+
+    async fn set_asset_item_primary_image(&self, asset_item_id: i32, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin().await.context("could not start transaction")?;
+
+        sqlx::query!(
+            r#"
+            UPDATE asset_item_images
+            SET is_primary = FALSE
+            WHERE asset_item_id = $1 AND is_primary AND id <> $2
+            "#,
+            asset_item_id,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not unset primary asset item image")?;
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE asset_item_images
+            SET is_primary = TRUE
+            WHERE id = $1 AND asset_item_id = $2
+            RETURNING id
+            "#,
+            id,
+            asset_item_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not set primary asset item image")?;
+
+        let Some(updated) = updated else {
+            tx.rollback().await.context("could not rollback transaction")?;
+            return Ok(None);
+        };
+
+        sync_asset_item_picture(&mut tx, asset_item_id).await?;
+
+        tx.commit().await.context("could not commit primary asset item image")?;
+
+        Ok(Some(updated.id))
+    }
+
+    async fn reorder_asset_item_images(&self, asset_item_id: i32, ids: &[i32]) -> Result<(), CrudRepositoryError> {
+        sqlx::query!(
+            r#"
+            UPDATE asset_item_images AS g
+            SET position = (o.position - 1)::INTEGER
+            FROM UNNEST($2::INTEGER[]) WITH ORDINALITY AS o(id, position)
+            WHERE g.id = o.id AND g.asset_item_id = $1
+            "#,
+            asset_item_id,
+            ids,
+        )
+        .execute(&self.pool)
+        .await
+        .context("could not reorder asset item images")?;
+
+        Ok(())
+    }
+
+    async fn delete_asset_item_image(&self, asset_item_id: i32, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        let mut tx = self.pool.begin().await.context("could not start transaction")?;
+
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM asset_item_images
+            WHERE id = $1 AND asset_item_id = $2
+            RETURNING id, is_primary
+            "#,
+            id,
+            asset_item_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not delete asset item image from database")?;
+
+        let Some(deleted) = deleted else {
+            tx.rollback().await.context("could not rollback transaction")?;
+            return Ok(None);
+        };
+
+        if deleted.is_primary == true {
+            sqlx::query!(
+                r#"
+                UPDATE asset_item_images
+                SET is_primary = TRUE
+                WHERE id = (
+                    SELECT id FROM asset_item_images
+                    WHERE asset_item_id = $1
+                    ORDER BY position, id
+                    LIMIT 1
+                )
+                "#,
+                asset_item_id,
+            )
+            .execute(&mut *tx)
+            .await
+            .context("could not promote next asset item image")?;
+
+            sync_asset_item_picture(&mut tx, asset_item_id).await?;
+        }
+
+        tx.commit().await.context("could not commit asset item image removal")?;
+
+        Ok(Some(deleted.id))
+    }
+
+    async fn get_depreciable_asset_items(&self) -> Result<Vec<DepreciableAssetItem>, CrudRepositoryError> {
+        let asset_items = sqlx::query_as!(
+            DepreciableAssetItem,
+            r#"
+            SELECT ai.school, ai.funding_source, at.cost,
+                COALESCE(at.useful_life_years, cp.effective_useful_life_years) as useful_life_years,
+                COALESCE(ai.purchase_date, ai.created_at::DATE) as "placed_in_service!",
+                COALESCE(cp.path_names, '{}') as "category_path!"
+            FROM asset_items AS ai
+            LEFT JOIN asset_types AS at
+                ON ai.asset_type_id = at.id
+            LEFT JOIN category_paths AS cp
+                ON at.category_id = cp.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset items from database")?;
+
+        Ok(asset_items)
+    }
+
+    async fn get_custom_fields(&self, asset_type_id: i32) -> Result<Vec<CustomField>, CrudRepositoryError> {
+        let custom_fields = sqlx::query_as!(
+            CustomField,
+            r#"
+            SELECT id as "id!", asset_type_id as "asset_type_id!", category_id, name as "name!", label as "label!", field_type as "field_type!",
+                options as "options!", required as "required!", position as "position!", created_at as "created_at!"
+            FROM asset_type_effective_fields
+            WHERE asset_type_id = $1
+            ORDER BY distance DESC, position, id
+            "#,
+            asset_type_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve custom fields from database")?;
+
+        Ok(custom_fields)
+    }
+
+    async fn get_all_custom_fields(&self) -> Result<Vec<CustomField>, CrudRepositoryError> {
+        let custom_fields = sqlx::query_as!(
+            CustomField,
+            r#"
+            SELECT id as "id!", asset_type_id as "asset_type_id!", category_id, name as "name!", label as "label!", field_type as "field_type!",
+                options as "options!", required as "required!", position as "position!", created_at as "created_at!"
+            FROM asset_type_effective_fields
+            ORDER BY asset_type_id, distance DESC, position, id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve custom fields from database")?;
+
+        Ok(custom_fields)
+    }
+
+    async fn add_custom_field(&self, new_custom_field: NewCustomField) -> Result<CustomField, CrudRepositoryError> {
+        // new fields go to the end of the form
+        let custom_field = sqlx::query_as!(
+            CustomField,
+            r#"
+            INSERT INTO asset_type_fields (asset_type_id, name, label, field_type, options, required, position)
+            VALUES($1, $2, $3, $4, $5, $6, (SELECT COALESCE(MAX(position) + 1, 0) FROM asset_type_fields WHERE asset_type_id = $1))
+            RETURNING id, asset_type_id, NULL::INTEGER as "category_id?", name, label, field_type, options, required, position, created_at
+            "#,
+            new_custom_field.asset_type_id,
+            new_custom_field.name,
+            new_custom_field.label,
+            new_custom_field.field_type,
+            &new_custom_field.options,
+            new_custom_field.required,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(custom_field)
+    }
+
+    async fn delete_custom_field(&self, asset_type_id: i32, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        // stored item values are left in place so re-adding the field brings them back
+        let returned_id = sqlx::query!(
+            r#"
+            DELETE FROM asset_type_fields WHERE id = $1 AND asset_type_id = $2
+            RETURNING id
+            "#,
+            id,
+            asset_type_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not delete custom field from database")?;
+
+        Ok(returned_id.map(|r| r.id))
+    }
+
+    async fn get_categories(&self) -> Result<Vec<Category>, CrudRepositoryError> {
+        // views do not carry NOT NULL, the columns come straight from categories
+        let categories = sqlx::query_as!(
+            Category,
+            r#"
+            SELECT id as "id!", parent_id, name as "name!", useful_life_years, effective_useful_life_years,
+                path_names as "path_names!", ancestor_ids as "ancestor_ids!", created_at as "created_at!"
+            FROM category_paths
+            ORDER BY path_names
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve categories from database")?;
+
+        Ok(categories)
+    }
+
+    async fn get_category_by_id(&self, id: i32) -> Result<Option<Category>, CrudRepositoryError> {
+        let category = sqlx::query_as!(
+            Category,
+            r#"
+            SELECT id as "id!", parent_id, name as "name!", useful_life_years, effective_useful_life_years,
+                path_names as "path_names!", ancestor_ids as "ancestor_ids!", created_at as "created_at!"
+            FROM category_paths
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not retrieve category from database")?;
+
+        Ok(category)
+    }
+
+    async fn add_category(&self, new_category: NewCategory) -> Result<i32, CrudRepositoryError> {
+        let returned_id = sqlx::query!(
+            r#"
+            INSERT INTO categories (parent_id, name, useful_life_years)
+            VALUES($1, $2, $3)
+            RETURNING id
+            "#,
+            new_category.parent_id,
+            new_category.name,
+            new_category.useful_life_years,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(returned_id.id)
+    }
+
+    async fn update_category(&self, id: i32, update_category: UpdateCategory) -> Result<Option<i32>, CrudRepositoryError> {
+        let returned_id = sqlx::query!(
+            r#"
+            UPDATE categories
+            SET parent_id = $2, name = $3, useful_life_years = $4
+            WHERE id = $1
+            RETURNING id
+            "#,
+            id,
+            update_category.parent_id,
+            update_category.name,
+            update_category.useful_life_years,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(returned_id.map(|r| r.id))
+    }
+
+    async fn delete_category(&self, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        // subcategories block the delete, asset types are left uncategorized
+        let returned_id = sqlx::query!(
+            r#"
+            DELETE FROM categories WHERE id = $1
+            RETURNING id
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(returned_id.map(|r| r.id))
+    }
+
+    async fn get_asset_type_count_for_category(&self, category_id: i32) -> Result<i64, CrudRepositoryError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!" FROM asset_types WHERE category_id = $1
+            "#,
+            category_id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("could not count asset types from database")?;
+
+        Ok(count)
+    }
+
+    async fn get_category_fields(&self, category_id: i32) -> Result<Vec<CategoryField>, CrudRepositoryError> {
+        let category_fields = sqlx::query_as!(
+            CategoryField,
+            r#"
+            SELECT id, category_id, name, label, field_type, options, required, position, created_at
+            FROM category_fields
+            WHERE category_id = $1
+            ORDER BY position, id
+            "#,
+            category_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve category fields from database")?;
+
+        Ok(category_fields)
+    }
+
+    async fn add_category_field(&self, new_category_field: NewCategoryField) -> Result<CategoryField, CrudRepositoryError> {
+        let category_field = sqlx::query_as!(
+            CategoryField,
+            r#"
+            INSERT INTO category_fields (category_id, name, label, field_type, options, required, position)
+            VALUES($1, $2, $3, $4, $5, $6, (SELECT COALESCE(MAX(position) + 1, 0) FROM category_fields WHERE category_id = $1))
+            RETURNING id, category_id, name, label, field_type, options, required, position, created_at
+            "#,
+            new_category_field.category_id,
+            new_category_field.name,
+            new_category_field.label,
+            new_category_field.field_type,
+            &new_category_field.options,
+            new_category_field.required,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { CrudRepositoryError::Duplicate }
+            else if is_foreign_key_violation(&e) == true { CrudRepositoryError::Reference }
+            else { CrudRepositoryError::Unknown(e.into()) }
+        })?;
+
+        Ok(category_field)
+    }
+
+    async fn delete_category_field(&self, category_id: i32, id: i32) -> Result<Option<i32>, CrudRepositoryError> {
+        let returned_id = sqlx::query!(
+            r#"
+            DELETE FROM category_fields WHERE id = $1 AND category_id = $2
+            RETURNING id
+            "#,
+            id,
+            category_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not delete category field from database")?;
+
+        Ok(returned_id.map(|r| r.id))
+    }
+}
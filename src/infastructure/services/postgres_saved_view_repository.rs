@@ -0,0 +1,149 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{domain::{crud::model::asset_items::AssetItemFilter, saved_views::{model::saved_views::{NewSavedView, SavedView}, saved_view_repository::{SavedViewRepository, SavedViewRepositoryError}}}, settings::DatabaseConfig, infastructure::services::postgres::{self, is_unique_constraint_violation, is_foreign_key_violation}};
+
+
+struct SavedViewRow {
+    id: i32,
+    user_id: Uuid,
+    owner_name: String,
+    name: String,
+    search: Option<String>,
+    sort: Option<String>,
+    sort_order: Option<String>,
+    category_id: Option<i32>,
+    school: Option<String>,
+    status: Option<String>,
+    columns: Vec<String>,
+    shared_role: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<SavedViewRow> for SavedView {
+    fn from(value: SavedViewRow) -> Self {
+        SavedView {
+            id: value.id,
+            user_id: value.user_id,
+            owner_name: value.owner_name,
+            name: value.name,
+            filter: AssetItemFilter {
+                search: value.search,
+                sort: value.sort,
+                order: value.sort_order,
+                category_id: value.category_id,
+                school: value.school,
+                status: value.status,
+            },
+            columns: value.columns.into(),
+            shared_role: value.shared_role,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostgresSavedViewRepository {
+    pool: PgPool,
+}
+
+impl PostgresSavedViewRepository {
+    pub fn new(config: &DatabaseConfig) -> anyhow::Result<Self> {
+        Ok(Self { pool: postgres::connect_lazy(config) })
+    }
+}
+
+impl SavedViewRepository for PostgresSavedViewRepository {
+    async fn get_saved_views(&self, user_id: Uuid, role: String) -> Result<Vec<SavedView>, SavedViewRepositoryError> {
+        let rows = sqlx::query_as!(
+            SavedViewRow,
+            r#"
+            SELECT sv.id, sv.user_id, u.given_name || ' ' || u.family_name AS "owner_name!", sv.name, sv.search, sv.sort, sv.sort_order,
+                sv.category_id, sv.school, sv.status, sv.columns, r.name AS "shared_role?", sv.created_at
+            FROM saved_views AS sv
+            INNER JOIN users AS u ON u.id = sv.user_id
+            LEFT JOIN roles AS r ON r.id = sv.shared_role_id
+            WHERE sv.user_id = $1 OR r.name = $2
+            ORDER BY sv.user_id = $1 DESC, lower(sv.name)
+            "#,
+            user_id,
+            role,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve saved views from database")?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_saved_view_by_id(&self, id: i32) -> Result<Option<SavedView>, SavedViewRepositoryError> {
+        let row = sqlx::query_as!(
+            SavedViewRow,
+            r#"
+            SELECT sv.id, sv.user_id, u.given_name || ' ' || u.family_name AS "owner_name!", sv.name, sv.search, sv.sort, sv.sort_order,
+                sv.category_id, sv.school, sv.status, sv.columns, r.name AS "shared_role?", sv.created_at
+            FROM saved_views AS sv
+            INNER JOIN users AS u ON u.id = sv.user_id
+            LEFT JOIN roles AS r ON r.id = sv.shared_role_id
+            WHERE sv.id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not retrieve saved view from database")?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn add_saved_view(&self, new_saved_view: NewSavedView) -> Result<SavedView, SavedViewRepositoryError> {
+        let columns = new_saved_view.columns.into_inner();
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO saved_views (user_id, name, search, sort, sort_order, category_id, school, status, columns, shared_role_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id
+            "#,
+            new_saved_view.user_id,
+            new_saved_view.name,
+            new_saved_view.filter.search,
+            new_saved_view.filter.sort,
+            new_saved_view.filter.order,
+            new_saved_view.filter.category_id,
+            new_saved_view.filter.school,
+            new_saved_view.filter.status,
+            &columns,
+            new_saved_view.shared_role_id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) == true { SavedViewRepositoryError::Conflict }
+            else if is_foreign_key_violation(&e) == true { SavedViewRepositoryError::Reference }
+            else { SavedViewRepositoryError::Unknown(e.into()) }
+        })?;
+
+        self.get_saved_view_by_id(id)
+            .await?
+            .context("saved view missing after insert")
+            .map_err(Into::into)
+    }
+
+    async fn delete_saved_view(&self, id: i32, user_id: Uuid) -> Result<Option<i32>, SavedViewRepositoryError> {
+        let returned_id = sqlx::query_scalar!(
+            r#"
+            DELETE FROM saved_views WHERE id = $1 AND user_id = $2
+            RETURNING id
+            "#,
+            id,
+            user_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not delete saved view from database")?;
+
+        Ok(returned_id)
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::Context;
+use sqlx::PgPool;
+
+use crate::{domain::search::{model::search::{DocumentMatch, LocationMatch, SearchHit}, search_repository::{SearchRepository, SearchRepositoryError}}, settings::DatabaseConfig, infastructure::services::postgres};
+
+
+#[derive(Debug, Clone)]
+pub struct PostgresSearchRepository {
+    pool: PgPool,
+}
+
+impl PostgresSearchRepository {
+    pub fn new(config: &DatabaseConfig) -> anyhow::Result<Self> {
+        Ok(Self { pool: postgres::connect_lazy(config) })
+    }
+}
+
+impl SearchRepository for PostgresSearchRepository {
+    async fn search_asset_types(&self, tsquery: String, text: String, limit: i64) -> Result<Vec<SearchHit>, SearchRepositoryError> {
+        let hits = sqlx::query_as!(
+            SearchHit,
+            r#"
+            SELECT at.brand || ' ' || at.model as "title!", at.description as subtitle, '/asset_types/' || at.id as "url!",
+                (ts_rank(at.full_search, to_tsquery('simple', $1)) + word_similarity($2, at.brand || ' ' || at.model))::REAL as "rank!"
+            FROM asset_types AS at
+            WHERE at.full_search @@ to_tsquery('simple', $1)
+                OR $2 <% (at.brand || ' ' || at.model)
+            ORDER BY 4 DESC, 1
+            LIMIT $3
+            "#,
+            tsquery,
+            text,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not search asset types in database")?;
+
+        Ok(hits)
+    }
+
+    async fn search_asset_items(&self, tsquery: String, text: String, limit: i64) -> Result<Vec<SearchHit>, SearchRepositoryError> {
+        let hits = sqlx::query_as!(
+            SearchHit,
+            r#"
+            SELECT coalesce(nullif(ai.name, ''), ai.asset_id, ai.serial_number, 'Item ' || ai.id) as "title!",
+                nullif(concat_ws(' - ', ai.asset_id, at.brand || ' ' || at.model, ai.school), '') as subtitle,
+                '/asset_items/' || ai.id as "url!",
+                (ts_rank(ai.full_search, to_tsquery('simple', $1))
+                    + word_similarity($2, coalesce(ai.name, '') || ' ' || coalesce(ai.asset_id, '') || ' ' || coalesce(ai.serial_number, '')))::REAL as "rank!"
+            FROM asset_items AS ai
+            LEFT JOIN asset_types AS at
+                ON ai.asset_type_id = at.id
+            WHERE ai.full_search @@ to_tsquery('simple', $1)
+                OR $2 <% (coalesce(ai.name, '') || ' ' || coalesce(ai.asset_id, '') || ' ' || coalesce(ai.serial_number, ''))
+            ORDER BY 4 DESC, 1
+            LIMIT $3
+            "#,
+            tsquery,
+            text,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not search asset items in database")?;
+
+        Ok(hits)
+    }
+
+    async fn search_users(&self, tsquery: String, text: String, limit: i64) -> Result<Vec<SearchHit>, SearchRepositoryError> {
+        let hits = sqlx::query_as!(
+            SearchHit,
+            r#"
+            SELECT u.given_name || ' ' || u.family_name as "title!", u.email as subtitle, '/settings/users/' || u.id || '/edit' as "url!",
+                (ts_rank(u.full_search, to_tsquery('simple', $1)) + word_similarity($2, u.given_name || ' ' || u.family_name || ' ' || u.email))::REAL as "rank!"
+            FROM users AS u
+            WHERE u.full_search @@ to_tsquery('simple', $1)
+                OR $2 <% (u.given_name || ' ' || u.family_name || ' ' || u.email)
+            ORDER BY 4 DESC, 1
+            LIMIT $3
+            "#,
+            tsquery,
+            text,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not search users in database")?;
+
+        Ok(hits)
+    }
+
+    async fn search_locations(&self, tsquery: String, text: String, limit: i64) -> Result<Vec<LocationMatch>, SearchRepositoryError> {
+        // a school matches on its own and with each of its rooms, there are few enough to not need an index
+        let locations = sqlx::query_as!(
+            LocationMatch,
+            r#"
+            WITH locations AS (
+                SELECT school, NULL::TEXT AS room, count(*) AS item_count, school AS label
+                FROM asset_items
+                WHERE school IS NOT NULL AND school <> ''
+                GROUP BY school
+                UNION ALL
+                SELECT school, room, count(*), school || ' ' || room
+                FROM asset_items
+                WHERE school IS NOT NULL AND school <> '' AND room IS NOT NULL AND room <> ''
+                GROUP BY school, room
+            )
+            SELECT school as "school!", room, item_count as "item_count!",
+                (ts_rank(to_tsvector('simple', label), to_tsquery('simple', $1)) + word_similarity($2, label))::REAL as "rank!"
+            FROM locations
+            WHERE to_tsvector('simple', label) @@ to_tsquery('simple', $1)
+                OR $2 <% label
+            ORDER BY 4 DESC, 1, 2 NULLS FIRST
+            LIMIT $3
+            "#,
+            tsquery,
+            text,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not search locations in database")?;
+
+        Ok(locations)
+    }
+
+    async fn search_documents(&self, query: String, limit: i64) -> Result<Vec<DocumentMatch>, SearchRepositoryError> {
+        // headlines are only built for the documents that made the limit, ts_headline re-parses the whole text
+        let matches = sqlx::query_as!(
+            DocumentMatch,
+            r#"
+            WITH query AS (
+                SELECT websearch_to_tsquery('english', $1) AS q
+            ),
+            matches AS (
+                SELECT d.id, d.filename, d.url, d.text_content, ts_rank(d.text_search, query.q) AS rank
+                FROM document_attachments AS d, query
+                WHERE d.text_search @@ query.q
+                ORDER BY rank DESC
+                LIMIT $2
+            )
+            SELECT m.id as "document_id!", m.filename as "filename!", m.url as "url!", 'asset_item' as "owner_kind!",
+                i.id as "owner_id!", coalesce(nullif(i.name, ''), i.asset_id, i.serial_number, 'Item ' || i.id) as "owner_name!",
+                ts_headline('english', coalesce(m.text_content, ''), query.q, 'StartSel=<mark>, StopSel=</mark>, MaxWords=30, MinWords=10') as "snippet!",
+                m.rank as "rank!"
+            FROM matches AS m
+            CROSS JOIN query
+            JOIN asset_item_documents AS aid
+                ON aid.document_id = m.id
+            JOIN asset_items AS i
+                ON i.id = aid.asset_item_id
+            UNION ALL
+            SELECT m.id, m.filename, m.url, 'asset_type', t.id, t.brand || ' ' || t.model,
+                ts_headline('english', coalesce(m.text_content, ''), query.q, 'StartSel=<mark>, StopSel=</mark>, MaxWords=30, MinWords=10'),
+                m.rank
+            FROM matches AS m
+            CROSS JOIN query
+            JOIN asset_type_documents AS atd
+                ON atd.document_id = m.id
+            JOIN asset_types AS t
+                ON t.id = atd.asset_type_id
+            ORDER BY 8 DESC, 1, 5
+            "#,
+            query,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not search documents in database")?;
+
+        Ok(matches)
+    }
+}
@@ -0,0 +1,385 @@
+use anyhow::Context;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{domain::{crud::model::{asset_items::AssetItem, history::HISTORY_ACTION_UPDATED}, transfers::{model::transfers::{NewTransfer, Transfer, TransferItem}, transfer_repository::{TransferRepository, TransferRepositoryError}}}, settings::DatabaseConfig, infastructure::services::postgres::{self, is_foreign_key_violation}};
+
+
+#[derive(Debug, Clone)]
+pub struct PostgresTransferRepository {
+    pool: PgPool,
+}
+
+impl PostgresTransferRepository {
+    pub fn new(config: &DatabaseConfig) -> anyhow::Result<Self> {
+        Ok(Self { pool: postgres::connect_lazy(config) })
+    }
+}
+
+impl TransferRepository for PostgresTransferRepository {
+    async fn add_transfer(&self, new_transfer: NewTransfer) -> Result<Transfer, TransferRepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO transfers (source_school, destination_school, destination_room, notes, created_by)
+            VALUES($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            new_transfer.source_school,
+            new_transfer.destination_school,
+            new_transfer.destination_room,
+            new_transfer.notes,
+            new_transfer.created_by,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { TransferRepositoryError::Reference }
+            else { TransferRepositoryError::Unknown(e.into()) }
+        })?;
+
+        let asset_item_ids: Vec<i32> = new_transfer.asset_items.iter().map(|i| i.id).collect();
+        let from_rooms: Vec<Option<String>> = new_transfer.asset_items.iter().map(|i| i.room.clone()).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO transfer_items (transfer_id, asset_item_id, from_room)
+            SELECT $1, * FROM UNNEST($2::INTEGER[], $3::TEXT[])
+            "#,
+            inserted.id,
+            &asset_item_ids,
+            &from_rooms as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) == true { TransferRepositoryError::Reference }
+            else { TransferRepositoryError::Unknown(e.into()) }
+        })?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        let transfer = self.get_transfer_by_id(inserted.id)
+            .await?
+            .context("inserted transfer not found")?;
+
+        Ok(transfer)
+    }
+
+    async fn get_transfers(&self) -> Result<Vec<Transfer>, TransferRepositoryError> {
+        let transfers = sqlx::query_as!(
+            Transfer,
+            r#"
+            SELECT t.id, t.source_school, t.destination_school, t.destination_room, t.notes, t.status,
+                t.created_by, cu.given_name || ' ' || cu.family_name as created_by_name,
+                t.received_by, ru.given_name || ' ' || ru.family_name as received_by_name,
+                t.created_at, t.received_at,
+                (SELECT COUNT(*) FROM transfer_items AS ti WHERE ti.transfer_id = t.id) as "item_count!",
+                (SELECT COUNT(ti.scanned_at) FROM transfer_items AS ti WHERE ti.transfer_id = t.id) as "scanned_count!"
+            FROM transfers AS t
+            LEFT JOIN users AS cu
+                ON t.created_by = cu.id
+            LEFT JOIN users AS ru
+                ON t.received_by = ru.id
+            ORDER BY t.created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve transfers from database")?;
+
+        Ok(transfers)
+    }
+
+    async fn get_transfer_by_id(&self, id: i32) -> Result<Option<Transfer>, TransferRepositoryError> {
+        let transfer = sqlx::query_as!(
+            Transfer,
+            r#"
+            SELECT t.id, t.source_school, t.destination_school, t.destination_room, t.notes, t.status,
+                t.created_by, cu.given_name || ' ' || cu.family_name as created_by_name,
+                t.received_by, ru.given_name || ' ' || ru.family_name as received_by_name,
+                t.created_at, t.received_at,
+                (SELECT COUNT(*) FROM transfer_items AS ti WHERE ti.transfer_id = t.id) as "item_count!",
+                (SELECT COUNT(ti.scanned_at) FROM transfer_items AS ti WHERE ti.transfer_id = t.id) as "scanned_count!"
+            FROM transfers AS t
+            LEFT JOIN users AS cu
+                ON t.created_by = cu.id
+            LEFT JOIN users AS ru
+                ON t.received_by = ru.id
+            WHERE t.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not retrieve transfer from database")?;
+
+        Ok(transfer)
+    }
+
+    async fn get_pending_transfers_for_school(&self, school: String) -> Result<Vec<Transfer>, TransferRepositoryError> {
+        let transfers = sqlx::query_as!(
+            Transfer,
+            r#"
+            SELECT t.id, t.source_school, t.destination_school, t.destination_room, t.notes, t.status,
+                t.created_by, cu.given_name || ' ' || cu.family_name as created_by_name,
+                t.received_by, ru.given_name || ' ' || ru.family_name as received_by_name,
+                t.created_at, t.received_at,
+                (SELECT COUNT(*) FROM transfer_items AS ti WHERE ti.transfer_id = t.id) as "item_count!",
+                (SELECT COUNT(ti.scanned_at) FROM transfer_items AS ti WHERE ti.transfer_id = t.id) as "scanned_count!"
+            FROM transfers AS t
+            LEFT JOIN users AS cu
+                ON t.created_by = cu.id
+            LEFT JOIN users AS ru
+                ON t.received_by = ru.id
+            WHERE t.status = 'pending'
+                AND (t.source_school = $1 OR t.destination_school = $1)
+            ORDER BY t.created_at
+            "#,
+            school
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve pending transfers from database")?;
+
+        Ok(transfers)
+    }
+
+    async fn get_transfer_items(&self, transfer_id: i32) -> Result<Vec<TransferItem>, TransferRepositoryError> {
+        let transfer_items = sqlx::query_as!(
+            TransferItem,
+            r#"
+            SELECT ti.transfer_id, ti.asset_item_id, ai.asset_id, ai.name, ai.serial_number, at.brand as "brand?", at.model as "model?",
+                ai.school, ai.room, ti.from_room, ti.scanned_at
+            FROM transfer_items AS ti
+            INNER JOIN asset_items AS ai
+                ON ti.asset_item_id = ai.id
+            LEFT JOIN asset_types AS at
+                ON ai.asset_type_id = at.id
+            WHERE ti.transfer_id = $1
+            ORDER BY ai.asset_id, ai.id
+            "#,
+            transfer_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve transfer items from database")?;
+
+        Ok(transfer_items)
+    }
+
+    async fn get_transfer_candidates(&self, asset_item_ids: &[i32]) -> Result<Vec<AssetItem>, TransferRepositoryError> {
+        let asset_items = sqlx::query_as!(
+            AssetItem,
+            r#"
+            SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+            FROM asset_item_details AS ai
+            WHERE ai.id = ANY($1)
+            ORDER BY ai.id
+            "#,
+            asset_item_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve asset items from database")?;
+
+        Ok(asset_items)
+    }
+
+    async fn get_school_asset_items(&self, school: String) -> Result<Vec<AssetItem>, TransferRepositoryError> {
+        // retired items stay where they are
+        let asset_items = sqlx::query_as!(
+            AssetItem,
+            r#"
+            SELECT ai.id as "id!", ai.asset_id, ai.name, ai.serial_number, ai.asset_type_id, ai.brand, ai.model, ai.description, ai.cost, ai.school, ai.room, ai.funding_source, ai.purchase_date, ai.status as "status!", ai.custom_fields as "custom_fields!", ai.picture, ai.created_at as "created_at!"
+            FROM asset_item_details AS ai
+            WHERE ai.school = $1
+                AND ai.status <> 'retired'
+                AND NOT EXISTS (
+                    SELECT 1 FROM transfer_items AS ti
+                    INNER JOIN transfers AS t
+                        ON ti.transfer_id = t.id
+                    WHERE ti.asset_item_id = ai.id AND t.status = 'pending'
+                )
+            ORDER BY ai.room, ai.asset_id, ai.id
+            "#,
+            school
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve school asset items from database")?;
+
+        Ok(asset_items)
+    }
+
+    async fn get_pending_asset_item_ids(&self, asset_item_ids: &[i32]) -> Result<Vec<i32>, TransferRepositoryError> {
+        let pending_ids = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT ti.asset_item_id
+            FROM transfer_items AS ti
+            INNER JOIN transfers AS t
+                ON ti.transfer_id = t.id
+            WHERE ti.asset_item_id = ANY($1) AND t.status = 'pending'
+            "#,
+            asset_item_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve pending transfer items from database")?;
+
+        Ok(pending_ids)
+    }
+
+    async fn get_schools(&self) -> Result<Vec<String>, TransferRepositoryError> {
+        let schools = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT school as "school!"
+            FROM asset_items
+            WHERE school IS NOT NULL AND school <> ''
+            ORDER BY 1
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("could not retrieve schools from database")?;
+
+        Ok(schools)
+    }
+
+    async fn scan_transfer_item(&self, transfer_id: i32, asset_item_id: i32) -> Result<bool, TransferRepositoryError> {
+        // scanning the same item twice keeps the first scan time
+        let scanned = sqlx::query!(
+            r#"
+            UPDATE transfer_items SET scanned_at = COALESCE(scanned_at, NOW())
+            WHERE transfer_id = $1 AND asset_item_id = $2
+            RETURNING id
+            "#,
+            transfer_id,
+            asset_item_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not scan transfer item")?;
+
+        Ok(scanned.is_some())
+    }
+
+    async fn receive_transfer(&self, transfer_id: i32, received_by: Uuid) -> Result<Option<Transfer>, TransferRepositoryError> {
+        let transfer = match self.get_transfer_by_id(transfer_id).await? {
+            Some(transfer) => transfer,
+            None => return Ok(None),
+        };
+
+        let mut tx = self.pool.begin()
+            .await
+            .context("could not start transaction")?;
+
+        let received = sqlx::query!(
+            r#"
+            UPDATE transfers SET status = 'received', received_by = $2, received_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING id
+            "#,
+            transfer_id,
+            received_by,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("could not receive transfer")?;
+
+        if received.is_none() {
+            return Ok(None);
+        }
+
+        // lock the rows so the recorded "from" values match what gets overwritten
+        let transfer_items = sqlx::query_as!(
+            TransferItem,
+            r#"
+            SELECT ti.transfer_id, ti.asset_item_id, ai.asset_id, ai.name, ai.serial_number, NULL::TEXT as brand, NULL::TEXT as model,
+                ai.school, ai.room, ti.from_room, ti.scanned_at
+            FROM transfer_items AS ti
+            INNER JOIN asset_items AS ai
+                ON ti.asset_item_id = ai.id
+            WHERE ti.transfer_id = $1
+            FOR UPDATE OF ai
+            "#,
+            transfer_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("could not retrieve transfer items from database")?;
+
+        sqlx::query!(
+            r#"
+            UPDATE asset_items AS ai
+            SET school = t.destination_school,
+                room = t.destination_room
+            FROM transfers AS t, transfer_items AS ti
+            WHERE t.id = $1
+                AND ti.transfer_id = t.id
+                AND ai.id = ti.asset_item_id
+            "#,
+            transfer_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not move transfer items")?;
+
+        let mut history_ids: Vec<i32> = Vec::new();
+        let mut history_changes: Vec<serde_json::Value> = Vec::new();
+        for transfer_item in &transfer_items {
+            let changes = transfer_item.received_changes(&transfer);
+            if changes.is_empty() {
+                continue;
+            }
+
+            history_ids.push(transfer_item.asset_item_id);
+            history_changes.push(serde_json::to_value(&changes).context("could not serialize asset item changes")?);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO asset_item_history (asset_item_id, action, changes, changed_by)
+            SELECT t.asset_item_id, $3, t.changes, $4
+            FROM UNNEST($1::INTEGER[], $2::JSONB[]) AS t(asset_item_id, changes)
+            "#,
+            &history_ids,
+            &history_changes,
+            HISTORY_ACTION_UPDATED,
+            received_by,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("could not record asset item history")?;
+
+        tx.commit()
+            .await
+            .context("could not commit transaction")?;
+
+        self.get_transfer_by_id(transfer_id).await
+    }
+
+    async fn cancel_transfer(&self, transfer_id: i32) -> Result<Option<Transfer>, TransferRepositoryError> {
+        let cancelled = sqlx::query!(
+            r#"
+            UPDATE transfers SET status = 'cancelled'
+            WHERE id = $1 AND status = 'pending'
+            RETURNING id
+            "#,
+            transfer_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("could not cancel transfer")?;
+
+        match cancelled {
+            Some(_) => self.get_transfer_by_id(transfer_id).await,
+            None => Ok(None),
+        }
+    }
+}
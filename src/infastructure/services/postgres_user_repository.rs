@@ -1,8 +1,8 @@
 use anyhow::Context;
-use sqlx::{postgres::{PgConnectOptions, PgPoolOptions, PgSslMode}, PgPool};
+use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::{domain::identityaccess::model::{roles::Role, user_repository::{UserRepository, UserRepositoryError}, users::{EmailAddress, NewUser, PasswordHash, Picture, UpdateUser, User, UserDescriptor}}, settings::DatabaseConfig};
+use crate::{domain::identityaccess::model::{roles::Role, user_repository::{UserRepository, UserRepositoryError}, users::{EmailAddress, NewUser, PasswordHash, Picture, UpdateUser, User, UserDescriptor}}, settings::DatabaseConfig, infastructure::services::postgres::{self, is_unique_constraint_violation}};
 
 
 #[derive(Debug, Clone)]
@@ -12,36 +12,11 @@ pub struct PostgresUserRepository {
 
 impl PostgresUserRepository {
     pub fn new(config: &DatabaseConfig) -> anyhow::Result<Self> {
-        let ssl_mode = if config.require_ssl == true { PgSslMode::Require } else { PgSslMode::Prefer };
-        let pg_connect_options = PgConnectOptions::new()
-            .host(&config.host)
-            .username(&config.username)
-            .password(&config.password)
-            .port(config.port)
-            .ssl_mode(ssl_mode)
-            .database(&config.database_name);
-
-        let pg_pool = PgPoolOptions::new().connect_lazy_with(pg_connect_options);
-
-        Ok(PostgresUserRepository { pool: pg_pool })
+        Ok(PostgresUserRepository { pool: postgres::connect_lazy(config) })
     }
 }
 
 
-const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "23505";
-
-fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
-    if let sqlx::Error::Database(db_err) = err {
-        if let Some(code) = db_err.code() {
-            if code == UNIQUE_CONSTRAINT_VIOLATION_CODE {
-                return true;
-            }
-        }
-    }
-
-    false
-}
-
 impl UserRepository for PostgresUserRepository {
     async fn add_user(&self, user: NewUser) -> Result<UserDescriptor, UserRepositoryError> {
         let user_descriptor = sqlx::query_as!(
@@ -0,0 +1,235 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Context;
+use futures::StreamExt;
+use s3::{creds::Credentials, error::S3Error, Bucket, Region};
+
+use crate::{domain::filesystem::{models::{FilePayload, NewDocumentAttachment, NewImageAttachment}, persistence_service::{prepare_document_file, prepare_image_files, FileRequest, PersistenceError, PersistenceService, StorageFolder, StoredFile}}, settings::S3Config};
+
+
+/// Stores attachments in an S3 compatible bucket (AWS, MinIO, ...) under <folder>/<name> keys,
+/// so every app instance sees the same files
+#[derive(Debug, Clone)]
+pub struct S3PersistenceService {
+    bucket: Box<Bucket>,
+    route_path: String,
+    presign_expiry_secs: Option<u32>,
+}
+
+impl S3PersistenceService {
+    pub fn new(route_path: &str, config: &S3Config) -> anyhow::Result<Self> {
+        let region = Region::Custom {
+            region: config.region.clone(),
+            endpoint: config.endpoint.clone(),
+        };
+        let credentials = Credentials::new(Some(&config.access_key), Some(&config.secret_key), None, None, None)
+            .context("invalid s3 credentials")?;
+
+        let mut bucket = Bucket::new(&config.bucket, region, credentials).context("could not create s3 bucket client")?;
+        if config.path_style == true {
+            bucket = bucket.with_path_style();
+        }
+
+        Ok(Self {
+            bucket,
+            route_path: route_path.to_string(),
+            presign_expiry_secs: config.presign_expiry_secs,
+        })
+    }
+
+    /// Copies a file from local storage, used when moving an existing install over. Returns false when the key already exists.
+    pub async fn upload_local_file(&self, folder: StorageFolder, name: &str, path: &Path, content_type: &str) -> Result<bool, PersistenceError> {
+        let key = object_key(folder, name);
+        if self.exists(&key).await? {
+            return Ok(false);
+        }
+
+        let mut file = tokio::fs::File::open(path).await?;
+        self.bucket.put_object_stream_with_content_type(&mut file, &key, content_type)
+            .await
+            .context(format!("could not upload {} to s3", key))?;
+
+        Ok(true)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, PersistenceError> {
+        match self.bucket.head_object(key).await {
+            Ok(_) => Ok(true),
+            Err(S3Error::HttpFailWithBody(404, _)) => Ok(false),
+            Err(e) => Err(anyhow::Error::new(e).context(format!("could not check {} in s3", key)).into()),
+        }
+    }
+
+    /// Fetches a byte range from the bucket, None when the range can't be satisfied and the whole file should go out
+    async fn retrieve_range(&self, key: &str, start: u64, end: Option<u64>) -> Result<Option<StoredFile>, PersistenceError> {
+        // rust-s3 asserts start < end, a single byte range is fetched as two and cut down
+        let (end, single_byte) = match end {
+            Some(end) if end < start => return Ok(None),
+            Some(end) if end == start => (Some(end + 1), true),
+            end => (end, false),
+        };
+
+        let response = match self.bucket.get_object_range(key, start, end).await {
+            Ok(response) => response,
+            Err(S3Error::HttpFailWithBody(404, _)) => return Err(PersistenceError::NotFound),
+            Err(S3Error::HttpFailWithBody(416, _)) => return Ok(None),
+            Err(e) => return Err(anyhow::Error::new(e).context(format!("could not read {} from s3", key)).into()),
+        };
+        if response.status_code() != 206 {
+            return Ok(None);
+        }
+
+        let total = response.headers()
+            .get("content-range")
+            .and_then(|range| range.rsplit_once('/'))
+            .map(|(_, total)| total.to_string())
+            .context(format!("s3 answered a range of {} without content-range", key))?;
+
+        let mut data = response.to_vec();
+        if single_byte == true {
+            data.truncate(1);
+        }
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        let content_range = format!("bytes {}-{}/{}", start, start + data.len() as u64 - 1, total);
+        Ok(Some(StoredFile::Partial { data, content_range }))
+    }
+}
+
+fn object_key(folder: StorageFolder, name: &str) -> String {
+    format!("{}/{}", folder, name)
+}
+
+impl PersistenceService for S3PersistenceService {
+    async fn persist_image_file(&self, payload: FilePayload) -> Result<NewImageAttachment, PersistenceError> {
+        let (attachment, objects) = prepare_image_files(payload, &self.route_path).await?;
+
+        for object in objects {
+            let key = object_key(StorageFolder::Images, &object.name);
+            self.bucket.put_object_with_content_type(&key, &object.data, &object.content_type)
+                .await
+                .context(format!("could not upload {} to s3", key))?;
+        }
+
+        Ok(attachment)
+    }
+
+    async fn persist_document_file(&self, payload: FilePayload) -> Result<NewDocumentAttachment, PersistenceError> {
+        let (attachment, name, temp_file) = prepare_document_file(payload, &self.route_path).await?;
+
+        let key = object_key(StorageFolder::Documents, &name);
+        let content_type: &str = attachment.content_type.as_ref();
+        let mut file = tokio::fs::File::open(temp_file.path()).await?;
+        self.bucket.put_object_stream_with_content_type(&mut file, &key, content_type)
+            .await
+            .context(format!("could not upload {} to s3", key))?;
+
+        Ok(attachment)
+    }
+
+    async fn retrieve_file(&self, folder: StorageFolder, name: &str, request: &FileRequest) -> Result<StoredFile, PersistenceError> {
+        let key = object_key(folder, name);
+
+        if let Some(expiry_secs) = self.presign_expiry_secs {
+            // the bucket answers the redirect, so it has to send the verified type and disposition instead of whatever was stored
+            let overrides = HashMap::from([
+                ("response-content-type".to_string(), request.content_type.clone()),
+                ("response-content-disposition".to_string(), request.content_disposition.clone()),
+            ]);
+            let url = self.bucket.presign_get(&key, expiry_secs, Some(overrides))
+                .await
+                .context(format!("could not presign {}", key))?;
+
+            return Ok(StoredFile::Redirect(url));
+        }
+
+        if let Some((start, end)) = request.range {
+            if let Some(partial) = self.retrieve_range(&key, start, end).await? {
+                return Ok(partial);
+            }
+        }
+
+        let response = match self.bucket.get_object_stream(&key).await {
+            Ok(response) => response,
+            Err(S3Error::HttpFailWithBody(404, _)) => return Err(PersistenceError::NotFound),
+            Err(e) => return Err(anyhow::Error::new(e).context(format!("could not read {} from s3", key)).into()),
+        };
+
+        let stream = response.bytes
+            .map(|chunk| chunk
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| PersistenceError::Unexpected(e.into())))
+            .boxed();
+
+        Ok(StoredFile::Stream(stream))
+    }
+
+    async fn read_file(&self, folder: StorageFolder, name: &str) -> Result<Vec<u8>, PersistenceError> {
+        let key = object_key(folder, name);
+
+        match self.bucket.get_object(&key).await {
+            Ok(response) => Ok(response.to_vec()),
+            Err(S3Error::HttpFailWithBody(404, _)) => Err(PersistenceError::NotFound),
+            Err(e) => Err(anyhow::Error::new(e).context(format!("could not read {} from s3", key)).into()),
+        }
+    }
+
+    async fn delete_file(&self, folder: StorageFolder, name: &str) -> Result<(), PersistenceError> {
+        // s3 deletes are idempotent, a missing key still answers 204
+        let key = object_key(folder, name);
+        self.bucket.delete_object(&key)
+            .await
+            .context(format!("could not delete {} from s3", key))?;
+
+        Ok(())
+    }
+
+    async fn list_files(&self, folder: StorageFolder) -> Result<Vec<String>, PersistenceError> {
+        let prefix = format!("{}/", folder);
+        let pages = self.bucket.list(prefix.clone(), None)
+            .await
+            .context(format!("could not list {} in s3", prefix))?;
+
+        let names = pages.into_iter()
+            .flat_map(|page| page.contents)
+            .filter_map(|object| object.key.strip_prefix(&prefix).map(str::to_string))
+            .filter(|name| !name.is_empty() && !name.contains('/'))
+            .collect();
+
+        Ok(names)
+    }
+
+    async fn hash_stored_file(&self, folder: StorageFolder, name: &str) -> Result<String, PersistenceError> {
+        let key = object_key(folder, name);
+
+        let mut response = match self.bucket.get_object_stream(&key).await {
+            Ok(response) => response,
+            Err(S3Error::HttpFailWithBody(404, _)) => return Err(PersistenceError::NotFound),
+            Err(e) => return Err(anyhow::Error::new(e).context(format!("could not read {} from s3", key)).into()),
+        };
+
+        let mut hasher = blake3::Hasher::new();
+        while let Some(chunk) = response.bytes.next().await {
+            let chunk = chunk.context(format!("could not read {} from s3", key))?;
+            hasher.update(&chunk);
+        }
+
+        Ok(hasher.finalize().to_string())
+    }
+
+    async fn quarantine_file(&self, folder: StorageFolder, name: &str) -> Result<(), PersistenceError> {
+        let key = object_key(folder, name);
+        let quarantine_key = format!("quarantine/{}", key);
+
+        self.bucket.copy_object_internal(&key, &quarantine_key)
+            .await
+            .context(format!("could not copy {} to quarantine", key))?;
+        self.bucket.delete_object(&key)
+            .await
+            .context(format!("could not delete {} from s3", key))?;
+
+        Ok(())
+    }
+}
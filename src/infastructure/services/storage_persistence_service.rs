@@ -0,0 +1,89 @@
+use anyhow::Context;
+
+use crate::{domain::filesystem::{models::{FilePayload, NewDocumentAttachment, NewImageAttachment}, persistence_service::{FileRequest, PersistenceError, PersistenceService, StorageFolder, StoredFile}}, settings::{Settings, StorageKind}};
+
+use super::{local_persistence_service::LocalPersistenceService, s3_persistence_service::S3PersistenceService};
+
+
+/// The storage backend picked by `[storage] kind` in the settings
+#[derive(Debug, Clone)]
+pub enum StoragePersistenceService {
+    Local(LocalPersistenceService),
+    S3(S3PersistenceService),
+}
+
+impl StoragePersistenceService {
+    pub fn new(config: &Settings) -> anyhow::Result<Self> {
+        let persistence = match config.storage.kind {
+            StorageKind::Local => {
+                let local = LocalPersistenceService::new(&config.local_storage.route_path, &config.local_storage.serve_path)?;
+                Self::Local(local)
+            },
+            StorageKind::S3 => {
+                let s3_config = config.s3.as_ref().context("storage kind is s3 but the [s3] settings are missing")?;
+                let s3 = S3PersistenceService::new(&config.local_storage.route_path, s3_config)?;
+                Self::S3(s3)
+            },
+        };
+
+        Ok(persistence)
+    }
+}
+
+impl PersistenceService for StoragePersistenceService {
+    async fn persist_image_file(&self, payload: FilePayload) -> Result<NewImageAttachment, PersistenceError> {
+        match self {
+            Self::Local(local) => local.persist_image_file(payload).await,
+            Self::S3(s3) => s3.persist_image_file(payload).await,
+        }
+    }
+
+    async fn persist_document_file(&self, payload: FilePayload) -> Result<NewDocumentAttachment, PersistenceError> {
+        match self {
+            Self::Local(local) => local.persist_document_file(payload).await,
+            Self::S3(s3) => s3.persist_document_file(payload).await,
+        }
+    }
+
+    async fn retrieve_file(&self, folder: StorageFolder, name: &str, request: &FileRequest) -> Result<StoredFile, PersistenceError> {
+        match self {
+            Self::Local(local) => local.retrieve_file(folder, name, request).await,
+            Self::S3(s3) => s3.retrieve_file(folder, name, request).await,
+        }
+    }
+
+    async fn read_file(&self, folder: StorageFolder, name: &str) -> Result<Vec<u8>, PersistenceError> {
+        match self {
+            Self::Local(local) => local.read_file(folder, name).await,
+            Self::S3(s3) => s3.read_file(folder, name).await,
+        }
+    }
+
+    async fn delete_file(&self, folder: StorageFolder, name: &str) -> Result<(), PersistenceError> {
+        match self {
+            Self::Local(local) => local.delete_file(folder, name).await,
+            Self::S3(s3) => s3.delete_file(folder, name).await,
+        }
+    }
+
+    async fn list_files(&self, folder: StorageFolder) -> Result<Vec<String>, PersistenceError> {
+        match self {
+            Self::Local(local) => local.list_files(folder).await,
+            Self::S3(s3) => s3.list_files(folder).await,
+        }
+    }
+
+    async fn hash_stored_file(&self, folder: StorageFolder, name: &str) -> Result<String, PersistenceError> {
+        match self {
+            Self::Local(local) => local.hash_stored_file(folder, name).await,
+            Self::S3(s3) => s3.hash_stored_file(folder, name).await,
+        }
+    }
+
+    async fn quarantine_file(&self, folder: StorageFolder, name: &str) -> Result<(), PersistenceError> {
+        match self {
+            Self::Local(local) => local.quarantine_file(folder, name).await,
+            Self::S3(s3) => s3.quarantine_file(folder, name).await,
+        }
+    }
+}
@@ -1,14 +1,18 @@
+// house style the lints disagree with: explicit `== true`, `field: field` initializers, upper case file extensions,
+// handlers/<name>/<name>.rs modules, routers bound before they are returned and template constructors taking every field
+#![allow(clippy::bool_comparison, clippy::redundant_field_names, clippy::upper_case_acronyms, clippy::module_inception, clippy::let_and_return, clippy::too_many_arguments)]
+
 mod telemetry;
 
 mod domain;
 mod settings;
 mod infastructure;
 mod application;
+mod cli;
 
 use anyhow::{Context, Ok};
-use application::{content::content_application_service::ContentApplicationService, crud::crud_application_service::CrudApplicationService, http::server::AppHttpServer, identityaccess::identity_application_service::IdentityApplicationService, state::AppState};
-use domain::filesystem::persistence_service;
-use infastructure::services::{google_oauth_service::GoogleOauthService, local_persistence_service::LocalPersistenceService, postgres_attachment_repository::PostgresAttachmentRepository, postgres_crud_repository::PostgresCrudRepository, postgres_user_repository::PostgresUserRepository};
+use application::{audits::audit_application_service::AuditApplicationService, content::{attachment_gc, content_application_service::ContentApplicationService, document_text}, crud::crud_application_service::CrudApplicationService, http::server::AppHttpServer, identityaccess::identity_application_service::IdentityApplicationService, labels::label_application_service::LabelApplicationService, saved_views::saved_view_application_service::SavedViewApplicationService, search::search_application_service::SearchApplicationService, state::AppState, transfers::transfer_application_service::TransferApplicationService};
+use infastructure::services::{google_oauth_service::GoogleOauthService, postgres_attachment_repository::PostgresAttachmentRepository, postgres_audit_repository::PostgresAuditRepository, postgres_crud_repository::PostgresCrudRepository, postgres_saved_view_repository::PostgresSavedViewRepository, postgres_search_repository::PostgresSearchRepository, postgres_transfer_repository::PostgresTransferRepository, postgres_user_repository::PostgresUserRepository, storage_persistence_service::StoragePersistenceService};
 use settings::Settings;
 use telemetry::init_console_subscriber;
 use tracing::Level;
@@ -19,6 +23,12 @@ async fn main() -> anyhow::Result<()> {
     //load configuration data
     let config = Settings::new()?;
 
+    // maintenance commands run instead of the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return cli::run(&config, &args).await;
+    }
+
     // init tracing
     let _log_gaurd = init_console_subscriber(Level::DEBUG)?;
 
@@ -27,15 +37,32 @@ async fn main() -> anyhow::Result<()> {
     let user_repo = PostgresUserRepository::new(&config.database).context("failed to init user repository")?;
     let attachment_repo = PostgresAttachmentRepository::new(&config.database).context("failed to init attachment repository")?;
     let crud_repo = PostgresCrudRepository::new(&config.database).context("failed to init attachment repository")?;
-    let persistence = LocalPersistenceService::new(&config.local_storage.route_path, &config.local_storage.serve_path).context("failed to init persistence repository")?;
+    let audit_repo = PostgresAuditRepository::new(&config.database).context("failed to init audit repository")?;
+    let transfer_repo = PostgresTransferRepository::new(&config.database).context("failed to init transfer repository")?;
+    let search_repo = PostgresSearchRepository::new(&config.database).context("failed to init search repository")?;
+    let saved_view_repo = PostgresSavedViewRepository::new(&config.database).context("failed to init saved view repository")?;
+    let persistence = StoragePersistenceService::new(&config).context("failed to init persistence repository")?;
 
-    let content_service = ContentApplicationService::new(attachment_repo, persistence);
+    let content_service = ContentApplicationService::new(attachment_repo, persistence, &config.uploads).context("failed to init content service")?;
     let identity_serivce = IdentityApplicationService::new(user_repo, google_oauth);
+    let audit_service = AuditApplicationService::new(audit_repo, crud_repo.clone());
+    let transfer_service = TransferApplicationService::new(transfer_repo, crud_repo.clone());
     let crud_service = CrudApplicationService::new(crud_repo);
+    let label_service = LabelApplicationService::new(&config.application.base_url);
+    let search_service = SearchApplicationService::new(search_repo);
+    let saved_view_service = SavedViewApplicationService::new(saved_view_repo);
+
+    if config.attachment_gc.enabled {
+        tokio::spawn(attachment_gc::run_scheduled(content_service.clone(), config.attachment_gc.clone()));
+    }
+
+    if config.text_extraction.enabled {
+        tokio::spawn(document_text::run_scheduled(content_service.clone(), config.text_extraction.clone()));
+    }
 
     //init server
-    let app_state = AppState::new(identity_serivce, crud_service, content_service);
-    let app_server = AppHttpServer::new(&config.application, app_state).await?;
+    let app_state = AppState::new(identity_serivce, crud_service, content_service, label_service, audit_service, transfer_service, search_service, saved_view_service);
+    let app_server = AppHttpServer::new(&config.application, &config.uploads, app_state).await?;
 
     // run tasks
     app_server.run_until_stopped().await?;
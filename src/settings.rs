@@ -1,6 +1,6 @@
 use config::{Config, ConfigError, Environment, File};
 use serde::Deserialize;
-use std::env;
+use std::{env, num::NonZeroU64};
 
 
 #[derive(Debug, Deserialize)]
@@ -20,6 +20,7 @@ pub struct ApplicationConfig {
     pub port: u16,
     pub host: String,
     pub session_secret: String,
+    pub base_url: String,
 }
 
 impl ApplicationConfig {
@@ -45,6 +46,92 @@ pub struct LocalStorageConfig {
     pub route_path: String,
 }
 
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    Local,
+    S3,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(unused)]
+pub struct StorageConfig {
+    pub kind: StorageKind,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(unused)]
+pub struct S3Config {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// MinIO and most self hosted stores need bucket names in the path rather than the host
+    pub path_style: bool,
+    /// when set, downloads redirect to presigned urls valid this long instead of streaming through the app
+    pub presign_expiry_secs: Option<u32>,
+}
+
+/// Room for the other fields of an upload form on top of the file itself
+const FORM_OVERHEAD_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct UploadLimitConfig {
+    /// largest accepted file in bytes
+    pub max_size: usize,
+    /// mime types as sent by the browser, each has to be one the app knows how to store
+    pub allowed_types: Vec<String>,
+}
+
+impl UploadLimitConfig {
+    /// Limit for the whole request body, so oversized uploads are cut off while they stream in
+    pub fn body_limit(&self) -> usize {
+        self.max_size + FORM_OVERHEAD_BYTES
+    }
+
+    pub fn allows(&self, content_type: &str) -> bool {
+        self.allowed_types.iter().any(|allowed| allowed.eq_ignore_ascii_case(content_type))
+    }
+
+    pub fn max_size_display(&self) -> String {
+        let mib = self.max_size as f64 / (1024.0 * 1024.0);
+        format!("{} MiB", (mib * 10.0).round() / 10.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct UploadsConfig {
+    /// pictures and gallery images
+    pub images: UploadLimitConfig,
+    /// documents attached to asset types and items
+    pub documents: UploadLimitConfig,
+    /// csv imports
+    pub imports: UploadLimitConfig,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct AttachmentGcConfig {
+    /// run the collection in the background, the cli command works either way
+    pub enabled: bool,
+    pub interval_mins: NonZeroU64,
+    /// how long an attachment stays unreferenced before its row and files are deleted
+    pub grace_period_hours: i32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct TextExtractionConfig {
+    /// index document text in the background, the cli command works either way
+    pub enabled: bool,
+    /// uploads wake the job straight away, the interval picks up anything they missed
+    pub interval_mins: NonZeroU64,
+    pub batch_size: i64,
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(unused)]
 pub struct Settings {
@@ -52,6 +139,11 @@ pub struct Settings {
     pub application: ApplicationConfig,
     pub google: GoogleConfig,
     pub local_storage: LocalStorageConfig,
+    pub storage: StorageConfig,
+    pub s3: Option<S3Config>,
+    pub attachment_gc: AttachmentGcConfig,
+    pub text_extraction: TextExtractionConfig,
+    pub uploads: UploadsConfig,
 }
 
 impl Settings {